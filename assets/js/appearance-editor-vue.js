@@ -44,6 +44,10 @@ export default {
       </b-col>
     </b-row>
 
+    <b-alert variant="danger" :show="!!saveError" dismissible @dismissed="saveError = null">
+      Couldn't save the appearance: {{ saveError }}
+    </b-alert>
+
     <div>
       <b-form inline>
         <b-button variant="outline-primary" @click="spawnCarForViewing(0)" class="mr-1">
@@ -160,6 +164,7 @@ export default {
         { id: "goal-explosion", name: "Goal explosion" },
       ],
       selectedShowcaseType: "boost",
+      saveError: null,
     };
   },
 
@@ -201,9 +206,15 @@ export default {
     },
     saveAppearance: function () {
       console.log(this.config);
-      invoke("save_looks", { path: this.path, config: this.config });
-      this.$bvModal.hide("appearance-editor-dialog");
-      this.config = {}; // prevents a memory leak from not un-loading configs when the dialog is closed
+      invoke("save_looks", { path: this.path, config: this.config })
+        .then(() => {
+          this.saveError = null;
+          this.$bvModal.hide("appearance-editor-dialog");
+          this.config = {}; // prevents a memory leak from not un-loading configs when the dialog is closed
+        })
+        .catch((error) => {
+          this.saveError = error;
+        });
     },
     spawnCarForViewing: function (team) {
       invoke("spawn_car_for_viewing", {
@@ -216,6 +227,7 @@ export default {
     loadLooks: async function (path) {
       invoke("get_looks", { path }).then((config) => {
         this.config = config;
+        this.saveError = null;
       });
     },
     randomizeTeamLoadout: function (team) {
@@ -324,6 +324,14 @@ export default {
         <span class="bot-info-key">Tags:</span>
         <b-badge v-for="tag in activeBot.info.tags" class="ml-1">{{tag}}</b-badge>
       </p>
+      <p v-if="activeBot.maximum_tick_rate_preference">
+        <span class="bot-info-key">Maximum Tick Rate:</span> {{activeBot.maximum_tick_rate_preference}}
+      </p>
+      <p v-if="activeBot.supports_early_start"><span class="bot-info-key">Supports Early Start:</span> Yes</p>
+      <p v-if="activeBot.bot_parameters && Object.keys(activeBot.bot_parameters).length > 0">
+        <span class="bot-info-key">Bot Parameters:</span>
+        <b-badge v-for="(value, key) in activeBot.bot_parameters" class="ml-1">{{key}} = {{value}}</b-badge>
+      </p>
       <p class="bot-file-path">{{activeBot.path}}</p>
 
       <div>
@@ -582,6 +590,17 @@ export default {
           "Error starting the match! See the console for more details.";
         this.showSnackbar = true;
       }),
+      matchHandlerExited: listen("match-handler-exited", (event) => {
+        this.matchStarting = false;
+        this.snackbarContent = event.payload.restarting
+          ? "The match handler stopped unexpectedly, restarting it..."
+          : "The match handler stopped unexpectedly! See the console for more details.";
+        this.showSnackbar = true;
+      }),
+      matchHandlerRestarted: listen("match-handler-restarted", () => {
+        this.snackbarContent = "The match handler was restarted";
+        this.showSnackbar = true;
+      }),
       setAppearanceFile: listen("set-appearance-file", (event) => {
         this.$store.commit("setActiveBot", null);
         const path = event.payload;
@@ -599,6 +618,9 @@ export default {
           runnable_type: bot.runnable_type,
           skill: bot.skill ? bot.skill : 1,
           path: bot.path,
+          supports_early_start: bot.supports_early_start || false,
+          maximum_tick_rate_preference: bot.maximum_tick_rate_preference,
+          bot_parameters: bot.bot_parameters || {},
         };
       });
       const orangeBots = this.orangeTeam.map((bot) => {
@@ -608,6 +630,9 @@ export default {
           runnable_type: bot.runnable_type,
           skill: bot.skill ? bot.skill : 1,
           path: bot.path,
+          supports_early_start: bot.supports_early_start || false,
+          maximum_tick_rate_preference: bot.maximum_tick_rate_preference,
+          bot_parameters: bot.bot_parameters || {},
         };
       });
 
@@ -769,6 +794,9 @@ export default {
           runnable_type: bot.runnable_type,
           skill: bot.skill ? bot.skill : 1,
           path: bot.path,
+          supports_early_start: bot.supports_early_start || false,
+          maximum_tick_rate_preference: bot.maximum_tick_rate_preference,
+          bot_parameters: bot.bot_parameters || {},
         };
       });
       const orangeBots = this.orangeTeam.map((bot) => {
@@ -778,9 +806,22 @@ export default {
           runnable_type: bot.runnable_type,
           skill: bot.skill ? bot.skill : 1,
           path: bot.path,
+          supports_early_start: bot.supports_early_start || false,
+          maximum_tick_rate_preference: bot.maximum_tick_rate_preference,
+          bot_parameters: bot.bot_parameters || {},
         };
       });
 
+      invoke("check_tick_rate_preferences", {
+        botList: blueBots.concat(orangeBots),
+      }).then((conflict) => {
+        if (conflict) {
+          const preferences = conflict.preferences.map(([name, rate]) => `${name} (${rate})`).join(", ");
+          this.snackbarContent = `These bots want different maximum tick rates: ${preferences}`;
+          this.showSnackbar = true;
+        }
+      });
+
       invoke("start_match", {
         botList: blueBots.concat(orangeBots),
         matchSettings: this.matchSettings,
@@ -133,13 +133,19 @@ export default {
       },
       cars: [],
       hasPacketHistory: false,
-      gtp: listen("gtp", (event) => this.gameTickPacketReceived(event.payload)),
+      gtp: listen("game-tick-packet", (event) =>
+        this.gameTickPacketReceived(event.payload)
+      ),
     };
   },
+  beforeDestroy() {
+    invoke("unsubscribe_game_tick_packets").catch(console.error);
+  },
   methods: {
     startWatching: function () {
-      invoke("fetch_game_tick_packet_json");
       this.previousSecondsElapsed = 0;
+      // 20 packets per second is plenty for the sandbox, and avoids hurting the CPU
+      invoke("subscribe_game_tick_packets", { rate: 20 }).catch(console.error);
     },
     gameTickPacketReceived: function (result) {
       if (
@@ -201,11 +207,6 @@ export default {
         packetHistory.push(result);
       }
       this.hasPacketHistory = true;
-
-      if (this.watching) {
-        // Delay 50 milliseconds to avoid hurting the CPU
-        setTimeout(() => invoke("fetch_game_tick_packet_json"), 50);
-      }
     },
     toCanvasVec: function (packetVec) {
       // Height without goals: 512 px
@@ -323,6 +324,8 @@ export default {
       handler: function (newVal) {
         if (newVal) {
           this.startWatching();
+        } else {
+          invoke("unsubscribe_game_tick_packets").catch(console.error);
         }
       },
     },
@@ -0,0 +1,500 @@
+use crate::{
+    automation::{Automation, AutomationError, AutomationSlot},
+    bot_management::cfg_helper::{self, change_key_in_cfg},
+    ccprintln,
+    commands::{kill_bots_helper, start_match_helper},
+    content::{read_json, write_json, ContentError, ContentFolder},
+    get_command, impl_serialize_from_display, match_history,
+    recordings::unix_time,
+    reporter::Reporter,
+    rlbot::parsing::bot_config_bundle::{BotConfigBundle, RLBotCfgParseError, BOT_CONFIG_MODULE_HEADER, NAME_KEY},
+    settings::{MiniMatchConfig, Team, TeamBotBundle},
+    AB_TEST_RUNNER,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, create_dir_all},
+    io,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+use tauri::async_runtime::block_on as tauri_block_on;
+use thiserror::Error;
+
+pub const AB_TESTS: ContentFolder = ContentFolder {
+    folder: "ab_tests",
+    extension: "json",
+    kind: "A/B test",
+};
+const WORKTREES_FOLDER: &str = "worktrees";
+pub const AB_TEST_UPDATED_SIGNAL: &str = "ab-test-updated";
+
+/// How long to wait after a game ends before starting the next one, so that the bots have time to shut down
+const BETWEEN_GAMES: Duration = Duration::from_secs(5);
+/// The z-score for a 95% confidence interval
+const CONFIDENCE_Z: f64 = 1.96;
+
+#[derive(Debug, Error)]
+pub enum AbTestError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Content(#[from] ContentError),
+    #[error(transparent)]
+    Cfg(#[from] cfg_helper::Error),
+    #[error(transparent)]
+    Parse(#[from] RLBotCfgParseError),
+    #[error("git {0} failed: {1}")]
+    Git(String, String),
+    #[error("\"{0}\" isn't in a folder")]
+    NoFolder(String),
+    #[error("An A/B test needs at least one pair of games")]
+    NoGames,
+    #[error("The A/B test \"{0}\" is already running")]
+    AlreadyRunning(String),
+    #[error("No A/B test is running")]
+    NotRunning,
+    #[error(transparent)]
+    Automation(#[from] AutomationError),
+    #[error("Mutex {0} was poisoned")]
+    Poisoned(&'static str),
+}
+
+impl_serialize_from_display!(AbTestError);
+
+/// One side of an A/B test
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Revision {
+    /// The revision as it was given, like a branch name or a commit hash
+    pub revision: String,
+    /// The full hash of the commit that the revision pointed to
+    pub commit: String,
+    /// The name of the bot in the match, which includes the side and the short commit hash
+    pub name: String,
+    /// The path to the bot's config file in the temporary worktree
+    pub path: String,
+    pub wins: u32,
+}
+
+impl Revision {
+    fn short_commit(&self) -> &str {
+        &self.commit[..self.commit.len().min(8)]
+    }
+}
+
+/// A win rate along with a 95% confidence interval
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+pub struct WinRate {
+    pub rate: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl WinRate {
+    /// Get the Wilson score interval, which stays sensible with few games or lopsided results
+    ///
+    /// # Arguments
+    ///
+    /// * `score` - Wins plus half of the draws
+    /// * `games` - How many games finished
+    fn wilson(score: f64, games: u32) -> Self {
+        if games == 0 {
+            return Self {
+                rate: 0.,
+                lower: 0.,
+                upper: 1.,
+            };
+        }
+
+        let n = games as f64;
+        let rate = score / n;
+        let z2 = CONFIDENCE_Z * CONFIDENCE_Z;
+        let denominator = 1. + z2 / n;
+        let center = (rate + z2 / (2. * n)) / denominator;
+        let margin = CONFIDENCE_Z * (rate * (1. - rate) / n + z2 / (4. * n * n)).sqrt() / denominator;
+
+        Self {
+            rate,
+            lower: (center - margin).max(0.),
+            upper: (center + margin).min(1.),
+        }
+    }
+}
+
+/// The results of pitting two revisions of a bot against each other
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AbTest {
+    pub id: String,
+    /// The path to the bot's config file in the original repository
+    pub config_path: String,
+    pub a: Revision,
+    pub b: Revision,
+    /// Each pair is two games with the bots on opposite sides
+    pub pairs: u32,
+    pub match_settings: MiniMatchConfig,
+    pub draws: u32,
+    /// Games that couldn't be started or didn't reach the end
+    pub unfinished: u32,
+    /// How often A beat B, counting draws as half a win
+    pub a_win_rate: WinRate,
+    /// The ids of the games in the match history
+    pub record_ids: Vec<String>,
+    pub finished: bool,
+    pub created_at: u64,
+}
+
+impl AbTest {
+    fn games_finished(&self) -> u32 {
+        self.a.wins + self.b.wins + self.draws
+    }
+
+    fn update_win_rate(&mut self) {
+        self.a_win_rate = WinRate::wilson(self.a.wins as f64 + self.draws as f64 / 2., self.games_finished());
+    }
+}
+
+fn write_ab_test(test: &AbTest) -> Result<(), AbTestError> {
+    Ok(write_json(&AB_TESTS.item_path(&test.id)?, test)?)
+}
+
+pub fn get_ab_test(id: &str) -> Result<AbTest, AbTestError> {
+    Ok(read_json(&AB_TESTS.existing_item_path(id)?)?)
+}
+
+/// Get every A/B test, newest first
+///
+/// # Arguments
+///
+/// * `reporter` - Where to report the A/B tests that couldn't be read
+pub fn list_ab_tests(reporter: &dyn Reporter) -> Result<Vec<AbTest>, AbTestError> {
+    let mut tests = AB_TESTS.list(reporter, read_json::<AbTest>)?;
+    tests.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+    Ok(tests)
+}
+
+pub fn delete_ab_test(id: &str) -> Result<(), AbTestError> {
+    if current_ab_test()?.as_deref() == Some(id.trim()) {
+        return Err(AbTestError::AlreadyRunning(id.to_owned()));
+    }
+
+    fs::remove_file(AB_TESTS.existing_item_path(id)?)?;
+
+    Ok(())
+}
+
+/// Run git and get what it printed, with the trailing newline removed
+///
+/// # Arguments
+///
+/// * `directory` - The folder to run git in
+/// * `args` - The arguments to pass to git
+fn git<P: AsRef<Path>>(directory: P, args: &[&str]) -> Result<String, AbTestError> {
+    let output = get_command("git", args).current_dir(directory).output()?;
+
+    if !output.status.success() {
+        return Err(AbTestError::Git(
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_owned())
+}
+
+/// Remove a temporary worktree, printing instead of failing if it can't be removed
+fn remove_worktree(reporter: &dyn Reporter, repo: &Path, worktree: &Path) {
+    let worktree_str = worktree.to_string_lossy();
+    if let Err(e) = git(repo, &["worktree", "remove", "--force", &worktree_str]) {
+        ccprintln!(reporter, "Couldn't remove the worktree {worktree_str}: {e}");
+
+        if worktree.exists() {
+            if let Err(e) = fs::remove_dir_all(worktree) {
+                ccprintln!(reporter, "Couldn't delete {worktree_str}: {e}");
+            }
+        }
+    }
+
+    let _ = git(repo, &["worktree", "prune"]);
+}
+
+/// Check out a revision into a temporary worktree, and give the bot a name that says which revision it's from
+///
+/// # Arguments
+///
+/// * `repo` - The folder that the bot's config file is in, which must be in a git repository
+/// * `relative_config` - The path to the bot's config file from the root of the repository
+/// * `worktree` - Where to put the worktree
+/// * `label` - Either "A" or "B"
+/// * `revision` - The git revision to check out
+async fn prepare_revision(
+    repo: &Path,
+    relative_config: &str,
+    worktree: &Path,
+    label: &str,
+    revision: &str,
+) -> Result<Revision, AbTestError> {
+    let commit = git(repo, &["rev-parse", "--verify", &format!("{revision}^{{commit}}")])?;
+
+    if let Some(parent) = worktree.parent() {
+        create_dir_all(parent)?;
+    }
+    git(repo, &["worktree", "add", "--detach", &worktree.to_string_lossy(), &commit])?;
+
+    let config_path = worktree.join(relative_config);
+    let bundle = BotConfigBundle::minimal_from_path(&config_path).await?;
+
+    let mut revision = Revision {
+        revision: revision.to_owned(),
+        commit,
+        name: String::new(),
+        path: String::new(),
+        wins: 0,
+    };
+    revision.name = format!("{} ({label} {})", bundle.name, revision.short_commit());
+
+    change_key_in_cfg(&config_path, BOT_CONFIG_MODULE_HEADER, NAME_KEY, revision.name.clone()).await?;
+    revision.path = BotConfigBundle::minimal_from_path(&config_path).await?.path;
+
+    Ok(revision)
+}
+
+/// Plays the games of an A/B test from a background thread, then removes the worktrees
+pub struct AbTestRunner {
+    id: String,
+    running: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+fn emit_update(reporter: &dyn Reporter, test: &AbTest) {
+    if let Err(e) = write_ab_test(test) {
+        ccprintln!(reporter, "Couldn't save A/B test \"{}\": {e}", test.id);
+    }
+
+    if let Err(e) = reporter.emit(AB_TEST_UPDATED_SIGNAL, test) {
+        ccprintln!(reporter, "Failed to emit {AB_TEST_UPDATED_SIGNAL}: {e}");
+    }
+}
+
+/// Play every game of an A/B test, with A starting on blue and the bots swapping sides every game
+fn run(reporter: &dyn Reporter, mut test: AbTest, running: &AtomicBool, use_pipe: bool) {
+    let bundle = |revision: &Revision, team: Team| -> Result<TeamBotBundle, AbTestError> {
+        let bundle = BotConfigBundle::minimal_from_path_sync(Path::new(&revision.path))?;
+        Ok(TeamBotBundle::from_bundle(&bundle, team))
+    };
+
+    for game in 0..test.pairs * 2 {
+        if !running.load(Ordering::Relaxed) {
+            break;
+        }
+
+        let (a_team, b_team) = if game % 2 == 0 {
+            (Team::Blue, Team::Orange)
+        } else {
+            (Team::Orange, Team::Blue)
+        };
+
+        let bots = match (bundle(&test.a, a_team.clone()), bundle(&test.b, b_team)) {
+            (Ok(a), Ok(b)) => vec![a, b],
+            (Err(e), _) | (_, Err(e)) => {
+                ccprintln!(reporter, "Couldn't load the bots for the A/B test, stopping it: {e}");
+                break;
+            }
+        };
+
+        ccprintln!(
+            reporter,
+            "A/B test {}, game {} of {}: {} vs {}",
+            test.id,
+            game + 1,
+            test.pairs * 2,
+            test.a.name,
+            test.b.name
+        );
+
+        if let Err(e) = tauri_block_on(start_match_helper(
+            reporter,
+            bots,
+            test.match_settings.clone(),
+            use_pipe,
+            true,
+        )) {
+            ccprintln!(reporter, "Couldn't start the A/B test game: {e}");
+            test.unfinished += 1;
+        } else {
+            match match_history::await_match_result() {
+                Ok(Some(record)) if record.completed => {
+                    match &record.winner {
+                        Some(winner) if winner == &a_team => test.a.wins += 1,
+                        Some(_) => test.b.wins += 1,
+                        None => test.draws += 1,
+                    }
+                    test.record_ids.push(record.id);
+                }
+                Ok(_) => test.unfinished += 1,
+                Err(e) => {
+                    ccprintln!(reporter, "Couldn't get the result of the A/B test game: {e}");
+                    test.unfinished += 1;
+                }
+            }
+
+            if let Err(e) = tauri_block_on(kill_bots_helper(reporter)) {
+                ccprintln!(reporter, "Couldn't stop the bots after the game: {e}");
+            }
+        }
+
+        test.update_win_rate();
+        emit_update(reporter, &test);
+
+        thread::sleep(BETWEEN_GAMES);
+    }
+
+    test.finished = true;
+    emit_update(reporter, &test);
+
+    ccprintln!(
+        reporter,
+        "A/B test {} finished: {} won {:.1}% of the time (95% CI {:.1}% to {:.1}%) against {}",
+        test.id,
+        test.a.name,
+        test.a_win_rate.rate * 100.,
+        test.a_win_rate.lower * 100.,
+        test.a_win_rate.upper * 100.,
+        test.b.name
+    );
+}
+
+/// Check out two revisions of a bot into temporary worktrees, then start playing them against each other
+///
+/// Returns the id of the A/B test
+///
+/// # Arguments
+///
+/// * `reporter` - Where to send console output and events, like the GUI
+/// * `config_path` - The path to the bot's config file, which must be in a git repository
+/// * `revision_a` - The first git revision, like a branch name or a commit hash
+/// * `revision_b` - The second git revision
+/// * `pairs` - How many pairs of games to play, with the bots swapping sides between the games in a pair
+/// * `match_settings` - The settings that every game is started with
+/// * `use_pipe` - Whether or not to capture the output of the match handler
+pub async fn start_ab_test(
+    reporter: Box<dyn Reporter>,
+    config_path: &str,
+    revision_a: &str,
+    revision_b: &str,
+    pairs: u32,
+    match_settings: MiniMatchConfig,
+    use_pipe: bool,
+) -> Result<String, AbTestError> {
+    if pairs == 0 {
+        return Err(AbTestError::NoGames);
+    }
+
+    let id = format!("{}-{}", unix_time(), rand::random::<u16>());
+    // Taken before the worktrees are made, so that nothing else can start while they're being prepared
+    let slot = AutomationSlot::reserve(Automation::AbTest(id.clone()))?;
+
+    let config = Path::new(config_path);
+    let repo = config
+        .parent()
+        .filter(|parent| parent.is_dir())
+        .ok_or_else(|| AbTestError::NoFolder(config_path.to_owned()))?;
+    let file_name = config
+        .file_name()
+        .ok_or_else(|| AbTestError::NoFolder(config_path.to_owned()))?
+        .to_string_lossy();
+    // The path of the config file's folder from the root of the repository, which ends with a slash if it isn't empty
+    let relative_config = format!("{}{file_name}", git(repo, &["rev-parse", "--show-prefix"])?);
+
+    let worktrees = AB_TESTS.path().join(WORKTREES_FOLDER).join(&id);
+    let (worktree_a, worktree_b) = (worktrees.join("a"), worktrees.join("b"));
+
+    let revisions = match prepare_revision(repo, &relative_config, &worktree_a, "A", revision_a).await {
+        Ok(a) => prepare_revision(repo, &relative_config, &worktree_b, "B", revision_b)
+            .await
+            .map(|b| (a, b)),
+        Err(e) => Err(e),
+    };
+
+    let (a, b) = match revisions {
+        Ok(revisions) => revisions,
+        Err(e) => {
+            for worktree in [&worktree_a, &worktree_b] {
+                if worktree.exists() {
+                    remove_worktree(&*reporter, repo, worktree);
+                }
+            }
+            let _ = fs::remove_dir(&worktrees);
+
+            return Err(e);
+        }
+    };
+
+    let mut test = AbTest {
+        id: id.clone(),
+        config_path: config_path.to_owned(),
+        a,
+        b,
+        pairs,
+        match_settings,
+        draws: 0,
+        unfinished: 0,
+        a_win_rate: WinRate::default(),
+        record_ids: Vec::new(),
+        finished: false,
+        created_at: unix_time(),
+    };
+    test.update_win_rate();
+    write_ab_test(&test)?;
+
+    let running = Arc::new(AtomicBool::new(true));
+    let thread_running = running.clone();
+    let repo = repo.to_path_buf();
+    let thread = thread::spawn(move || {
+        run(&*reporter, test, &thread_running, use_pipe);
+
+        for worktree in [&worktree_a, &worktree_b] {
+            remove_worktree(&*reporter, &repo, worktree);
+        }
+        let _ = fs::remove_dir(&worktrees);
+        drop(slot);
+    });
+
+    *AB_TEST_RUNNER.lock().map_err(|_| AbTestError::Poisoned("AB_TEST_RUNNER"))? = Some(AbTestRunner {
+        id: id.clone(),
+        running,
+        thread,
+    });
+
+    Ok(id)
+}
+
+/// Stop the A/B test after the current game has finished
+///
+/// Returns the id of the A/B test
+pub fn stop_ab_test() -> Result<String, AbTestError> {
+    let runner = AB_TEST_RUNNER.lock().map_err(|_| AbTestError::Poisoned("AB_TEST_RUNNER"))?;
+
+    let runner = runner
+        .as_ref()
+        .filter(|runner| !runner.thread.is_finished())
+        .ok_or(AbTestError::NotRunning)?;
+    runner.running.store(false, Ordering::Relaxed);
+
+    Ok(runner.id.clone())
+}
+
+/// Get the id of the A/B test that's currently running, if there is one
+pub fn current_ab_test() -> Result<Option<String>, AbTestError> {
+    Ok(AB_TEST_RUNNER
+        .lock()
+        .map_err(|_| AbTestError::Poisoned("AB_TEST_RUNNER"))?
+        .as_ref()
+        .filter(|runner| !runner.thread.is_finished())
+        .map(|runner| runner.id.clone()))
+}
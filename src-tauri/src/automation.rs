@@ -0,0 +1,82 @@
+use crate::{impl_serialize_from_display, AUTOMATION};
+use std::fmt;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum AutomationError {
+    #[error("The {0} is still running")]
+    Busy(Automation),
+    #[error("Mutex {0} was poisoned")]
+    Poisoned(&'static str),
+}
+
+impl_serialize_from_display!(AutomationError);
+
+/// Something that plays games by itself, which can't share the match handler with anything else
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Automation {
+    Tournament(String),
+    RegressionSuite(String),
+    AbTest(String),
+}
+
+impl fmt::Display for Automation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tournament(name) => write!(f, "tournament \"{name}\""),
+            Self::RegressionSuite(name) => write!(f, "regression suite \"{name}\""),
+            Self::AbTest(id) => write!(f, "A/B test \"{id}\""),
+        }
+    }
+}
+
+/// Proof that nothing else is playing games by itself, which frees the slot up again when it's dropped
+///
+/// It should be moved into the thread that plays the games, so that it's held until the last game is over
+#[derive(Debug)]
+pub struct AutomationSlot(());
+
+impl AutomationSlot {
+    /// Take the slot, failing if a tournament, regression suite or A/B test already has it
+    ///
+    /// # Arguments
+    ///
+    /// * `automation` - What's going to play games
+    pub fn reserve(automation: Automation) -> Result<Self, AutomationError> {
+        let mut current = AUTOMATION.lock().map_err(|_| AutomationError::Poisoned("AUTOMATION"))?;
+
+        if let Some(current) = current.as_ref() {
+            return Err(AutomationError::Busy(current.clone()));
+        }
+
+        *current = Some(automation);
+
+        Ok(Self(()))
+    }
+}
+
+impl Drop for AutomationSlot {
+    fn drop(&mut self) {
+        if let Ok(mut current) = AUTOMATION.lock() {
+            *current = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_one_automation_at_a_time() {
+        let slot = AutomationSlot::reserve(Automation::Tournament("Cup".to_owned())).unwrap();
+
+        match AutomationSlot::reserve(Automation::AbTest("1-2".to_owned())) {
+            Err(AutomationError::Busy(current)) => assert_eq!(current, Automation::Tournament("Cup".to_owned())),
+            other => panic!("expected the slot to be taken, got {other:?}"),
+        }
+
+        drop(slot);
+        assert!(AutomationSlot::reserve(Automation::RegressionSuite("Nightly".to_owned())).is_ok());
+    }
+}
@@ -1,4 +1,6 @@
 pub(crate) mod bot_creation;
+pub(crate) mod bot_index;
+pub(crate) mod cfg_document;
 pub(crate) mod cfg_helper;
 pub(crate) mod downloader;
 pub(crate) mod zip_extract_fixed;
@@ -1,9 +1,11 @@
 use super::{
-    cfg_helper::{self, change_key_in_cfg, load_cfg, save_cfg},
+    cfg_document::CfgDocument,
+    cfg_helper::{self, change_key_in_cfg},
     zip_extract_fixed,
 };
 use crate::{
     ccprintln,
+    reporter::Reporter,
     rlbot::parsing::{
         bot_config_bundle::{BOT_CONFIG_MODULE_HEADER, BOT_CONFIG_PARAMS_HEADER, EXECUTABLE_PATH_KEY, NAME_KEY},
         directory_scanner::scan_directory_for_bot_configs,
@@ -22,7 +24,6 @@ use std::{
     io::{Cursor, Read, Result as IoResult},
     path::{Path, PathBuf},
 };
-use tauri::Window;
 use thiserror::Error;
 
 pub const CREATED_BOTS_FOLDER: &str = "MyBots";
@@ -35,22 +36,22 @@ pub enum DownloadExtractError {
     Extract(#[from] zip_extract_fixed::ExtractError),
 }
 
-/// Downloads a ZIP from a given URL and unpacks it to `top_dir`, updating progress in the window along the way
+/// Downloads a ZIP from a given URL and unpacks it to `top_dir`, reporting progress along the way
 ///
 /// # Arguments
 ///
-/// * `window`: A reference to the GUI, obtained from a `#[tauri::command]` function
+/// * `reporter`: Where to send console output and events, like the GUI
 /// * `url`: The URL of the ZIP that should be downloaded
 /// * `top_dir`: The path to the folder where the ZIP will get extracted
 async fn download_extract_bot_template<T: IntoUrl>(
-    window: &Window,
+    reporter: &dyn Reporter,
     url: T,
     top_dir: &Path,
 ) -> Result<(), DownloadExtractError> {
     let res = reqwest::get(url).await?;
     let bytes = res.bytes().await?;
 
-    zip_extract_fixed::extract(window, Cursor::new(bytes), top_dir, true, true)?;
+    zip_extract_fixed::extract(reporter, Cursor::new(bytes), top_dir, true, true)?;
 
     Ok(())
 }
@@ -73,10 +74,14 @@ pub enum BoostrapError {
 ///
 /// # Arguments
 ///
-/// * `window`: A reference to the GUI, obtained from a `#[tauri::command]` function
+/// * `reporter`: Where to send console output and events, like the GUI
 /// * `bot_name`: The name of the bot
 /// * `directory`: The base directory to put the bot it, which must exist already
-pub async fn bootstrap_python_bot(window: &Window, bot_name: String, directory: PathBuf) -> Result<String, BoostrapError> {
+pub async fn bootstrap_python_bot(
+    reporter: &dyn Reporter,
+    bot_name: String,
+    directory: PathBuf,
+) -> Result<String, BoostrapError> {
     let sanitized_name = sanitize(&bot_name);
     let top_dir = directory.join(&sanitized_name);
 
@@ -85,24 +90,24 @@ pub async fn bootstrap_python_bot(window: &Window, bot_name: String, directory:
     }
 
     download_extract_bot_template(
-        window,
+        reporter,
         "https://github.com/RLBot/RLBotPythonExample/archive/master.zip",
         top_dir.as_path(),
     )
     .await?;
 
-    let bundles = scan_directory_for_bot_configs(window, &top_dir.to_string_lossy()).await;
+    let bundles = scan_directory_for_bot_configs(reporter, &top_dir.to_string_lossy()).await;
     let config_file = &bundles[0].path;
     let python_file = &bundles[0].python_path;
 
     change_key_in_cfg(config_file, BOT_CONFIG_MODULE_HEADER, NAME_KEY, bot_name).await?;
 
-    BOT_FOLDER_SETTINGS.write().await.add_file(window, config_file.clone())?;
+    BOT_FOLDER_SETTINGS.write().await.add_file(reporter, config_file.clone())?;
 
     if open::that(python_file).is_err() {
         // We don't want to return an error here, because the bot was successfully created
         ccprintln!(
-            window,
+            reporter,
             "You have no default program to open .py files. Your new bot is located at {}",
             top_dir.to_string_lossy(),
         );
@@ -131,11 +136,11 @@ fn replace_all_regex_in_file<R: Replacer>(file_path: &Path, regex: &Regex, repla
 ///
 /// # Arguments
 ///
-/// * `window`: A reference to the GUI, obtained from a `#[tauri::command]` function
+/// * `reporter`: Where to send console output and events, like the GUI
 /// * `hive_name`: The name of the bots
 /// * `directory`: The base directory to put the bot it, which must exist already
 pub async fn bootstrap_python_hivemind(
-    window: &Window,
+    reporter: &dyn Reporter,
     hive_name: String,
     directory: PathBuf,
 ) -> Result<String, BoostrapError> {
@@ -147,7 +152,7 @@ pub async fn bootstrap_python_hivemind(
     }
 
     download_extract_bot_template(
-        window,
+        reporter,
         "https://github.com/RLBot/RLBotPythonHivemindExample/archive/master.zip",
         top_dir.as_path(),
     )
@@ -185,11 +190,14 @@ pub async fn bootstrap_python_hivemind(
 
     let config_file = config_file.to_string_lossy();
 
-    BOT_FOLDER_SETTINGS.write().await.add_file(window, config_file.to_string())?;
+    BOT_FOLDER_SETTINGS
+        .write()
+        .await
+        .add_file(reporter, config_file.to_string())?;
 
     if open::that(hive_file).is_err() {
         ccprintln!(
-            window,
+            reporter,
             "You have no default program to open .py files. Your new bot is located at {}",
             top_dir.to_string_lossy(),
         );
@@ -202,10 +210,14 @@ pub async fn bootstrap_python_hivemind(
 ///
 /// # Arguments
 ///
-/// * `window`: A reference to the GUI, obtained from a `#[tauri::command]` function
+/// * `reporter`: Where to send console output and events, like the GUI
 /// * `bot_name`: The name of the bot
 /// * `directory`: The base directory to put the bot it, which must exist already
-pub async fn bootstrap_rust_bot(window: &Window, bot_name: String, directory: PathBuf) -> Result<String, BoostrapError> {
+pub async fn bootstrap_rust_bot(
+    reporter: &dyn Reporter,
+    bot_name: String,
+    directory: PathBuf,
+) -> Result<String, BoostrapError> {
     let sanitized_name = sanitize(&bot_name);
     let top_dir = directory.join(&sanitized_name);
 
@@ -214,7 +226,7 @@ pub async fn bootstrap_rust_bot(window: &Window, bot_name: String, directory: Pa
     }
 
     download_extract_bot_template(
-        window,
+        reporter,
         "https://github.com/NicEastvillage/RLBotRustTemplateBot/archive/master.zip",
         top_dir.as_path(),
     )
@@ -222,29 +234,29 @@ pub async fn bootstrap_rust_bot(window: &Window, bot_name: String, directory: Pa
 
     let config_file = top_dir.join("rustbot_dev").join("rustbot.cfg");
 
-    let mut conf = load_cfg(&config_file).await?;
+    let mut conf = CfgDocument::load(&config_file).await?;
 
-    conf.set(BOT_CONFIG_MODULE_HEADER, NAME_KEY, Some(bot_name.clone()));
+    conf.set(BOT_CONFIG_MODULE_HEADER, NAME_KEY, bot_name.clone());
     conf.set(
         BOT_CONFIG_PARAMS_HEADER,
         EXECUTABLE_PATH_KEY,
-        Some(format!("../target/debug/{bot_name}.exe")),
+        format!("../target/debug/{bot_name}.exe"),
     );
 
-    save_cfg(&conf, &config_file).await?;
+    conf.save(&config_file).await?;
 
     let cargo_toml_file = top_dir.join("Cargo.toml");
 
-    let mut conf = load_cfg(&cargo_toml_file).await?;
+    let mut conf = CfgDocument::load(&cargo_toml_file).await?;
 
-    conf.set("package", "name", Some(format!("\"{sanitized_name}\"")));
-    conf.set("package", "authors", Some("[\"\"]".to_owned()));
+    conf.set("package", "name", format!("\"{sanitized_name}\""));
+    conf.set("package", "authors", "[\"\"]");
 
-    save_cfg(&conf, cargo_toml_file).await?;
+    conf.save(cargo_toml_file).await?;
 
     if open::that(top_dir.join("src").join("main.rs")).is_err() {
         ccprintln!(
-            window,
+            reporter,
             "You have no default program to open .rs files. Your new bot is located at {}",
             top_dir.to_string_lossy()
         );
@@ -257,10 +269,14 @@ pub async fn bootstrap_rust_bot(window: &Window, bot_name: String, directory: Pa
 ///
 /// # Arguments
 ///
-/// * `window`: A reference to the GUI, obtained from a `#[tauri::command]` function
+/// * `reporter`: Where to send console output and events, like the GUI
 /// * `bot_name`: The name of the bot
 /// * `directory`: The base directory to put the bot it, which must exist already
-pub async fn bootstrap_scratch_bot(window: &Window, bot_name: String, directory: PathBuf) -> Result<String, BoostrapError> {
+pub async fn bootstrap_scratch_bot(
+    reporter: &dyn Reporter,
+    bot_name: String,
+    directory: PathBuf,
+) -> Result<String, BoostrapError> {
     let sanitized_name = sanitize(&bot_name);
     let top_dir = directory.join(&sanitized_name);
 
@@ -269,7 +285,7 @@ pub async fn bootstrap_scratch_bot(window: &Window, bot_name: String, directory:
     }
 
     download_extract_bot_template(
-        window,
+        reporter,
         "https://github.com/RLBot/RLBotScratchInterface/archive/gui-friendly.zip",
         top_dir.as_path(),
     )
@@ -308,19 +324,19 @@ pub async fn bootstrap_scratch_bot(window: &Window, bot_name: String, directory:
     rename(code_dir.join("my_scratch_bot.sb3"), sb3_file)?;
 
     let old_config_file = code_dir.join("my_scratch_bot.cfg");
-    let mut conf = load_cfg(&old_config_file).await?;
+    let mut conf = CfgDocument::load(&old_config_file).await?;
 
-    conf.set(BOT_CONFIG_MODULE_HEADER, NAME_KEY, Some(bot_name.clone()));
-    conf.set(BOT_CONFIG_PARAMS_HEADER, "sb3file", Some(sb3_filename));
+    conf.set(BOT_CONFIG_MODULE_HEADER, NAME_KEY, bot_name.clone());
+    conf.set(BOT_CONFIG_PARAMS_HEADER, "sb3file", sb3_filename);
     let random_port = rand::thread_rng().gen_range(20000..65000);
-    conf.set(BOT_CONFIG_PARAMS_HEADER, "port", Some(random_port.to_string()));
+    conf.set(BOT_CONFIG_PARAMS_HEADER, "port", random_port.to_string());
 
-    save_cfg(&conf, &config_file).await?;
+    conf.save(&config_file).await?;
 
     // delete the old config file
     remove_file(old_config_file)?;
 
-    ccprintln!(window, "Your new bot is located at {}", top_dir.to_string_lossy());
+    ccprintln!(reporter, "Your new bot is located at {}", top_dir.to_string_lossy());
 
     Ok(config_file.to_string_lossy().to_string())
 }
@@ -0,0 +1,609 @@
+use crate::{
+    ccprintln, get_content_folder, impl_serialize_from_display,
+    reporter::Reporter,
+    rlbot::{
+        agents::runnable::Runnable,
+        parsing::{
+            bot_config_bundle::{self, BotConfigBundle, ScriptConfigBundle},
+            config_diagnostics::{self, ConfigDiagnostic},
+        },
+    },
+    settings::BotFolders,
+    BOT_FOLDER_SETTINGS, BOT_INDEX, BOT_INDEX_WATCHER,
+};
+use crossbeam_channel::{unbounded, RecvTimeoutError, Sender};
+use glob::glob;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    ffi::OsStr,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::MutexGuard,
+    thread,
+    time::{Duration, UNIX_EPOCH},
+};
+use tauri::async_runtime::block_on as tauri_block_on;
+use thiserror::Error;
+
+pub const BOT_INDEX_FILE: &str = "bot_index.json";
+pub const BOT_ADDED_SIGNAL: &str = "bot-added";
+pub const BOT_CHANGED_SIGNAL: &str = "bot-changed";
+pub const BOT_REMOVED_SIGNAL: &str = "bot-removed";
+const RECOMMENDATIONS_FILE_NAME: &str = "recommendations.json";
+/// Bump this when `IndexEntry` changes, so that old indexes are rebuilt instead of missing the new information
+const BOT_INDEX_VERSION: u32 = 4;
+
+/// How long the file system has to be quiet before the index is updated, so that something like a bot pack being extracted is handled in one go
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Error)]
+pub enum BotIndexError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+    #[error(transparent)]
+    Notify(#[from] notify::Error),
+    #[error("Mutex {0} was poisoned")]
+    Poisoned(&'static str),
+}
+
+impl_serialize_from_display!(BotIndexError);
+
+/// A parsed config file, which can be a bot, a script, both, or neither, like a looks config
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct IndexEntry {
+    pub path: String,
+    /// When the file was last modified, in milliseconds since the Unix epoch
+    pub modified: u64,
+    /// The files that the config file points to, like its Python file, and when they were last modified (0 if missing)
+    pub references: BTreeMap<String, u64>,
+    pub bot: Option<BotConfigBundle>,
+    pub script: Option<ScriptConfigBundle>,
+    /// Everything wrong with the file, which explains why a bot or script isn't showing up
+    #[serde(default)]
+    pub diagnostics: Vec<ConfigDiagnostic>,
+}
+
+impl IndexEntry {
+    fn is_runnable(&self) -> bool {
+        self.bot.is_some() || self.script.is_some()
+    }
+
+    fn config_file_name(&self) -> &str {
+        self.bot
+            .as_ref()
+            .map(Runnable::get_config_file_name)
+            .or_else(|| self.script.as_ref().map(Runnable::get_config_file_name))
+            .or_else(|| Path::new(&self.path).file_name().and_then(OsStr::to_str))
+            .unwrap_or_default()
+    }
+
+    /// Check if the config file and every file it points to are the same as when it was parsed
+    ///
+    /// # Arguments
+    ///
+    /// * `modified` - When the config file was last modified, in milliseconds since the Unix epoch
+    fn is_up_to_date(&self, modified: u64) -> bool {
+        self.modified == modified
+            && self
+                .references
+                .iter()
+                .all(|(path, modified)| modified_millis(Path::new(path)).unwrap_or_default() == *modified)
+    }
+
+    /// Check if the config file points to a path, or to something in it if it's a folder
+    fn points_to(&self, path: &Path) -> bool {
+        self.references.keys().any(|reference| Path::new(reference).starts_with(path))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum IndexChange {
+    Added(IndexEntry),
+    Changed(IndexEntry),
+    Removed(IndexEntry),
+}
+
+/// Every config file in the bot folders, keyed by path, so that only the files that have changed need to be parsed again
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BotIndex {
+    #[serde(default)]
+    version: u32,
+    entries: HashMap<String, IndexEntry>,
+    /// Paths to every `recommendations.json` in the bot folders
+    recommendations: BTreeSet<String>,
+}
+
+fn modified_millis(path: &Path) -> Option<u64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_millis() as u64)
+}
+
+/// Parse a config file as both a bot and a script, keeping track of anything that's wrong with it
+fn parse(path: &Path, modified: u64) -> IndexEntry {
+    IndexEntry {
+        path: path.to_string_lossy().to_string(),
+        modified,
+        references: bot_config_bundle::referenced_files(path)
+            .into_iter()
+            .map(|file| {
+                let modified = modified_millis(&file).unwrap_or_default();
+                (file.to_string_lossy().to_string(), modified)
+            })
+            .collect(),
+        bot: BotConfigBundle::minimal_from_path_sync(path).ok(),
+        script: ScriptConfigBundle::minimal_from_path_sync(path).ok(),
+        diagnostics: config_diagnostics::diagnose(path),
+    }
+}
+
+/// Check if a config file should be shown, either because it was added directly or because it's in a visible folder
+///
+/// Files in folders that start with an underscore are hidden
+fn is_visible(bfs: &BotFolders, entry: &IndexEntry) -> bool {
+    if bfs.files.get(&entry.path).map_or(false, |props| props.visible) {
+        return true;
+    }
+
+    !entry.config_file_name().starts_with('_')
+        && bfs
+            .folders
+            .iter()
+            .any(|(folder, props)| props.visible && Path::new(&entry.path).starts_with(folder))
+}
+
+impl Default for BotIndex {
+    fn default() -> Self {
+        Self {
+            version: BOT_INDEX_VERSION,
+            entries: HashMap::new(),
+            recommendations: BTreeSet::new(),
+        }
+    }
+}
+
+impl BotIndex {
+    /// Load the index from disk, or start a new one if it's missing, outdated or broken
+    fn load(reporter: &dyn Reporter) -> Self {
+        let path = get_bot_index_path();
+        if !path.is_file() {
+            return Self::default();
+        }
+
+        match File::open(&path).map(BufReader::new) {
+            Ok(reader) => match serde_json::from_reader::<_, Self>(reader) {
+                Ok(index) if index.version == BOT_INDEX_VERSION => index,
+                Ok(_) => {
+                    ccprintln(reporter, "Rebuilding the bot index because it's from an older version");
+                    Self::default()
+                }
+                Err(e) => {
+                    ccprintln!(reporter, "Rebuilding the bot index because it couldn't be read: {e}");
+                    Self::default()
+                }
+            },
+            Err(e) => {
+                ccprintln!(reporter, "Rebuilding the bot index because it couldn't be opened: {e}");
+                Self::default()
+            }
+        }
+    }
+
+    fn save(&self) -> Result<(), BotIndexError> {
+        let path = get_bot_index_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, self)?;
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Parse a config file again if it or any of the files it points to have changed since it was last indexed
+    fn update_file(&mut self, path: &Path, changes: &mut Vec<IndexChange>) {
+        let Some(modified) = modified_millis(path) else {
+            return;
+        };
+
+        let key = path.to_string_lossy().to_string();
+        let previous = self.entries.get(&key);
+        if previous.map_or(false, |entry| entry.is_up_to_date(modified)) {
+            return;
+        }
+
+        let entry = parse(path, modified);
+        match (previous.map_or(false, IndexEntry::is_runnable), entry.is_runnable()) {
+            (false, true) => changes.push(IndexChange::Added(entry.clone())),
+            (true, true) => changes.push(IndexChange::Changed(entry.clone())),
+            (true, false) => changes.push(IndexChange::Removed(entry.clone())),
+            (false, false) => {}
+        }
+
+        self.entries.insert(key, entry);
+    }
+
+    /// Remove a file from the index, along with everything in it if it was a folder
+    fn remove(&mut self, path: &Path, changes: &mut Vec<IndexChange>) {
+        let removed = self
+            .entries
+            .keys()
+            .filter(|key| Path::new(key).starts_with(path))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for key in removed {
+            if let Some(entry) = self.entries.remove(&key).filter(IndexEntry::is_runnable) {
+                changes.push(IndexChange::Removed(entry));
+            }
+        }
+
+        self.recommendations.retain(|file| !Path::new(file).starts_with(path));
+    }
+
+    /// Update the index for a path that the file system watcher said had changed
+    fn update_path(&mut self, reporter: &dyn Reporter, path: &Path, changes: &mut Vec<IndexChange>) {
+        if path.is_dir() {
+            for file in find_files(reporter, &path.to_string_lossy(), "*.cfg") {
+                self.update_file(&file, changes);
+            }
+
+            for file in find_files(reporter, &path.to_string_lossy(), RECOMMENDATIONS_FILE_NAME) {
+                self.recommendations.insert(file.to_string_lossy().to_string());
+            }
+        } else if path.is_file() {
+            if path.file_name() == Some(OsStr::new(RECOMMENDATIONS_FILE_NAME)) {
+                self.recommendations.insert(path.to_string_lossy().to_string());
+            } else if path.extension() == Some(OsStr::new("cfg")) {
+                self.update_file(path, changes);
+            }
+        } else {
+            self.remove(path, changes);
+        }
+
+        // Config files are parsed differently when their Python file, executable or looks config changes
+        let referencing = self
+            .entries
+            .values()
+            .filter(|entry| entry.points_to(path))
+            .map(|entry| PathBuf::from(&entry.path))
+            .collect::<Vec<_>>();
+
+        for config in referencing {
+            self.update_file(&config, changes);
+        }
+    }
+
+    /// Go through every file in the bot folders, parsing the ones that are new or have changed and forgetting the ones that are gone
+    fn refresh(&mut self, reporter: &dyn Reporter, bfs: &BotFolders) -> Vec<IndexChange> {
+        let mut changes = Vec::new();
+        let mut seen = HashSet::new();
+        self.recommendations.clear();
+
+        for folder in bfs.folders.keys() {
+            for file in find_files(reporter, folder, "*.cfg") {
+                self.update_file(&file, &mut changes);
+                seen.insert(file.to_string_lossy().to_string());
+            }
+
+            for file in find_files(reporter, folder, RECOMMENDATIONS_FILE_NAME) {
+                self.recommendations.insert(file.to_string_lossy().to_string());
+            }
+        }
+
+        for file in bfs.files.keys().filter(|file| Path::new(file).is_file()) {
+            self.update_file(Path::new(file), &mut changes);
+            seen.insert(file.clone());
+        }
+
+        let gone = self
+            .entries
+            .keys()
+            .filter(|key| !seen.contains(*key))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for key in gone {
+            if let Some(entry) = self.entries.remove(&key).filter(IndexEntry::is_runnable) {
+                changes.push(IndexChange::Removed(entry));
+            }
+        }
+
+        changes
+    }
+
+    fn visible_entries<'a>(&'a self, bfs: &'a BotFolders) -> impl Iterator<Item = &'a IndexEntry> {
+        let mut entries = self
+            .entries
+            .values()
+            .filter(|entry| entry.is_runnable() && is_visible(bfs, entry))
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        entries.into_iter()
+    }
+}
+
+/// Find every file under a folder with a name that matches the pattern, like `*.cfg`
+fn find_files(reporter: &dyn Reporter, folder: &str, pattern: &str) -> Vec<PathBuf> {
+    match glob(&format!("{folder}/**/{pattern}")) {
+        Ok(paths) => paths.flatten().collect(),
+        Err(e) => {
+            ccprintln!(reporter, "{e}");
+            Vec::new()
+        }
+    }
+}
+
+pub fn get_bot_index_path() -> PathBuf {
+    get_content_folder().join(BOT_INDEX_FILE)
+}
+
+/// Lock the index, loading it from disk if it hasn't been yet
+fn lock_index(reporter: &dyn Reporter) -> Result<MutexGuard<'static, Option<BotIndex>>, BotIndexError> {
+    let mut index = BOT_INDEX.lock().map_err(|_| BotIndexError::Poisoned("BOT_INDEX"))?;
+
+    if index.is_none() {
+        *index = Some(BotIndex::load(reporter));
+    }
+
+    Ok(index)
+}
+
+fn is_watching() -> bool {
+    BOT_INDEX_WATCHER.lock().map_or(false, |watcher| watcher.is_some())
+}
+
+/// Bring the index up to date, unless the file system watcher is already keeping it that way
+fn lock_fresh_index(
+    reporter: &dyn Reporter,
+    bfs: &BotFolders,
+) -> Result<MutexGuard<'static, Option<BotIndex>>, BotIndexError> {
+    let watching = is_watching();
+    let mut lock = lock_index(reporter)?;
+
+    if !watching {
+        let index = lock.get_or_insert_with(BotIndex::default);
+        if !index.refresh(reporter, bfs).is_empty() {
+            index.save()?;
+        }
+    }
+
+    Ok(lock)
+}
+
+/// Get every visible bot, sorted by the path to its config file
+///
+/// # Arguments
+///
+/// * `reporter` - Where to send console output and events, like the GUI
+/// * `bfs` - The bot folders and files to look in
+pub fn bots(reporter: &dyn Reporter, bfs: &BotFolders) -> Result<Vec<BotConfigBundle>, BotIndexError> {
+    Ok(lock_fresh_index(reporter, bfs)?
+        .as_ref()
+        .map(|index| index.visible_entries(bfs).filter_map(|entry| entry.bot.clone()).collect())
+        .unwrap_or_default())
+}
+
+/// Get every visible script, sorted by the path to its config file
+///
+/// # Arguments
+///
+/// * `reporter` - Where to send console output and events, like the GUI
+/// * `bfs` - The bot folders and files to look in
+pub fn scripts(reporter: &dyn Reporter, bfs: &BotFolders) -> Result<Vec<ScriptConfigBundle>, BotIndexError> {
+    Ok(lock_fresh_index(reporter, bfs)?
+        .as_ref()
+        .map(|index| index.visible_entries(bfs).filter_map(|entry| entry.script.clone()).collect())
+        .unwrap_or_default())
+}
+
+/// Get everything that's wrong with the visible config files, sorted by path
+///
+/// # Arguments
+///
+/// * `reporter` - Where to send console output and events, like the GUI
+/// * `bfs` - The bot folders and files to look in
+pub fn diagnostics(reporter: &dyn Reporter, bfs: &BotFolders) -> Result<Vec<ConfigDiagnostic>, BotIndexError> {
+    Ok(lock_fresh_index(reporter, bfs)?
+        .as_ref()
+        .map(|index| {
+            let mut entries = index
+                .entries
+                .values()
+                .filter(|entry| is_visible(bfs, entry))
+                .collect::<Vec<_>>();
+            entries.sort_by(|a, b| a.path.cmp(&b.path));
+            entries.into_iter().flat_map(|entry| entry.diagnostics.clone()).collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Get the paths to every `recommendations.json` in the bot folders, including the hidden ones
+pub fn recommendation_files(reporter: &dyn Reporter, bfs: &BotFolders) -> Result<Vec<String>, BotIndexError> {
+    Ok(lock_fresh_index(reporter, bfs)?
+        .as_ref()
+        .map(|index| index.recommendations.iter().cloned().collect())
+        .unwrap_or_default())
+}
+
+enum WatchMessage {
+    /// Paths that the file system watcher said have changed
+    Paths(Vec<PathBuf>),
+    /// The bot folders have changed, so everything has to be checked again
+    Refresh,
+}
+
+/// Keeps the bot index up to date by watching the bot folders for changes
+pub struct IndexWatcher {
+    watcher: RecommendedWatcher,
+    watched: Vec<PathBuf>,
+    sender: Sender<WatchMessage>,
+}
+
+impl IndexWatcher {
+    /// Watch every bot folder, and the folders that contain the bot files that were added on their own
+    fn watch(&mut self, reporter: &dyn Reporter, bfs: &BotFolders) {
+        for path in self.watched.drain(..) {
+            let _ = self.watcher.unwatch(&path);
+        }
+
+        let folders = bfs
+            .folders
+            .keys()
+            .map(|folder| (PathBuf::from(folder), RecursiveMode::Recursive));
+        let files = bfs
+            .files
+            .keys()
+            .filter_map(|file| Path::new(file).parent())
+            .map(|parent| (parent.to_path_buf(), RecursiveMode::NonRecursive));
+
+        for (path, mode) in folders.chain(files) {
+            if self.watched.contains(&path) {
+                continue;
+            }
+
+            match self.watcher.watch(&path, mode) {
+                Ok(()) => self.watched.push(path),
+                Err(e) => ccprintln!(reporter, "Couldn't watch {} for changes: {e}", path.display()),
+            }
+        }
+    }
+}
+
+fn emit_changes(reporter: &dyn Reporter, bfs: &BotFolders, changes: Vec<IndexChange>) {
+    for change in changes {
+        let (signal, entry) = match change {
+            IndexChange::Added(entry) => (BOT_ADDED_SIGNAL, entry),
+            IndexChange::Changed(entry) => (BOT_CHANGED_SIGNAL, entry),
+            IndexChange::Removed(entry) => (BOT_REMOVED_SIGNAL, entry),
+        };
+
+        if !is_visible(bfs, &entry) {
+            continue;
+        }
+
+        let result = if signal == BOT_REMOVED_SIGNAL {
+            reporter.emit(signal, &entry.path)
+        } else {
+            reporter.emit(signal, &entry)
+        };
+
+        if let Err(e) = result {
+            ccprintln!(reporter, "Failed to emit {signal}: {e}");
+        }
+    }
+}
+
+/// Apply a batch of messages from the file system watcher to the index
+fn handle_messages(reporter: &dyn Reporter, messages: Vec<WatchMessage>) -> Result<(), BotIndexError> {
+    let bfs = tauri_block_on(BOT_FOLDER_SETTINGS.read()).clone();
+    let refresh = messages.iter().any(|message| matches!(message, WatchMessage::Refresh));
+
+    if refresh {
+        if let Some(watcher) = BOT_INDEX_WATCHER
+            .lock()
+            .map_err(|_| BotIndexError::Poisoned("BOT_INDEX_WATCHER"))?
+            .as_mut()
+        {
+            watcher.watch(reporter, &bfs);
+        }
+    }
+
+    let mut lock = lock_index(reporter)?;
+    let index = lock.get_or_insert_with(BotIndex::default);
+
+    let changes = if refresh {
+        index.refresh(reporter, &bfs)
+    } else {
+        let mut changes = Vec::new();
+        let paths = messages
+            .into_iter()
+            .flat_map(|message| match message {
+                WatchMessage::Paths(paths) => paths,
+                WatchMessage::Refresh => Vec::new(),
+            })
+            .collect::<BTreeSet<_>>();
+
+        for path in paths {
+            index.update_path(reporter, &path, &mut changes);
+        }
+
+        changes
+    };
+
+    index.save()?;
+    drop(lock);
+
+    emit_changes(reporter, &bfs, changes);
+
+    Ok(())
+}
+
+/// Bring the index up to date, then keep it that way by watching the bot folders
+///
+/// # Arguments
+///
+/// * `reporter` - Where to send console output and events, like the GUI
+pub fn start_watching(reporter: Box<dyn Reporter>) -> Result<(), BotIndexError> {
+    let mut lock = BOT_INDEX_WATCHER
+        .lock()
+        .map_err(|_| BotIndexError::Poisoned("BOT_INDEX_WATCHER"))?;
+
+    if lock.is_some() {
+        return Ok(());
+    }
+
+    let (sender, receiver) = unbounded();
+    let event_sender = sender.clone();
+    let event_reporter = reporter.boxed();
+    let watcher = notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+        Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+            let _ = event_sender.send(WatchMessage::Paths(event.paths));
+        }
+        Ok(_) => {}
+        Err(e) => ccprintln!(&*event_reporter, "Error watching the bot folders: {e}"),
+    })?;
+
+    *lock = Some(IndexWatcher {
+        watcher,
+        watched: Vec::new(),
+        sender: sender.clone(),
+    });
+    drop(lock);
+
+    thread::spawn(move || {
+        while let Ok(first) = receiver.recv() {
+            let mut messages = vec![first];
+
+            loop {
+                match receiver.recv_timeout(DEBOUNCE) {
+                    Ok(message) => messages.push(message),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+
+            if let Err(e) = handle_messages(&*reporter, messages) {
+                ccprintln!(&*reporter, "Couldn't update the bot index: {e}");
+            }
+        }
+    });
+
+    // The first refresh also starts watching the bot folders
+    let _ = sender.send(WatchMessage::Refresh);
+
+    Ok(())
+}
+
+/// Tell the file system watcher that the bot folders have changed, if it's running
+pub fn folders_changed() {
+    if let Ok(Some(watcher)) = BOT_INDEX_WATCHER.lock().as_deref() {
+        let _ = watcher.sender.send(WatchMessage::Refresh);
+    }
+}
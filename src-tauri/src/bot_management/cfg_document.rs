@@ -0,0 +1,361 @@
+use super::cfg_helper::Error;
+use std::{
+    fmt::{self, Display, Formatter},
+    path::Path,
+};
+use tokio::fs as async_fs;
+
+/// The indentation used for the continuation lines of new multiline values
+const CONTINUATION_INDENT: &str = "    ";
+
+#[derive(Debug, Clone)]
+enum Line {
+    /// Comments, blank lines and anything else that isn't a section header or a key, kept exactly as it was
+    Raw(String),
+    Section {
+        raw: String,
+        name: String,
+    },
+    Entry {
+        key: String,
+        /// The value without any inline comments, which is what `Ini` would read
+        value: String,
+        /// Everything before the value on the first line, like `key = `
+        prefix: String,
+        /// The inline comment at the end of the first line, along with the whitespace before it
+        comment: String,
+        /// The original lines of the entry, which are written back out until the value changes
+        raw: Option<Vec<String>>,
+    },
+}
+
+impl Line {
+    fn is_blank(&self) -> bool {
+        matches!(self, Self::Raw(raw) if raw.trim().is_empty())
+    }
+}
+
+/// A cfg file that can be edited without losing its comments, blank lines, ordering or formatting
+///
+/// `Ini` only keeps keys and values, so anything written with `Ini::writes` loses everything else in the file.
+/// Sections and keys are matched ignoring case, just like `Ini`.
+#[derive(Debug, Clone)]
+pub struct CfgDocument {
+    lines: Vec<Line>,
+    newline: &'static str,
+    trailing_newline: bool,
+}
+
+impl Default for CfgDocument {
+    fn default() -> Self {
+        Self {
+            lines: Vec::new(),
+            newline: "\n",
+            trailing_newline: true,
+        }
+    }
+}
+
+/// Split a line into its contents and an inline comment, since `Ini` ignores everything after a `;`
+fn split_comment(line: &str) -> (&str, &str) {
+    match line.find(';') {
+        Some(index) => {
+            let contents = line[..index].trim_end();
+            (contents, &line[contents.len()..])
+        }
+        None => (line, ""),
+    }
+}
+
+/// Split an entry's first line into its key, everything up to the value, the value itself and its inline comment
+fn split_entry(line: &str) -> Option<(&str, &str, &str, &str)> {
+    let (contents, comment) = split_comment(line);
+    let delimiter = contents.find(['=', ':'])?;
+    let key = contents[..delimiter].trim();
+    if key.is_empty() {
+        return None;
+    }
+
+    let value = contents[delimiter + 1..].trim_start();
+    let prefix = &contents[..contents.len() - value.len()];
+
+    Some((key, prefix, value.trim_end(), comment))
+}
+
+impl CfgDocument {
+    /// Parse the contents of a cfg file
+    ///
+    /// # Arguments
+    ///
+    /// * `text`: The contents of the cfg file
+    pub fn parse(text: &str) -> Self {
+        let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+        let trailing_newline = text.is_empty() || text.ends_with('\n');
+
+        let mut lines: Vec<Line> = Vec::new();
+        for line in text.lines() {
+            let trimmed = line.trim();
+            // Section headers can have an inline comment after them too
+            let (header, _) = split_comment(trimmed);
+
+            // Indented lines right after a key continue its value, like `Ini` with multiline enabled
+            if line.starts_with(char::is_whitespace) && !trimmed.is_empty() {
+                if let Some(Line::Entry {
+                    value, raw: Some(raw), ..
+                }) = lines.last_mut()
+                {
+                    let (contents, _) = split_comment(trimmed);
+                    if !contents.is_empty() {
+                        value.push('\n');
+                        value.push_str(contents);
+                    }
+                    raw.push(line.to_owned());
+                    continue;
+                }
+            }
+
+            if trimmed.is_empty() || trimmed.starts_with(';') || trimmed.starts_with('#') {
+                lines.push(Line::Raw(line.to_owned()));
+            } else if header.starts_with('[') && header.ends_with(']') {
+                lines.push(Line::Section {
+                    raw: line.to_owned(),
+                    name: header[1..header.len() - 1].trim().to_owned(),
+                });
+            } else if let Some((key, prefix, value, comment)) = split_entry(line) {
+                lines.push(Line::Entry {
+                    key: key.to_owned(),
+                    value: value.to_owned(),
+                    prefix: prefix.to_owned(),
+                    comment: comment.to_owned(),
+                    raw: Some(vec![line.to_owned()]),
+                });
+            } else {
+                lines.push(Line::Raw(line.to_owned()));
+            }
+        }
+
+        Self {
+            lines,
+            newline,
+            trailing_newline,
+        }
+    }
+
+    /// Load a cfg file
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The path to the cfg file that needs to be loaded
+    pub async fn load<T: AsRef<Path>>(path: T) -> Result<Self, Error> {
+        Ok(Self::parse(&async_fs::read_to_string(path).await?))
+    }
+
+    /// Save the cfg file, with everything that wasn't changed written back exactly as it was
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: Where to save the cfg file to
+    pub async fn save<T: AsRef<Path>>(&self, path: T) -> Result<(), Error> {
+        async_fs::write(path, self.to_string()).await?;
+        Ok(())
+    }
+
+    /// Set the value of a key, changing only the lines that the key is on
+    ///
+    /// An inline comment after the old value is kept after the new one.
+    /// New keys go after the last key in their section, and new sections go at the end of the file
+    ///
+    /// # Arguments
+    ///
+    /// * `section`: The section of the cfg file to change
+    /// * `key`: The key in `section` to change
+    /// * `value`: What to set the value to
+    pub fn set<S: Into<String>>(&mut self, section: &str, key: &str, value: S) {
+        let new_value = value.into();
+        let mut in_section = false;
+        let mut existing = None;
+        let mut insert_at = None;
+
+        for (index, line) in self.lines.iter().enumerate() {
+            match line {
+                Line::Section { name, .. } => {
+                    in_section = name.eq_ignore_ascii_case(section);
+                    if in_section {
+                        insert_at = Some(index + 1);
+                    }
+                }
+                Line::Entry { key: entry_key, .. } if in_section => {
+                    if entry_key.eq_ignore_ascii_case(key) {
+                        existing = Some(index);
+                    }
+                    insert_at = Some(index + 1);
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(Line::Entry { value, prefix, raw, .. }) = existing.and_then(|index| self.lines.get_mut(index)) {
+            if *value != new_value {
+                // Keep the space between the delimiter and the value, even if the old value was empty
+                if !prefix.ends_with(char::is_whitespace) {
+                    prefix.push(' ');
+                }
+
+                *value = new_value;
+                *raw = None;
+            }
+
+            return;
+        }
+
+        let entry = Line::Entry {
+            key: key.to_owned(),
+            value: new_value,
+            prefix: format!("{key} = "),
+            comment: String::new(),
+            raw: None,
+        };
+
+        if let Some(index) = insert_at {
+            self.lines.insert(index, entry);
+            return;
+        }
+
+        if self.lines.last().map_or(false, |line| !line.is_blank()) {
+            self.lines.push(Line::Raw(String::new()));
+        }
+
+        self.lines.push(Line::Section {
+            raw: format!("[{section}]"),
+            name: section.to_owned(),
+        });
+        self.lines.push(entry);
+    }
+}
+
+impl Display for CfgDocument {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut rendered = Vec::with_capacity(self.lines.len());
+
+        for line in &self.lines {
+            match line {
+                Line::Raw(raw) | Line::Section { raw, .. } => rendered.push(raw.clone()),
+                Line::Entry { raw: Some(raw), .. } => rendered.extend(raw.iter().cloned()),
+                Line::Entry {
+                    value,
+                    prefix,
+                    comment,
+                    raw: None,
+                    ..
+                } => {
+                    let mut value_lines = value.split('\n');
+                    rendered.push(format!("{prefix}{}{comment}", value_lines.next().unwrap_or_default()));
+                    rendered.extend(value_lines.map(|line| format!("{CONTINUATION_INDENT}{line}")));
+                }
+            }
+        }
+
+        write!(f, "{}", rendered.join(self.newline))?;
+
+        if self.trailing_newline && !rendered.is_empty() {
+            write!(f, "{}", self.newline)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unchanged_files_round_trip() {
+        let texts = [
+            "",
+            "[Locations]\r\n; The bot's name\r\nname = Nexto\r\n\r\n[Details]\r\ndescription = One\r\n    two\r\n",
+            "[Locations]\nname=Nexto\npython_file = bot.py",
+            "[Details]\ndeveloper: Me ; or you\n  fun_fact:\tnone  \n\n\n",
+            "# Not a comment to Ini, but kept anyway\n[Locations]\n   \nname = Nexto\n",
+        ];
+
+        for text in texts {
+            assert_eq!(CfgDocument::parse(text).to_string(), text);
+        }
+    }
+
+    #[test]
+    fn sections_can_have_inline_comments() {
+        let text = "[Bot Loadout] ; team colors\nteam_color_id = 1\n\n[Bot Loadout Orange]\nteam_color_id = 2\n";
+        let mut document = CfgDocument::parse(text);
+        assert_eq!(document.to_string(), text);
+
+        document.set("Bot Loadout", "team_color_id", "3");
+        assert_eq!(
+            document.to_string(),
+            "[Bot Loadout] ; team colors\nteam_color_id = 3\n\n[Bot Loadout Orange]\nteam_color_id = 2\n"
+        );
+    }
+
+    #[test]
+    fn set_only_changes_its_line() {
+        let mut document = CfgDocument::parse(
+            "[Locations]\r\n; The bot's name\r\nname = Nexto ; shown in the GUI\r\nlooks_config = looks.cfg\r\n",
+        );
+
+        document.set("locations", "NAME", "Nexto");
+        assert_eq!(
+            document.to_string(),
+            "[Locations]\r\n; The bot's name\r\nname = Nexto ; shown in the GUI\r\nlooks_config = looks.cfg\r\n"
+        );
+
+        document.set("locations", "NAME", "Necto");
+        assert_eq!(
+            document.to_string(),
+            "[Locations]\r\n; The bot's name\r\nname = Necto ; shown in the GUI\r\nlooks_config = looks.cfg\r\n"
+        );
+    }
+
+    #[test]
+    fn set_keeps_colon_delimiters() {
+        let mut document = CfgDocument::parse("[Details]\ndeveloper:Me\n");
+        document.set("Details", "developer", "You");
+
+        assert_eq!(document.to_string(), "[Details]\ndeveloper: You\n");
+    }
+
+    #[test]
+    fn set_multiline_values() {
+        let mut document = CfgDocument::parse("[Details]\ndescription = One\n    two ; not part of it\nfun_fact = x\n");
+        document.set("Details", "description", "One\ntwo");
+        assert_eq!(
+            document.to_string(),
+            "[Details]\ndescription = One\n    two ; not part of it\nfun_fact = x\n"
+        );
+
+        document.set("Details", "description", "A\nB");
+        assert_eq!(document.to_string(), "[Details]\ndescription = A\n    B\nfun_fact = x\n");
+    }
+
+    #[test]
+    fn new_keys_go_after_the_last_key_in_their_section() {
+        let mut document = CfgDocument::parse("[Locations]\nname = Nexto\n\n[Details]\ndeveloper = Me");
+        document.set("Locations", "python_file", "bot.py");
+
+        assert_eq!(
+            document.to_string(),
+            "[Locations]\nname = Nexto\npython_file = bot.py\n\n[Details]\ndeveloper = Me"
+        );
+    }
+
+    #[test]
+    fn new_sections_go_at_the_end() {
+        let mut document = CfgDocument::parse("[Locations]\r\nname = Nexto\r\n");
+        document.set("Bot Parameters", "path", "bot.exe");
+
+        assert_eq!(
+            document.to_string(),
+            "[Locations]\r\nname = Nexto\r\n\r\n[Bot Parameters]\r\npath = bot.exe\r\n"
+        );
+    }
+}
@@ -3,6 +3,7 @@ use std::path::Path;
 use thiserror::Error;
 use tokio::fs as async_fs;
 
+use super::cfg_document::CfgDocument;
 use crate::impl_serialize_from_display;
 
 #[derive(Debug, Error)]
@@ -54,7 +55,7 @@ pub async fn save_cfg<T: AsRef<Path>>(conf: &Ini, path: T) -> Result<(), Error>
     Ok(())
 }
 
-/// Load, change a key, and save a cfg file. Returns a descripton of any errors if unable to do so
+/// Load, change a key, and save a cfg file, keeping the rest of the file as it was. Returns a descripton of any errors if unable to do so
 ///
 /// # Arguments
 ///
@@ -63,7 +64,7 @@ pub async fn save_cfg<T: AsRef<Path>>(conf: &Ini, path: T) -> Result<(), Error>
 /// * `key`: The key in `section` to change
 /// * `value`: What to set the value to
 pub async fn change_key_in_cfg<T: AsRef<Path>>(path: T, section: &str, key: &str, value: String) -> Result<(), Error> {
-    let mut conf = load_cfg(&path).await?;
-    conf.set(section, key, Some(value));
-    save_cfg(&conf, path).await
+    let mut doc = CfgDocument::load(&path).await?;
+    doc.set(section, key, value);
+    doc.save(path).await
 }
@@ -2,7 +2,7 @@ use super::{
     cfg_helper::{load_cfg, save_cfg},
     zip_extract_fixed,
 };
-use crate::{ccprintln, commands::UPDATE_DOWNLOAD_PROGRESS_SIGNAL, emit_text, get_config_path, load_gui_config};
+use crate::{ccprintln, get_config_path, load_gui_config, reporter::Reporter};
 use fs_extra::dir;
 use futures_util::StreamExt;
 use rand::Rng;
@@ -17,7 +17,6 @@ use std::{
     path::{Path, PathBuf},
     time::Instant,
 };
-use tauri::Window;
 use tokio::{fs as async_fs, task};
 
 const FOLDER_SUFFIX: &str = "master";
@@ -37,9 +36,9 @@ pub enum BotpackStatus {
 ///
 /// # Arguments
 ///
-/// * `window`: A reference to the GUI, obtained from a `#[tauri::command]` function
+/// * `reporter`: Where to send console output and events, like the GUI
 /// * `dir`: The directory to target
-fn remove_empty_folders<T: AsRef<Path>>(window: &Window, dir: T) -> Result<(), Box<dyn Error>> {
+fn remove_empty_folders<T: AsRef<Path>>(reporter: &dyn Reporter, dir: T) -> Result<(), Box<dyn Error>> {
     let dir = dir.as_ref();
 
     // remove any empty sub folders
@@ -47,12 +46,12 @@ fn remove_empty_folders<T: AsRef<Path>>(window: &Window, dir: T) -> Result<(), B
         .flatten()
         .map(|entry| entry.path())
         .filter(|path| path.is_dir())
-        .try_for_each(|path| remove_empty_folders(window, path))?;
+        .try_for_each(|path| remove_empty_folders(reporter, path))?;
 
     // remove the folder if it is empty
     if dir.read_dir()?.next().is_none() {
         remove_dir(dir)?;
-        ccprintln!(window, "Removed empty folder: {}", dir.display());
+        ccprintln!(reporter, "Removed empty folder: {}", dir.display());
     }
 
     Ok(())
@@ -105,14 +104,14 @@ impl ProgressBarUpdate {
 ///
 /// # Arguments
 ///
-/// * `window`: A reference to the GUI, obtained from a `#[tauri::command]` function
+/// * `reporter`: Where to send console output and events, like the GUI
 /// * `client`: The client to use to make the request
 /// * `download_url`: The URL to get the zip from
 /// * `local_folder_path`: The path to the folder to extract the zip to
 /// * `clobber`: Deletes `local_folder_path` if it already exists
 /// * `repo_full_name`: The owner/name of the repo, e.x. "RLBot/RLBotPack"
 async fn download_and_extract_repo_zip<T: IntoUrl, J: AsRef<Path>>(
-    window: &Window,
+    reporter: &dyn Reporter,
     client: &Client,
     download_url: T,
     local_folder_path: J,
@@ -136,31 +135,21 @@ async fn download_and_extract_repo_zip<T: IntoUrl, J: AsRef<Path>>(
 
         if last_update.elapsed().as_secs_f32() >= 0.1 {
             let progress = bytes.len() as f64 / real_size_estimate * 100.0;
-            if let Err(e) = window.emit(
-                UPDATE_DOWNLOAD_PROGRESS_SIGNAL,
-                ProgressBarUpdate::new(progress, "Downloading zip...".to_owned()),
-            ) {
-                ccprintln!(window, "Error when updating progress bar: {e}");
-            }
+            reporter.progress(progress, "Downloading zip...");
             last_update = Instant::now();
         }
     }
 
     if clobber && local_folder_path.exists() {
         if let Err(e) = dir::remove(local_folder_path) {
-            ccprintln!(window, "Error when removing existing folder: {e}");
+            ccprintln!(reporter, "Error when removing existing folder: {e}");
         }
     }
 
-    if let Err(e) = window.emit(
-        UPDATE_DOWNLOAD_PROGRESS_SIGNAL,
-        ProgressBarUpdate::new(100., "Extracting zip...".to_owned()),
-    ) {
-        ccprintln!(window, "Error when updating progress bar: {e}");
-    }
+    reporter.progress(100., "Extracting zip...");
 
-    if let Err(e) = zip_extract_fixed::extract(window, Cursor::new(bytes), local_folder_path, false, true) {
-        ccprintln!(window, "Error when extracting zip: {e}");
+    if let Err(e) = zip_extract_fixed::extract(reporter, Cursor::new(bytes), local_folder_path, false, true) {
+        ccprintln!(reporter, "Error when extracting zip: {e}");
     }
 
     Ok(())
@@ -170,13 +159,13 @@ async fn download_and_extract_repo_zip<T: IntoUrl, J: AsRef<Path>>(
 ///
 /// # Arguments
 ///
-/// * `window`: A reference to the GUI, obtained from a `#[tauri::command]` function
+/// * `reporter`: Where to send console output and events, like the GUI
 /// * `repo_owner`: The owner of the repo, e.x. `"RLBot"`
 /// * `repo_name`: The name of the repo, e.x. `"RLBotPack"`
 /// * `checkout_folder`: The folder to checkout the repo to
 /// * `update_tag_settings`: Whether to update the incr tag in the GUI config
 pub async fn download_repo(
-    window: &Window,
+    reporter: &dyn Reporter,
     repo_owner: &str,
     repo_name: &str,
     checkout_folder: &str,
@@ -186,7 +175,7 @@ pub async fn download_repo(
     let repo_full_name = format!("{repo_owner}/{repo_name}");
 
     if let Err(e) = download_and_extract_repo_zip(
-        window,
+        reporter,
         &client,
         &format!("https://github.com/{repo_full_name}/archive/refs/heads/master.zip"),
         checkout_folder,
@@ -195,7 +184,7 @@ pub async fn download_repo(
     )
     .await
     {
-        ccprintln(window, e.to_string());
+        ccprintln(reporter, e.to_string());
         return BotpackStatus::Skipped("Failed to download the bot pack...".to_owned());
     };
 
@@ -208,7 +197,7 @@ pub async fn download_repo(
         {
             Ok(release) => release["tag_name"].as_str().unwrap_or_default().to_owned(),
             Err(e) => {
-                ccprintln(window, e.to_string());
+                ccprintln(reporter, e.to_string());
                 return BotpackStatus::Success(
                     "Downloaded the bot pack, but failed to get the latest release tag.".to_owned(),
                 );
@@ -216,12 +205,12 @@ pub async fn download_repo(
         };
 
         let config_path = get_config_path();
-        let mut config = load_gui_config(window).await;
+        let mut config = load_gui_config(reporter).await;
 
         config.set("bot_folder_settings", "incr", Some(latest_release_tag_name));
 
         if let Err(e) = save_cfg(&config, config_path).await {
-            ccprintln(window, e.to_string());
+            ccprintln(reporter, e.to_string());
             return BotpackStatus::Success("Downloaded the bot pack, but failed to write GUI's config.".to_owned());
         }
     }
@@ -275,9 +264,9 @@ async fn get_latest_release_tag(repo_full_name: &str) -> Result<u32, String> {
 ///
 /// # Arguments
 ///
-/// * `window`: A reference to the GUI, obtained from a `#[tauri::command]` function
+/// * `reporter`: Where to send console output and events, like the GUI
 /// * `repo_full_name`: The owner/name of the repo, e.x. "RLBot/RLBotPack"
-pub async fn is_botpack_up_to_date(window: &Window, repo_full_name: &str) -> bool {
+pub async fn is_botpack_up_to_date(reporter: &dyn Reporter, repo_full_name: &str) -> bool {
     let Some(current_tag_name) = get_current_tag_name().await else {
         return true;
     };
@@ -285,7 +274,7 @@ pub async fn is_botpack_up_to_date(window: &Window, repo_full_name: &str) -> boo
     match get_latest_release_tag(repo_full_name).await {
         Ok(latest_release_tag) => latest_release_tag == current_tag_name,
         Err(e) => {
-            ccprintln(window, e);
+            ccprintln(reporter, e);
             true
         }
     }
@@ -295,11 +284,16 @@ pub async fn is_botpack_up_to_date(window: &Window, repo_full_name: &str) -> boo
 ///
 /// # Arguments
 ///
-/// * `window`: A reference to the GUI, obtained from a `#[tauri::command]` function
+/// * `reporter`: Where to send console output and events, like the GUI
 /// * `repo_owner`: The owner of the repo, e.x. `"RLBot"`
 /// * `repo_name`: The name of the repo, e.x. `"RLBotPack"`
 /// * `checkout_folder`: The folder to checkout the repo to
-pub async fn update_bot_pack(window: &Window, repo_owner: &str, repo_name: &str, checkout_folder: &str) -> BotpackStatus {
+pub async fn update_bot_pack(
+    reporter: &dyn Reporter,
+    repo_owner: &str,
+    repo_name: &str,
+    checkout_folder: &str,
+) -> BotpackStatus {
     let repo_full_name = format!("{repo_owner}/{repo_name}");
 
     let Some(current_tag_name) = get_current_tag_name().await else {
@@ -309,13 +303,13 @@ pub async fn update_bot_pack(window: &Window, repo_owner: &str, repo_name: &str,
     let latest_release_tag = match get_latest_release_tag(&repo_full_name).await {
         Ok(value) => value,
         Err(e) => {
-            ccprintln(window, e);
+            ccprintln(reporter, e);
             return BotpackStatus::Skipped("Failed to get the latest release tag.".to_owned());
         }
     };
 
     if latest_release_tag == current_tag_name {
-        ccprintln(window, "The botpack is already up-to-date!");
+        ccprintln(reporter, "The botpack is already up-to-date!");
         return BotpackStatus::Skipped("The botpack is already up-to-date!".to_owned());
     }
 
@@ -333,7 +327,7 @@ pub async fn update_bot_pack(window: &Window, repo_owner: &str, repo_name: &str,
     }
 
     let config_path = get_config_path();
-    let mut config = load_gui_config(window).await;
+    let mut config = load_gui_config(reporter).await;
 
     let tag_deleted_files_path = local_folder_path.join(".deleted");
 
@@ -347,53 +341,43 @@ pub async fn update_bot_pack(window: &Window, repo_owner: &str, repo_name: &str,
 
     for handle in handles {
         let patch_status = format!("Patching in update incr-{tag}");
-        ccprintln(window, &patch_status);
+        ccprintln(reporter, &patch_status);
 
         let progress = f64::from(tag - current_tag_name) / total_patches * 100.;
-        if let Err(e) = window.emit(
-            UPDATE_DOWNLOAD_PROGRESS_SIGNAL,
-            ProgressBarUpdate::new(progress, patch_status),
-        ) {
-            ccprintln!(window, "Error when updating progress bar: {e}");
-        }
+        reporter.progress(progress, &patch_status);
 
         let resp = match handle.await {
             Ok(resp) => resp,
             Err(e) => {
-                ccprintln!(window, "Error awaiting handle: {e}");
+                ccprintln!(reporter, "Error awaiting handle: {e}");
                 break;
             }
         };
 
         let progress = progress + 1. / (total_patches * 2.) * 100.;
-        if let Err(e) = window.emit(
-            UPDATE_DOWNLOAD_PROGRESS_SIGNAL,
-            ProgressBarUpdate::new(progress, format!("Applying patch incr-{tag}...")),
-        ) {
-            ccprintln!(window, "Error when updating progress bar: {}", e);
-        }
+        reporter.progress(progress, &format!("Applying patch incr-{tag}..."));
 
-        if let ControlFlow::Break(_) = apply_patch(resp, window, &local_folder_path, &tag_deleted_files_path).await {
+        if let ControlFlow::Break(_) = apply_patch(resp, reporter, &local_folder_path, &tag_deleted_files_path).await {
             break;
         }
 
         config.set("bot_folder_settings", "incr", Some(format!("incr-{tag}")));
 
         if let Err(e) = save_cfg(&config, &config_path).await {
-            ccprintln(window, e.to_string());
+            ccprintln(reporter, e.to_string());
         }
 
         tag += 1;
 
         if tag_deleted_files_path.exists() {
             if let Err(e) = remove_file(&tag_deleted_files_path) {
-                ccprintln!(window, "Error deleting {}: {e}", tag_deleted_files_path.display());
+                ccprintln!(reporter, "Error deleting {}: {e}", tag_deleted_files_path.display());
             }
         }
     }
 
-    if let Err(e) = remove_empty_folders(window, local_folder_path) {
-        ccprintln!(window, "Error removing empty folders: {e}");
+    if let Err(e) = remove_empty_folders(reporter, local_folder_path) {
+        ccprintln!(reporter, "Error removing empty folders: {e}");
     }
 
     if tag - 1 == latest_release_tag {
@@ -408,19 +392,19 @@ pub async fn update_bot_pack(window: &Window, repo_owner: &str, repo_name: &str,
 /// # Arguments
 ///
 /// * `resp`: The response from the HTTP request
-/// * `window`: A reference to the GUI, obtained from a `#[tauri::command]` function
+/// * `reporter`: Where to send console output and events, like the GUI
 /// * `local_folder_path`: The path to the local folder containing the botpack
 /// * `tag_deleted_files_path`: The path to the file containing the deleted files for the patch
 async fn apply_patch(
     resp: Result<reqwest::Response, reqwest::Error>,
-    window: &Window,
+    reporter: &dyn Reporter,
     local_folder_path: &Path,
     tag_deleted_files_path: &Path,
 ) -> ControlFlow<()> {
     let download = match resp {
         Ok(download) => download,
         Err(e) => {
-            ccprintln!(window, "Error downloading upgrade zip: {e}");
+            ccprintln!(reporter, "Error downloading upgrade zip: {e}");
             return ControlFlow::Break(());
         }
     };
@@ -428,20 +412,20 @@ async fn apply_patch(
     let bytes = match download.bytes().await {
         Ok(bytes) => bytes,
         Err(e) => {
-            ccprintln!(window, "Error downloading upgrade zip: {e}");
+            ccprintln!(reporter, "Error downloading upgrade zip: {e}");
             return ControlFlow::Break(());
         }
     };
 
-    if let Err(e) = zip_extract_fixed::extract(window, Cursor::new(&bytes), local_folder_path, false, true) {
-        ccprintln!(window, "Error extracting upgrade zip: {e}");
+    if let Err(e) = zip_extract_fixed::extract(reporter, Cursor::new(&bytes), local_folder_path, false, true) {
+        ccprintln!(reporter, "Error extracting upgrade zip: {e}");
         return ControlFlow::Break(());
     }
 
     let file = match File::open(tag_deleted_files_path) {
         Ok(file) => file,
         Err(e) => {
-            ccprintln!(window, "Error opening .deleted file: {e}");
+            ccprintln!(reporter, "Error opening .deleted file: {e}");
             return ControlFlow::Break(());
         }
     };
@@ -453,17 +437,17 @@ async fn apply_patch(
         if !line.is_empty() {
             let file_name = local_folder_path.join(line);
             if let Err(e) = remove_file(&file_name) {
-                ccprintln!(window, "Error deleting {}: {e}", file_name.display());
+                ccprintln!(reporter, "Error deleting {}: {e}", file_name.display());
                 last_ok = false;
             } else {
-                emit_text(window, format!("Deleted {}", file_name.display()), last_ok);
+                reporter.print(&format!("Deleted {}", file_name.display()), last_ok);
                 last_ok = true;
                 count += 1;
             }
         }
     }
 
-    emit_text(window, format!("Deleted {count} files"), last_ok);
+    reporter.print(&format!("Deleted {count} files"), last_ok);
     ControlFlow::Continue(())
 }
 
@@ -485,14 +469,14 @@ impl MapPackUpdater {
     }
 
     /// For a map pack, gets you the index.json data
-    pub async fn get_map_index(&self, window: &Window) -> Option<serde_json::Value> {
+    pub async fn get_map_index(&self, reporter: &dyn Reporter) -> Option<serde_json::Value> {
         let index_path = self.full_path.join("index.json");
 
         if index_path.exists() {
             let contents = match async_fs::read_to_string(index_path).await {
                 Ok(contents) => contents,
                 Err(e) => {
-                    ccprintln!(window, "Error reading index.json: {e}");
+                    ccprintln!(reporter, "Error reading index.json: {e}");
                     return None;
                 }
             };
@@ -500,7 +484,7 @@ impl MapPackUpdater {
             match serde_json::from_str(&contents) {
                 Ok(json) => Some(json),
                 Err(e) => {
-                    ccprintln!(window, "Error parseing index.json: {e}");
+                    ccprintln!(reporter, "Error parseing index.json: {e}");
                     None
                 }
             }
@@ -512,8 +496,8 @@ impl MapPackUpdater {
     /// Compares the `old_index` with current index and for any
     /// maps that have updated the revision, we grab them
     /// from the latest revision
-    pub async fn needs_update(&self, window: &Window) -> BotpackStatus {
-        let Some(index) = self.get_map_index(window).await else {
+    pub async fn needs_update(&self, reporter: &dyn Reporter) -> BotpackStatus {
+        let Some(index) = self.get_map_index(reporter).await else {
             return BotpackStatus::RequiresFullDownload;
         };
         let revision = index["revision"].as_u64().unwrap();
@@ -525,7 +509,7 @@ impl MapPackUpdater {
         let latest_release = match get_json_from_url(&self.client, &url).await {
             Ok(latest_release) => latest_release,
             Err(e) => {
-                ccprintln!(window, "Error getting latest release: {e}");
+                ccprintln!(reporter, "Error getting latest release: {e}");
                 return BotpackStatus::Skipped("Failed to get latest release".to_owned());
             }
         };
@@ -535,7 +519,7 @@ impl MapPackUpdater {
         if latest_revision > revision {
             BotpackStatus::RequiresFullDownload
         } else {
-            ccprintln(window, "Map pack is already up-to-date!");
+            ccprintln(reporter, "Map pack is already up-to-date!");
             BotpackStatus::Skipped("Map pack is already up-to-date!".to_owned())
         }
     }
@@ -552,9 +536,9 @@ impl MapPackUpdater {
     /// Compares the `old_index` with current index and for any
     /// maps that have updated the revision, we grab them
     /// from the latest revision
-    pub async fn hydrate_map_pack(&self, window: &Window, old_index: Option<serde_json::Value>) {
-        let Some(index) = self.get_map_index(window).await else {
-            ccprintln(window, "Error getting index.json");
+    pub async fn hydrate_map_pack(&self, reporter: &dyn Reporter, old_index: Option<serde_json::Value>) {
+        let Some(index) = self.get_map_index(reporter).await else {
+            ccprintln(reporter, "Error getting index.json");
             return;
         };
 
@@ -586,7 +570,7 @@ impl MapPackUpdater {
         let latest_release = match get_json_from_url(&self.client, &url).await {
             Ok(latest_release) => latest_release,
             Err(e) => {
-                ccprintln!(window, "Error getting latest release: {e}");
+                ccprintln!(reporter, "Error getting latest release: {e}");
                 return;
             }
         };
@@ -594,17 +578,17 @@ impl MapPackUpdater {
         for asset in latest_release["assets"].as_array().unwrap() {
             let asset_name = asset["name"].as_str().unwrap();
             if let Err(e) = self
-                .download_asset(window, asset, asset_name, &filename_to_path, &self.full_path)
+                .download_asset(reporter, asset, asset_name, &filename_to_path, &self.full_path)
                 .await
             {
-                ccprintln!(window, "Error downloading asset {asset_name}: {e}");
+                ccprintln!(reporter, "Error downloading asset {asset_name}: {e}");
             }
         }
     }
 
     async fn download_asset<T: AsRef<Path>>(
         &self,
-        window: &Window,
+        reporter: &dyn Reporter,
         asset: &serde_json::Value,
         asset_name: &str,
         filename_to_path: &HashMap<String, String>,
@@ -612,7 +596,7 @@ impl MapPackUpdater {
     ) -> Result<(), Box<dyn Error>> {
         if let Some(local_path) = filename_to_path.get(asset_name) {
             let target_path = full_path.as_ref().join(local_path);
-            ccprintln!(window, "Will fetch updated map {asset_name}");
+            ccprintln!(reporter, "Will fetch updated map {asset_name}");
 
             let url = asset["browser_download_url"].as_str().unwrap();
             let resp = self.client.get(url).send().await?.bytes().await?;
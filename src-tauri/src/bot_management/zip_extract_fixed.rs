@@ -1,10 +1,9 @@
-use crate::{ccprintln, ccprintlnr};
+use crate::{ccprintln, ccprintlnr, reporter::Reporter};
 use std::{
     fs,
     io::{copy, Read, Seek},
     path::{Path, PathBuf, StripPrefixError},
 };
-use tauri::Window;
 use thiserror::Error;
 use zip::{result::ZipError, ZipArchive};
 
@@ -33,13 +32,13 @@ pub enum ExtractError {
 ///
 /// # Arguments
 ///
-/// * `window`: A reference to the GUI, obtained from a `#[tauri::command]` function
+/// * `reporter`: Where to send console output and events, like the GUI
 /// * `source`: The source zip file to extract
 /// * `target_dir`: The target directory to extract the zip file to
 /// * `toplevel`: If the top level directory to strip from the zip file (does nothing if there are multiple top level directories)
 /// * `replace`: Whether or not files should be overwritten if they already exist in the target directory
 pub fn extract<S: Read + Seek>(
-    window: &Window,
+    reporter: &dyn Reporter,
     source: S,
     target_dir: &Path,
     strip_toplevel: bool,
@@ -52,10 +51,10 @@ pub fn extract<S: Read + Seek>(
     let mut archive = ZipArchive::new(source)?;
     let num_files = archive.len();
 
-    let do_strip_toplevel = strip_toplevel && has_toplevel(window, &mut archive)?;
+    let do_strip_toplevel = strip_toplevel && has_toplevel(reporter, &mut archive)?;
 
-    ccprintln!(window, "Extracting to {}", target_dir.to_string_lossy());
-    ccprintln!(window);
+    ccprintln!(reporter, "Extracting to {}", target_dir.to_string_lossy());
+    ccprintln!(reporter);
     for i in 0..num_files {
         let mut item = archive.by_index(i)?;
         let Some(enclosed_name) = item.enclosed_name() else {
@@ -91,14 +90,14 @@ pub fn extract<S: Read + Seek>(
 
         if item.is_dir() {
             ccprintlnr!(
-                window,
+                reporter,
                 "Creating directory {} from {}",
                 outpath.to_string_lossy(),
                 relative_path.display()
             );
             if !outpath.exists() {
                 if let Err(e) = fs::create_dir_all(&outpath) {
-                    ccprintln!(window, "Error creating directory {}: {e}", outpath.display());
+                    ccprintln!(reporter, "Error creating directory {}: {e}", outpath.display());
                 }
             }
             continue;
@@ -113,13 +112,13 @@ pub fn extract<S: Read + Seek>(
         } else if let Some(p) = outpath.parent() {
             if !p.exists() {
                 if let Err(e) = fs::create_dir_all(p) {
-                    ccprintln!(window, "Error creating directory {}: {e}", p.display());
+                    ccprintln!(reporter, "Error creating directory {}: {e}", p.display());
                 }
             }
         }
 
         ccprintlnr!(
-            window,
+            reporter,
             "({i}/{num_files}) Creating {} from {}",
             outpath.to_string_lossy(),
             relative_path.display()
@@ -128,7 +127,7 @@ pub fn extract<S: Read + Seek>(
         copy(&mut item, &mut outfile)?;
     }
 
-    ccprintlnr!(window, "Extracted {num_files} items");
+    ccprintlnr!(reporter, "Extracted {num_files} items");
     Ok(())
 }
 
@@ -136,9 +135,9 @@ pub fn extract<S: Read + Seek>(
 ///
 /// # Arguments
 ///
-/// * `window`: A reference to the GUI, obtained from a `#[tauri::command]` function
+/// * `reporter`: Where to send console output and events, like the GUI
 /// * `archive`: The zip archive to check
-fn has_toplevel<S: Read + Seek>(window: &Window, archive: &mut ZipArchive<S>) -> Result<bool, ZipError> {
+fn has_toplevel<S: Read + Seek>(reporter: &dyn Reporter, archive: &mut ZipArchive<S>) -> Result<bool, ZipError> {
     let mut toplevel_dir: Option<PathBuf> = None;
     if archive.len() < 2 {
         return Ok(false);
@@ -148,20 +147,20 @@ fn has_toplevel<S: Read + Seek>(window: &Window, archive: &mut ZipArchive<S>) ->
         let file = archive.by_index(i)?.mangled_name();
         if let Some(toplevel_dir) = &toplevel_dir {
             if !file.starts_with(toplevel_dir) {
-                ccprintln(window, "Found different toplevel directory");
+                ccprintln(reporter, "Found different toplevel directory");
                 return Ok(false);
             }
         } else {
             // First iteration
             let comp: PathBuf = file.components().take(1).collect();
             ccprintln!(
-                window,
+                reporter,
                 "Checking if path component {} is the only toplevel directory",
                 comp.display()
             );
             toplevel_dir = Some(comp);
         }
     }
-    ccprintln(window, "Found no other toplevel directory");
+    ccprintln(reporter, "Found no other toplevel directory");
     Ok(true)
 }
@@ -0,0 +1,213 @@
+use crate::{
+    commands::{
+        install_requirements_helper, start_match_helper, update_bot_pack_helper, BotPackError, InstallRequirementseError,
+        MatchInteractionError,
+    },
+    config_handles::{get_team_settings_helper, scan_for_bots_helper},
+    get_config_path,
+    match_handler::{supervisor, MatchHandlerError},
+    reporter::{Reporter, StdoutReporter},
+    rlbot::gateway_util,
+    settings::{MatchConfig, MiniMatchConfig, Team, TeamBotBundle},
+    setup_load_config, start_capture_pipe_reader, CommandError,
+};
+use std::{thread, time::Duration};
+use tauri::async_runtime::block_on as tauri_block_on;
+use thiserror::Error;
+
+const USAGE: &str = "Usage: rlbot-gui-cli <command> [arguments]
+
+Commands:
+  start-match                     Start a match with the teams and match settings that were last used in the GUI,
+                                  then wait until the match handler exits (Ctrl+C stops the match)
+  scan-bots [--json]              List the bots in the GUI's bot folders
+  update-botpack                  Download the botpack, or update it if it's already downloaded
+  install-requirements <cfg>      Install the Python packages that the bot with the given config file needs
+  kill-bots                       Stop RLBot (the bots themselves can only be stopped by whatever started the match,
+                                  like the GUI or Ctrl+C in start-match)
+  help                            Show this message";
+
+/// How often to check if the match handler is still running while waiting for a match to end
+const WAIT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Error)]
+pub enum CliError {
+    #[error("Unknown command \"{0}\"")]
+    UnknownCommand(String),
+    #[error("Missing argument {0}")]
+    MissingArgument(&'static str),
+    #[error("Unknown argument \"{0}\"")]
+    UnknownArgument(String),
+    #[error(transparent)]
+    MatchInteraction(#[from] MatchInteractionError),
+    #[error(transparent)]
+    MatchHandler(#[from] MatchHandlerError),
+    #[error(transparent)]
+    BotPack(#[from] BotPackError),
+    #[error(transparent)]
+    InstallRequirements(#[from] InstallRequirementseError),
+    #[error(transparent)]
+    Command(#[from] CommandError),
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+}
+
+/// Start a match with the teams and match settings that are saved in the GUI config
+///
+/// Returns if the match handler was used to start the match, as opposed to RLBot directly
+async fn start_match(reporter: &dyn Reporter) -> Result<bool, CliError> {
+    let teams = get_team_settings_helper(reporter).await;
+    let bot_list = [("blue_team", Team::Blue), ("orange_team", Team::Orange)]
+        .into_iter()
+        .flat_map(|(key, team)| {
+            teams
+                .get(key)
+                .into_iter()
+                .flatten()
+                .map(move |bundle| TeamBotBundle::from_bundle(bundle, team.clone()))
+        })
+        .collect();
+    let match_settings = MiniMatchConfig::from(MatchConfig::load(reporter).await);
+
+    start_match_helper(reporter, bot_list, match_settings, true, false).await?;
+
+    Ok(supervisor::status()?.running)
+}
+
+/// Block until the match handler exits, including after any restarts by the supervisor
+fn wait_for_match_handler() -> Result<(), CliError> {
+    while supervisor::status()?.running {
+        thread::sleep(WAIT_POLL_INTERVAL);
+    }
+
+    Ok(())
+}
+
+async fn scan_bots(reporter: &dyn Reporter, json: bool) -> Result<(), CliError> {
+    let bots = scan_for_bots_helper(reporter).await;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&bots)?);
+    } else {
+        for bot in bots {
+            println!("{} ({}): {}", bot.name, bot.runnable_type, bot.path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the exit code of pip
+async fn install_requirements(reporter: &dyn Reporter, config_path: &str) -> Result<i32, CliError> {
+    let result = install_requirements_helper(reporter, config_path).await?;
+
+    if result.exit_code == 0 {
+        ccprintln!(reporter, "Installed requirements for {config_path}");
+    } else {
+        ccprintln!(
+            reporter,
+            "Failed to install {} (exit code {})",
+            result.packages.join(", "),
+            result.exit_code
+        );
+    }
+
+    Ok(result.exit_code)
+}
+
+/// Stop RLBot
+///
+/// Only the match handler that started the bots knows about them, and it belongs to whichever process started the match,
+/// which a command can't reach since there's no way to talk to another process's match handler.
+/// A new match handler wouldn't know about any bots, so it isn't started, and the bots are left to their own match handler
+fn kill_bots(reporter: &dyn Reporter) {
+    gateway_util::kill_existing_processes(reporter);
+}
+
+fn run_command(reporter: &dyn Reporter, command: &str, args: &[String]) -> Result<i32, CliError> {
+    let unexpected = |allowed: &[&str]| args.iter().find(|arg| !allowed.contains(&arg.as_str())).cloned();
+
+    match command {
+        "start-match" => {
+            if let Some(arg) = unexpected(&[]) {
+                return Err(CliError::UnknownArgument(arg));
+            }
+
+            if tauri_block_on(start_match(reporter))? {
+                ccprintln(reporter, "Match started, waiting for the match handler to exit...");
+                wait_for_match_handler()?;
+            } else {
+                ccprintln(reporter, "Match started");
+            }
+        }
+        "scan-bots" => {
+            if let Some(arg) = unexpected(&["--json"]) {
+                return Err(CliError::UnknownArgument(arg));
+            }
+
+            tauri_block_on(scan_bots(reporter, args.iter().any(|arg| arg == "--json")))?;
+        }
+        "update-botpack" => {
+            if let Some(arg) = unexpected(&[]) {
+                return Err(CliError::UnknownArgument(arg));
+            }
+
+            ccprintln(reporter, tauri_block_on(update_bot_pack_helper(reporter))?);
+        }
+        "install-requirements" => {
+            let config_path = args.first().ok_or(CliError::MissingArgument("<cfg>"))?;
+            if let Some(arg) = args.get(1) {
+                return Err(CliError::UnknownArgument(arg.clone()));
+            }
+
+            return tauri_block_on(install_requirements(reporter, config_path));
+        }
+        "kill-bots" => {
+            if let Some(arg) = unexpected(&[]) {
+                return Err(CliError::UnknownArgument(arg));
+            }
+
+            kill_bots(reporter);
+        }
+        "help" | "--help" | "-h" => println!("{USAGE}"),
+        _ => return Err(CliError::UnknownCommand(command.to_owned())),
+    }
+
+    Ok(0)
+}
+
+/// Run the command line interface, using the same config file as the GUI and printing console output to the terminal
+///
+/// Returns the exit code for the process
+///
+/// # Arguments
+///
+/// * `args` - The command line arguments, not including the name of the program
+pub fn run(args: &[String]) -> i32 {
+    let Some((command, args)) = args.split_first() else {
+        println!("{USAGE}");
+        return 2;
+    };
+
+    let reporter = StdoutReporter;
+
+    println!("Config path: {}", get_config_path().display());
+    setup_load_config(&reporter);
+
+    if let Err(e) = start_capture_pipe_reader(reporter.boxed()) {
+        eprintln!("Couldn't capture the output of child processes: {e}");
+        return 1;
+    }
+
+    match run_command(&reporter, command, args) {
+        Ok(code) => code,
+        Err(e @ (CliError::UnknownCommand(_) | CliError::MissingArgument(_) | CliError::UnknownArgument(_))) => {
+            eprintln!("{e}\n\n{USAGE}");
+            2
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            1
+        }
+    }
+}
@@ -1,38 +1,50 @@
 use crate::{
+    ab_test::{self, AbTest, AbTestError},
     bot_management::{
         bot_creation::{
             bootstrap_python_bot, bootstrap_python_hivemind, bootstrap_rust_bot, bootstrap_scratch_bot, BoostrapError,
             CREATED_BOTS_FOLDER,
         },
-        downloader::{self, get_current_tag_name, ProgressBarUpdate},
+        cfg_helper,
+        downloader::{self, get_current_tag_name},
         zip_extract_fixed::{self, ExtractError},
     },
+    match_handler::{
+        self, issue_match_handler_command, protocol::gzip_encode, supervisor::MatchHandlerStatus, CreateHandler,
+        MatchHandlerError,
+    },
+    match_history::{self, HistoryError, HistoryQuery, MatchRecord},
+    ratings::{self, BotRating, Ladder, RatingError},
+    recordings::{self, RecordingError, RecordingMetadata, RecordingSummary, DEFAULT_RECORDING_RATE},
+    regression::{self, RegressionError, RegressionReport, RegressionSuite},
     rlbot::{
-        agents::runnable::Runnable,
+        agents::{executable_agent, runnable::Runnable},
         gateway_util,
+        packet_stream::{self, PacketStreamError},
         parsing::{
             agent_config_parser::BotLooksConfig,
             bot_config_bundle::{BotConfigBundle, RLBotCfgParseError, ScriptConfigBundle},
             match_settings_config_parser::{BoostAmount, GameMode, MaxScore, Rumble},
         },
         setup_manager,
+        socket::{self, CoreConnection, CoreError},
+        state_setting::{DesiredGameState, InvalidStateError},
     },
     settings::*,
     stories::{Bot, BotType, Challenge, City, Script},
+    tournament::{self, TournamentError, TournamentFormat, TournamentView},
     *,
 };
-use base64::{prelude::BASE64_STANDARD, Engine};
-use flate2::{write::GzEncoder, Compression};
 use futures_util::StreamExt;
 use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 use std::{
     collections::HashMap,
     fs::{create_dir_all, File},
-    io::{copy, Cursor, Write},
+    io::{copy, Cursor},
     path::Path,
     time::Instant,
 };
-use tauri::Window;
+use tauri::{api::dialog::FileDialogBuilder, Window};
 use thiserror::Error;
 use tokio::{
     fs::File as AsyncFile,
@@ -68,12 +80,12 @@ pub async fn check_rlbot_python() -> HashMap<String, bool> {
     dbg!(python_support)
 }
 
-fn ensure_bot_directory(window: &Window) -> PathBuf {
+fn ensure_bot_directory(reporter: &dyn Reporter) -> PathBuf {
     let bot_directory_path = get_content_folder().join(CREATED_BOTS_FOLDER);
 
     if !bot_directory_path.exists() {
         if let Err(e) = create_dir_all(&bot_directory_path) {
-            ccprintln!(window, "Error creating bot directory: {e}");
+            ccprintln!(reporter, "Error creating bot directory: {e}");
         }
     }
 
@@ -159,13 +171,21 @@ pub enum InstallRequirementseError {
 
 impl_serialize_from_display!(InstallRequirementseError);
 
-#[tauri::command]
-pub async fn install_requirements(window: Window, config_path: String) -> Result<PackageResult, InstallRequirementseError> {
-    let bundle = BotConfigBundle::minimal_from_path(Path::new(&config_path)).await?;
+/// Install the Python packages that the bot with the given config file needs
+///
+/// # Arguments
+///
+/// * `reporter` - Where to send console output and events, like the GUI
+/// * `config_path` - The path to the bot's config file
+pub async fn install_requirements_helper(
+    reporter: &dyn Reporter,
+    config_path: &str,
+) -> Result<PackageResult, InstallRequirementseError> {
+    let bundle = BotConfigBundle::minimal_from_path(Path::new(config_path)).await?;
 
     Ok(if let Some(file) = bundle.get_requirements_file() {
         let python = PYTHON_PATH.read().await;
-        let packages = bundle.get_missing_packages(&window, &*python);
+        let packages = bundle.get_missing_packages(reporter, &*python);
         let exit_code = spawn_capture_process_and_get_exit_code(
             &*python,
             ["-m", "pip", "install", "--no-warn-script-location", "-r", file],
@@ -177,6 +197,11 @@ pub async fn install_requirements(window: Window, config_path: String) -> Result
     })
 }
 
+#[tauri::command]
+pub async fn install_requirements(window: Window, config_path: String) -> Result<PackageResult, InstallRequirementseError> {
+    install_requirements_helper(&window, &config_path).await
+}
+
 #[tauri::command]
 pub async fn install_basic_packages(window: Window) -> PackageResult {
     let packages = PACKAGES.iter().map(ToString::to_string).collect::<Vec<String>>();
@@ -272,7 +297,7 @@ pub async fn run_command(window: Window, input: String) -> Result<(), RunCommand
 }
 
 async fn get_missing_packages_generic<T: Runnable + Send + Sync>(
-    window: &Window,
+    reporter: &dyn Reporter,
     runnables: Vec<T>,
 ) -> Vec<MissingPackagesUpdate> {
     if check_has_rlbot().await {
@@ -290,7 +315,7 @@ async fn get_missing_packages_generic<T: Runnable + Send + Sync>(
 
                         missing_packages.clone()
                     } else {
-                        let bot_missing_packages = runnable.get_missing_packages(window, &python_path);
+                        let bot_missing_packages = runnable.get_missing_packages(reporter, &python_path);
 
                         if bot_missing_packages.is_empty() {
                             warn = None;
@@ -381,8 +406,6 @@ pub enum BootstrapCustomPythonError {
     #[error("Couldn't download the custom python zip: {0}")]
     Download(#[from] reqwest::Error),
     #[error(transparent)]
-    EmitSignal(#[from] tauri::Error),
-    #[error(transparent)]
     Io(#[from] std::io::Error),
     #[error("Coudn't extract the zip: {0}")]
     ExtractZip(#[from] ExtractError),
@@ -419,28 +442,19 @@ pub async fn install_python(window: Window) -> Result<(), BootstrapCustomPythonE
 
             if last_update.elapsed().as_secs_f32() >= 0.1 {
                 let progress = bytes.len() as f64 / total_size * 100.0;
-                window.emit(
-                    UPDATE_DOWNLOAD_PROGRESS_SIGNAL,
-                    ProgressBarUpdate::new(progress, "Downloading zip...".to_owned()),
-                )?;
+                window.progress(progress, "Downloading zip...");
                 last_update = Instant::now();
             }
         }
 
-        window.emit(
-            UPDATE_DOWNLOAD_PROGRESS_SIGNAL,
-            ProgressBarUpdate::new(100., "Writing zip to disk...".to_owned()),
-        )?;
+        window.progress(100., "Writing zip to disk...");
 
         let mut file = File::create(&file_path)?;
         let mut content = Cursor::new(bytes);
         copy(&mut content, &mut file)?;
     }
 
-    window.emit(
-        UPDATE_DOWNLOAD_PROGRESS_SIGNAL,
-        ProgressBarUpdate::new(100., "Extracting zip...".to_owned()),
-    )?;
+    window.progress(100., "Extracting zip...");
 
     // Extract the zip file
     zip_extract_fixed::extract(&window, File::open(&file_path)?, folder_destination.as_path(), false, false)?;
@@ -506,25 +520,29 @@ pub async fn download_bot_pack(window: Window) -> Result<String, BotPackError> {
     })
 }
 
-#[tauri::command]
-pub async fn update_bot_pack(window: Window) -> Result<String, BotPackError> {
+/// Update the botpack, or download it if it's missing or too far out of date
+///
+/// # Arguments
+///
+/// * `reporter` - Where to send console output and events, like the GUI
+pub async fn update_bot_pack_helper(reporter: &dyn Reporter) -> Result<String, BotPackError> {
     let botpack_location = get_content_folder().join(BOTPACK_FOLDER).to_string_lossy().to_string();
     let botpack_status =
-        downloader::update_bot_pack(&window, BOTPACK_REPO_OWNER, BOTPACK_REPO_NAME, &botpack_location).await;
+        downloader::update_bot_pack(reporter, BOTPACK_REPO_OWNER, BOTPACK_REPO_NAME, &botpack_location).await;
 
     Ok(match botpack_status {
         downloader::BotpackStatus::Skipped(message) => message,
         downloader::BotpackStatus::Success(message) => {
             // Configure the folder settings
-            BOT_FOLDER_SETTINGS.write().await.add_folder(&window, botpack_location)?;
+            BOT_FOLDER_SETTINGS.write().await.add_folder(reporter, botpack_location)?;
             message
         }
         downloader::BotpackStatus::RequiresFullDownload => {
             // We need to download the botpack
             // the most likely cause is the botpack not existing in the first place
-            match downloader::download_repo(&window, BOTPACK_REPO_OWNER, BOTPACK_REPO_NAME, &botpack_location, true).await {
+            match downloader::download_repo(reporter, BOTPACK_REPO_OWNER, BOTPACK_REPO_NAME, &botpack_location, true).await {
                 downloader::BotpackStatus::Success(message) => {
-                    BOT_FOLDER_SETTINGS.write().await.add_folder(&window, botpack_location)?;
+                    BOT_FOLDER_SETTINGS.write().await.add_folder(reporter, botpack_location)?;
                     message
                 }
                 downloader::BotpackStatus::Skipped(message) => message,
@@ -534,6 +552,11 @@ pub async fn update_bot_pack(window: Window) -> Result<String, BotPackError> {
     })
 }
 
+#[tauri::command]
+pub async fn update_bot_pack(window: Window) -> Result<String, BotPackError> {
+    update_bot_pack_helper(&window).await
+}
+
 #[tauri::command]
 pub async fn update_map_pack(window: Window) -> Result<String, BotPackError> {
     let mappack_location = get_content_folder().join(MAPPACK_FOLDER);
@@ -585,114 +608,14 @@ pub async fn save_launcher_settings(window: Window, settings: LauncherConfig) {
     settings.write_to_file(&window).await;
 }
 
-#[derive(Debug, Error)]
-pub enum MatchHandlerError {
-    #[error("Couldn't start match handler: {0}")]
-    Command(#[from] CommandError),
-    #[error(transparent)]
-    Io(#[from] std::io::Error),
-    #[error("Couldn't find STDIN in match handler")]
-    NoStdin,
-    #[error("Failed to write to match handler's STDIN")]
-    NoWrite,
-    #[error("Mutex {0} was poisoned")]
-    Poisoned(&'static str),
-}
-
-impl_serialize_from_display!(MatchHandlerError);
-
-/// Starts the match handler, which is written in Python so it can use the `RLBot` package (also written in Python)
-///
-/// Returns None if it fails, otherwise returns pipe for the child process's stdin
-///
-/// # Arguments
-///
-/// * `window` - A reference to the GUI, obtained from a `#[tauri::command]` function
-fn create_match_handler<S: AsRef<OsStr>>(
-    use_pipe: bool,
-    python_path: S,
-) -> Result<(String, (Child, ChildStdin)), MatchHandlerError> {
-    let mut child = get_maybe_capture_command(
-        &python_path,
-        [
-            "-u",
-            "-c",
-            "from rlbot_smh.match_handler import listen; listen(is_raw_json=False)",
-        ],
-        use_pipe,
-    )?
-    .stdin(Stdio::piped())
-    .spawn()?;
-
-    let stdin = child.stdin.take().ok_or(MatchHandlerError::NoStdin)?;
-    Ok((python_path.as_ref().to_string_lossy().to_string(), (child, stdin)))
-}
-
-enum CreateHandler {
-    /// The bool is whether is not a pipe should be attached to the process
-    Yes(bool),
-    No,
-}
-
-/// Use flate2 to encode a string with gzip then encode the binary with base64 back into a string
-fn gzip_encode(s: &str) -> Result<String, MatchHandlerError> {
-    let mut e = GzEncoder::new(Vec::new(), Compression::best());
-    e.write_all(s.as_bytes())?;
-    Ok(BASE64_STANDARD.encode(e.finish()?))
+#[tauri::command]
+pub async fn get_match_handler_settings(window: Window) -> MatchHandlerConfig {
+    MatchHandlerConfig::load(&window).await
 }
 
-/// Send a command to the match handler
-///
-/// # Arguments
-///
-/// * `window` - A reference to the GUI, obtained from a `#[tauri::command]` function
-/// * `command` - The command to send to the match handler - can be in multiple parts, for passing arguments
-/// * `create_handler` - If the match handler should be started if it's down
-fn issue_match_handler_command<S: AsRef<OsStr>>(
-    window: &Window,
-    command_parts: &[String],
-    mut create_handler: CreateHandler,
-    python_path: S,
-) -> Result<(), MatchHandlerError> {
-    let mut command_lock = MATCH_HANDLER_STDIN
-        .lock()
-        .map_err(|_| MatchHandlerError::Poisoned("MATCH_HANDLER_STDIN"))?;
-    let (used_py_path, match_handler_stdin) = &mut *command_lock;
-
-    if match_handler_stdin.is_none() {
-        let CreateHandler::Yes(use_pipe) = create_handler else {
-            ccprintln(window, "Not issuing command to handler as it's down and I was told to not start it");
-            return Ok(());
-        };
-
-        ccprintln(window, "Starting match handler!");
-        let (py_path, stdin) = create_match_handler(use_pipe, &python_path)?;
-
-        ccprintln!(window, "Waiting for match handler to start up...");
-        std::thread::sleep(std::time::Duration::from_secs(1));
-        ccprintln!(window, "Done waiting for match handler to start up");
-
-        *match_handler_stdin = Some(stdin);
-        *used_py_path = py_path;
-        create_handler = CreateHandler::No;
-    }
-
-    let command = gzip_encode(&format!("{} | ", command_parts.join(" | ")))?;
-    println!("Issuing command: {command}");
-    let (_, stdin) = match_handler_stdin.as_mut().ok_or(MatchHandlerError::NoStdin)?;
-
-    if stdin.write_fmt(format_args!("{command}\n")).is_err() {
-        drop(match_handler_stdin.take());
-        if matches!(create_handler, CreateHandler::Yes(_)) {
-            ccprintln(window, "Failed to write to match handler, trying to restart...");
-            issue_match_handler_command(window, command_parts, create_handler, python_path)
-        } else {
-            Err(MatchHandlerError::NoWrite)
-        }
-    } else {
-        stdin.flush()?;
-        Ok(())
-    }
+#[tauri::command]
+pub async fn save_match_handler_settings(window: Window, settings: MatchHandlerConfig) {
+    settings.write_to_file(&window).await;
 }
 
 #[derive(Debug, Error)]
@@ -705,6 +628,14 @@ pub enum MatchInteractionError {
     Serde(#[from] serde_json::Error),
     #[error(transparent)]
     MapSetup(#[from] MapSetupError),
+    #[error(transparent)]
+    Core(#[from] CoreError),
+    #[error(transparent)]
+    InvalidState(#[from] InvalidStateError),
+    #[error(transparent)]
+    Recording(#[from] RecordingError),
+    #[error(transparent)]
+    Cfg(#[from] cfg_helper::Error),
 }
 
 impl_serialize_from_display!(MatchInteractionError);
@@ -713,14 +644,14 @@ impl_serialize_from_display!(MatchInteractionError);
 ///
 /// # Arguments
 ///
-/// * `window` - A reference to the GUI, obtained from a `#[tauri::command]` function
-async fn pre_start_match(window: &Window) -> Result<(), MatchInteractionError> {
-    let port = gateway_util::find_existing_process(window);
+/// * `reporter` - Where to send console output and events, like the GUI
+async fn pre_start_match(reporter: &dyn Reporter) -> Result<(), MatchInteractionError> {
+    let port = gateway_util::find_existing_process(reporter);
     let rl_is_running = setup_manager::is_rocket_league_running(port.unwrap_or(gateway_util::IDEAL_RLBOT_PORT))
         .map_err(MatchInteractionError::RLNoBot)?;
 
     ccprintln(
-        window,
+        reporter,
         format!(
             "Rocket League is {}",
             if rl_is_running {
@@ -733,24 +664,26 @@ async fn pre_start_match(window: &Window) -> Result<(), MatchInteractionError> {
 
     if port.is_some() {
         // kill the current bots if they're running
-        kill_bots(window.clone()).await?;
+        kill_bots_helper(reporter).await?;
 
         // kill RLBot if it's running but Rocket League isn't
         if !rl_is_running {
-            gateway_util::kill_existing_processes(window);
+            gateway_util::kill_existing_processes(reporter);
         }
     }
 
     Ok(())
 }
 
-async fn get_start_match_args_arr(
-    window: &Window,
+pub async fn get_start_match_args_arr(
+    reporter: &dyn Reporter,
     bot_list: Vec<TeamBotBundle>,
     match_settings: MiniMatchConfig,
 ) -> Result<[String; 6], MatchInteractionError> {
-    let launcher_settings = LauncherConfig::load(window).await;
+    let launcher_settings = LauncherConfig::load(reporter).await;
     let match_settings = match_settings.setup_for_start_match(&BOT_FOLDER_SETTINGS.read().await.folders)?;
+    // The match handler can only start bots through a python_file, so executable bots get a generated one
+    let bot_list = executable_agent::with_launchers(bot_list).await?;
 
     Ok([
         "start_match".to_owned(),
@@ -773,27 +706,62 @@ pub async fn get_start_match_arguments(
         get_start_match_args_arr(&window, bot_list, match_settings).await?.join(" | ")
     );
     println!("Raw JSON command: {raw_string}");
-    Ok(gzip_encode(&raw_string)?)
+    Ok(gzip_encode(&raw_string).map_err(MatchHandlerError::from)?)
 }
 
 /// Starts a match via the match handler with the given settings
 ///
 /// # Arguments
 ///
-/// * `window` - A reference to the GUI, obtained from a `#[tauri::command]` function
+/// * `reporter` - Where to send console output and events, like the GUI
 /// * `bot_list` - A list of bots and their settings to use in the match
 /// * `match_settings` - The various match settings to use in the match, including scripts (only the path), mutators, game map, etc.
-async fn start_match_helper(
-    window: &Window,
+/// * `automated` - If the match is part of an A/B test or a regression suite, which keeps it out of the ratings
+pub async fn start_match_helper(
+    reporter: &dyn Reporter,
+    bot_list: Vec<TeamBotBundle>,
+    match_settings: MiniMatchConfig,
+    use_pipe: bool,
+    automated: bool,
+) -> Result<(), MatchInteractionError> {
+    pre_start_match(reporter).await?;
+
+    if let Some(conflict) = TickRateConflict::find(&bot_list) {
+        ccprintln(reporter, conflict.to_string());
+    }
+
+    let tracked_bots = bot_list.clone();
+    let tracked_settings = match_settings.clone();
+    launch_match(reporter, bot_list, match_settings, use_pipe).await?;
+
+    if let Err(e) = match_history::start_tracking(reporter.boxed(), tracked_bots, tracked_settings, automated) {
+        ccprintln!(reporter, "Couldn't start tracking the match for the match history: {e}");
+    }
+
+    Ok(())
+}
+
+async fn launch_match(
+    reporter: &dyn Reporter,
     bot_list: Vec<TeamBotBundle>,
     match_settings: MiniMatchConfig,
     use_pipe: bool,
 ) -> Result<(), MatchInteractionError> {
-    pre_start_match(window).await?;
+    // Matches with only humans and Psyonix bots can be started without Python, if RLBot is already running.
+    // Launching RLBot and Rocket League is still done by the match handler
+    if socket::can_start_natively(&bot_list, &match_settings) {
+        if let Some(connection) = CoreConnection::connect_existing() {
+            ccprintln(reporter, "Starting match directly through RLBot");
+            connection?.start_match(&bot_list, &match_settings)?;
+            return Ok(());
+        }
 
-    let args = get_start_match_args_arr(window, bot_list, match_settings).await?;
+        ccprintln(reporter, "RLBot isn't running yet, so the match handler has to start it");
+    }
+
+    let args = get_start_match_args_arr(reporter, bot_list, match_settings).await?;
 
-    issue_match_handler_command(window, &args, CreateHandler::Yes(use_pipe), &*PYTHON_PATH.read().await)?;
+    issue_match_handler_command::<(), _>(reporter, &args, CreateHandler::Yes(use_pipe), &*PYTHON_PATH.read().await).await?;
 
     Ok(())
 }
@@ -804,7 +772,8 @@ pub async fn start_match(
     bot_list: Vec<TeamBotBundle>,
     match_settings: MiniMatchConfig,
 ) -> Result<(), MatchInteractionError> {
-    if let Err(error) = start_match_helper(&window, bot_list, match_settings, USE_PIPE.load(Ordering::Relaxed)).await {
+    if let Err(error) = start_match_helper(&window, bot_list, match_settings, USE_PIPE.load(Ordering::Relaxed), false).await
+    {
         if let Err(e) = window.emit("match-start-failed", ()) {
             ccprintln!(&window, "Failed to emit match-start-failed: {e}");
         }
@@ -817,65 +786,373 @@ pub async fn start_match(
     }
 }
 
+/// Check if the bots in a match want different maximum tick rates, so that the GUI can warn about it before starting
+#[tauri::command]
+pub async fn check_tick_rate_preferences(bot_list: Vec<TeamBotBundle>) -> Option<TickRateConflict> {
+    TickRateConflict::find(&bot_list)
+}
+
+/// Tell the match handler to kill the bots, if it's running
+///
+/// # Arguments
+///
+/// * `reporter` - Where to send console output and events, like the GUI
+pub async fn kill_bots_helper(reporter: &dyn Reporter) -> Result<(), MatchHandlerError> {
+    issue_match_handler_command::<(), _>(reporter, &["kill_bots".to_owned()], CreateHandler::No, "").await?;
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn kill_bots(window: Window) -> Result<(), MatchHandlerError> {
-    issue_match_handler_command(&window, &["kill_bots".to_owned()], CreateHandler::No, "")
+    kill_bots_helper(&window).await
 }
 
 #[tauri::command]
 pub async fn shut_down_match_handler() -> Result<(), MatchHandlerError> {
-    let mut handler_lock = MATCH_HANDLER_STDIN
-        .lock()
-        .map_err(|_| MatchHandlerError::Poisoned("MATCH_HANDLER_STDIN"))?;
+    match_handler::supervisor::shut_down()
+}
 
-    // Send the command to the match handler to shut down
-    if let (_, Some((_, stdin))) = &mut *handler_lock {
-        let kill_bots_command = gzip_encode("shut_down | ")?;
-        stdin.write_fmt(format_args!("{kill_bots_command}\n"))?;
-        stdin.flush()?;
-    }
+#[tauri::command]
+pub async fn get_match_handler_status() -> Result<MatchHandlerStatus, MatchHandlerError> {
+    match_handler::supervisor::status()
+}
 
-    // Drop stdin
-    handler_lock.1 = None;
+#[tauri::command]
+pub async fn fetch_game_tick_packet_json(window: Window) -> Result<Option<GameTickPacket>, MatchHandlerError> {
+    packet_stream::latest_game_tick_packet(&window).await
+}
 
-    // Wait 15 seconds for the child to exit on it's own, then kill it if it's still running
-    if let (_, Some((child, _))) = &mut *handler_lock {
-        let start_time = Instant::now();
-        let pause_duration = Duration::from_secs_f32(0.25);
+#[tauri::command]
+pub async fn subscribe_game_tick_packets(window: Window, rate: f32) -> Result<(), PacketStreamError> {
+    packet_stream::subscribe(Box::new(window), rate)
+}
 
-        while start_time.elapsed() < Duration::from_secs(15) {
-            if let Ok(Some(_)) = child.try_wait() {
-                return Ok(());
-            }
+#[tauri::command]
+pub async fn unsubscribe_game_tick_packets() -> Result<(), PacketStreamError> {
+    packet_stream::unsubscribe()
+}
 
-            thread::sleep(pause_duration);
+/// Validate a desired game state and set it, directly through RLBot if possible and otherwise through the match handler
+///
+/// # Arguments
+///
+/// * `reporter` - Where to send console output and events, like the GUI
+/// * `state` - The state to set
+async fn set_state_helper(reporter: &dyn Reporter, state: &DesiredGameState) -> Result<(), MatchInteractionError> {
+    // The packet is only used to check the car and boost pad indices, so the state can still be set without it
+    let packet = packet_stream::latest_game_tick_packet(reporter).await.ok().flatten();
+    state.validate(packet.as_ref())?;
+
+    if let Some(result) = socket::set_state_existing(state) {
+        match result {
+            Ok(()) => return Ok(()),
+            Err(e) => ccprintln!(reporter, "Couldn't set state through RLBot, trying the match handler: {e}"),
         }
-
-        child.kill()?;
-        child.wait()?;
     }
 
+    issue_match_handler_command::<(), _>(
+        reporter,
+        &["set_state".to_owned(), serde_json::to_string(state)?],
+        CreateHandler::No,
+        &*PYTHON_PATH.read().await,
+    )
+    .await?;
+
     Ok(())
 }
 
 #[tauri::command]
-pub async fn fetch_game_tick_packet_json(window: Window) -> Result<(), MatchHandlerError> {
-    issue_match_handler_command(
-        &window,
-        &["fetch_gtp".to_owned()],
-        CreateHandler::No,
-        &*PYTHON_PATH.read().await,
+pub async fn set_state(window: Window, state: DesiredGameState) -> Result<(), MatchInteractionError> {
+    set_state_helper(&window, &state).await
+}
+
+#[tauri::command]
+pub async fn start_recording(
+    window: Window,
+    name: String,
+    bot_list: Vec<TeamBotBundle>,
+    match_settings: MiniMatchConfig,
+    rate: Option<f32>,
+) -> Result<(), RecordingError> {
+    let metadata = RecordingMetadata {
+        bots: bot_list,
+        match_settings,
+        started_at: recordings::unix_time(),
+    };
+
+    recordings::start_recording(Box::new(window), name, metadata, rate.unwrap_or(DEFAULT_RECORDING_RATE))
+}
+
+#[tauri::command]
+pub async fn stop_recording() -> Result<String, RecordingError> {
+    recordings::stop_recording()
+}
+
+#[tauri::command]
+pub async fn get_current_recording() -> Result<Option<String>, RecordingError> {
+    recordings::current_recording()
+}
+
+#[tauri::command]
+pub async fn list_recordings(window: Window) -> Result<Vec<RecordingSummary>, RecordingError> {
+    recordings::list_recordings(&window)
+}
+
+#[tauri::command]
+pub async fn rename_recording(old_name: String, new_name: String) -> Result<(), RecordingError> {
+    recordings::rename_recording(&old_name, &new_name)
+}
+
+#[tauri::command]
+pub async fn delete_recording(name: String) -> Result<(), RecordingError> {
+    recordings::delete_recording(&name)
+}
+
+#[tauri::command]
+pub async fn export_recording(window: Window, name: String) {
+    FileDialogBuilder::new()
+        .add_filter("Match Recording", &["ndjson"])
+        .set_file_name(&format!("{}.ndjson", name.trim()))
+        .save_file(move |path| {
+            let Some(path) = path else {
+                return;
+            };
+
+            if let Err(e) = recordings::export_recording(&name, &path) {
+                ccprintln!(&window, "Error exporting recording: {e}");
+            }
+        });
+}
+
+#[tauri::command]
+pub async fn open_recording(name: String) -> Result<RecordingSummary, RecordingError> {
+    recordings::open_recording(&name)
+}
+
+#[tauri::command]
+pub async fn close_recording() -> Result<(), RecordingError> {
+    recordings::close_recording()
+}
+
+#[tauri::command]
+pub async fn get_recording_frame(index: usize) -> Result<GameTickPacket, RecordingError> {
+    recordings::get_frame(index)
+}
+
+#[tauri::command]
+pub async fn apply_recording_frame(window: Window, index: usize) -> Result<(), MatchInteractionError> {
+    let frame = recordings::get_frame(index)?;
+    set_state_helper(&window, &DesiredGameState::from_packet(&frame)).await
+}
+
+#[tauri::command]
+pub async fn get_match_history(window: Window, query: HistoryQuery) -> Result<Vec<MatchRecord>, HistoryError> {
+    match_history::query_history(&window, &query)
+}
+
+#[tauri::command]
+pub async fn get_match_record(window: Window, id: String) -> Result<MatchRecord, HistoryError> {
+    match_history::get_record(&window, &id)
+}
+
+#[tauri::command]
+pub async fn delete_match_record(window: Window, id: String) -> Result<(), HistoryError> {
+    match_history::delete_record(&window, &id)
+}
+
+#[tauri::command]
+pub async fn create_tournament(
+    name: String,
+    format: TournamentFormat,
+    bots: Vec<BotConfigBundle>,
+    match_settings: MiniMatchConfig,
+) -> Result<TournamentView, TournamentError> {
+    tournament::create_tournament(&name, format, &bots, match_settings)
+}
+
+#[tauri::command]
+pub async fn list_tournaments(window: Window) -> Result<Vec<TournamentView>, TournamentError> {
+    tournament::list_tournaments(&window)
+}
+
+#[tauri::command]
+pub async fn get_tournament(name: String) -> Result<TournamentView, TournamentError> {
+    tournament::get_tournament(&name)
+}
+
+#[tauri::command]
+pub async fn start_tournament(window: Window, name: String) -> Result<(), TournamentError> {
+    tournament::start_tournament(Box::new(window), &name, USE_PIPE.load(Ordering::Relaxed))
+}
+
+#[tauri::command]
+pub async fn stop_tournament() -> Result<String, TournamentError> {
+    tournament::stop_tournament()
+}
+
+#[tauri::command]
+pub async fn delete_tournament(name: String) -> Result<(), TournamentError> {
+    tournament::delete_tournament(&name)
+}
+
+#[tauri::command]
+pub async fn get_leaderboard(game_mode: GameMode, team_size: usize) -> Result<Vec<BotRating>, RatingError> {
+    ratings::get_leaderboard(game_mode, team_size)
+}
+
+#[tauri::command]
+pub async fn get_rating_ladders() -> Result<Vec<Ladder>, RatingError> {
+    ratings::get_ladders()
+}
+
+#[tauri::command]
+pub async fn recompute_ratings(window: Window) -> Result<(), RatingError> {
+    ratings::recompute_ratings(&window)
+}
+
+#[tauri::command]
+pub async fn export_ratings(window: Window, game_mode: GameMode, team_size: usize) {
+    FileDialogBuilder::new()
+        .add_filter("CSV", &["csv"])
+        .set_file_name(&format!("{game_mode:?} {team_size}v{team_size} ratings.csv"))
+        .save_file(move |path| {
+            let Some(path) = path else {
+                return;
+            };
+
+            if let Err(e) = ratings::export_csv(game_mode, team_size, &path) {
+                ccprintln!(&window, "Error exporting ratings: {e}");
+            }
+        });
+}
+
+#[tauri::command]
+pub async fn create_regression_suite(
+    name: String,
+    bot: BotConfigBundle,
+    opponents: Vec<BotConfigBundle>,
+    games_per_opponent: u32,
+    match_settings: MiniMatchConfig,
+) -> Result<RegressionSuite, RegressionError> {
+    regression::create_suite(&name, &bot, &opponents, games_per_opponent, match_settings)
+}
+
+#[tauri::command]
+pub async fn list_regression_suites() -> Result<Vec<RegressionSuite>, RegressionError> {
+    regression::list_suites()
+}
+
+#[tauri::command]
+pub async fn get_regression_suite(name: String) -> Result<RegressionSuite, RegressionError> {
+    regression::get_suite(&name)
+}
+
+#[tauri::command]
+pub async fn delete_regression_suite(name: String) -> Result<(), RegressionError> {
+    regression::delete_suite(&name)
+}
+
+#[tauri::command]
+pub async fn start_regression_suite(window: Window, name: String) -> Result<(), RegressionError> {
+    regression::start_suite(Box::new(window), &name, USE_PIPE.load(Ordering::Relaxed))
+}
+
+#[tauri::command]
+pub async fn stop_regression_suite() -> Result<String, RegressionError> {
+    regression::stop_suite()
+}
+
+#[tauri::command]
+pub async fn get_running_regression_suite() -> Result<Option<String>, RegressionError> {
+    regression::current_suite()
+}
+
+#[tauri::command]
+pub async fn list_regression_reports(name: String) -> Result<Vec<String>, RegressionError> {
+    regression::list_reports(&name)
+}
+
+#[tauri::command]
+pub async fn get_regression_report(name: String, id: String) -> Result<RegressionReport, RegressionError> {
+    regression::get_report(&name, &id)
+}
+
+#[tauri::command]
+pub async fn get_regression_baseline(name: String) -> Result<Option<RegressionReport>, RegressionError> {
+    regression::get_baseline(&name)
+}
+
+#[tauri::command]
+pub async fn set_regression_baseline(name: String, id: String) -> Result<(), RegressionError> {
+    regression::set_baseline(&name, &id)
+}
+
+#[tauri::command]
+pub async fn clear_regression_baseline(name: String) -> Result<(), RegressionError> {
+    regression::clear_baseline(&name)
+}
+
+#[tauri::command]
+pub async fn export_regression_report(window: Window, name: String, id: String) {
+    FileDialogBuilder::new()
+        .add_filter("Markdown", &["md"])
+        .add_filter("JSON", &["json"])
+        .set_file_name(&format!("{} {id}.md", name.trim()))
+        .save_file(move |path| {
+            let Some(path) = path else {
+                return;
+            };
+
+            if let Err(e) = regression::export_report(&name, &id, &path) {
+                ccprintln!(&window, "Error exporting regression report: {e}");
+            }
+        });
+}
+
+#[tauri::command]
+pub async fn start_ab_test(
+    window: Window,
+    config_path: String,
+    revision_a: String,
+    revision_b: String,
+    pairs: u32,
+    match_settings: MiniMatchConfig,
+) -> Result<String, AbTestError> {
+    ab_test::start_ab_test(
+        Box::new(window),
+        &config_path,
+        &revision_a,
+        &revision_b,
+        pairs,
+        match_settings,
+        USE_PIPE.load(Ordering::Relaxed),
     )
+    .await
 }
 
 #[tauri::command]
-pub async fn set_state(window: Window, state: HashMap<String, serde_json::Value>) -> Result<(), MatchInteractionError> {
-    Ok(issue_match_handler_command(
-        &window,
-        &["set_state".to_owned(), serde_json::to_string(&state)?],
-        CreateHandler::No,
-        &*PYTHON_PATH.read().await,
-    )?)
+pub async fn stop_ab_test() -> Result<String, AbTestError> {
+    ab_test::stop_ab_test()
+}
+
+#[tauri::command]
+pub async fn get_running_ab_test() -> Result<Option<String>, AbTestError> {
+    ab_test::current_ab_test()
+}
+
+#[tauri::command]
+pub async fn list_ab_tests(window: Window) -> Result<Vec<AbTest>, AbTestError> {
+    ab_test::list_ab_tests(&window)
+}
+
+#[tauri::command]
+pub async fn get_ab_test(id: String) -> Result<AbTest, AbTestError> {
+    ab_test::get_ab_test(&id)
+}
+
+#[tauri::command]
+pub async fn delete_ab_test(id: String) -> Result<(), AbTestError> {
+    ab_test::delete_ab_test(&id)
 }
 
 #[tauri::command]
@@ -899,12 +1176,9 @@ pub async fn spawn_car_for_viewing(
         launcher_settings.rocket_league_exe_path.unwrap_or_default(),
     ];
 
-    Ok(issue_match_handler_command(
-        &window,
-        &args,
-        CreateHandler::Yes(true),
-        &*PYTHON_PATH.read().await,
-    )?)
+    issue_match_handler_command::<(), _>(&window, &args, CreateHandler::Yes(true), &*PYTHON_PATH.read().await).await?;
+
+    Ok(())
 }
 
 #[tauri::command]
@@ -924,6 +1198,9 @@ fn make_human_config(team: Team) -> TeamBotBundle {
         skill: 1.0,
         runnable_type: "human".to_owned(),
         path: None,
+        supports_early_start: false,
+        maximum_tick_rate_preference: None,
+        bot_parameters: HashMap::new(),
     }
 }
 
@@ -963,6 +1240,9 @@ fn rlbot_to_player_config(player: &Bot, team: Team, botpack_root: &Path) -> Team
         skill: 1.0,
         runnable_type: "rlbot".to_owned(),
         path: Some(collapse_path(player.path.as_ref(), botpack_root).unwrap_or_default()),
+        supports_early_start: false,
+        maximum_tick_rate_preference: None,
+        bot_parameters: HashMap::new(),
     }
 }
 
@@ -979,6 +1259,9 @@ fn pysonix_to_player_config(player: &Bot, team: Team) -> TeamBotBundle {
         skill: player.skill.unwrap_or(1.0),
         runnable_type: "psyonix".to_owned(),
         path: None,
+        supports_early_start: false,
+        maximum_tick_rate_preference: None,
+        bot_parameters: HashMap::new(),
     }
 }
 
@@ -1136,17 +1419,17 @@ impl_serialize_from_display!(RunChallengeError);
 ///
 /// # Arguments
 ///
-/// * `window` - A reference to the GUI, obtained from a `#[tauri::command]` function
+/// * `reporter` - Where to send console output and events, like the GUI
 /// * `story_save` - The save state of the story, containing all the information about the story
 /// * `challenge_id` - The ID of the challenge to run
 /// * `picked_teammates` - The teammates that were picked by the human for teammates to use in the challenge
 async fn run_challenge(
-    window: &Window,
+    reporter: &dyn Reporter,
     save_state: &StoryState,
     challenge_id: String,
     picked_teammates: &[String],
 ) -> Result<(), RunChallengeError> {
-    pre_start_match(window).await?;
+    pre_start_match(reporter).await?;
 
     let story_settings = save_state.get_story_settings();
 
@@ -1172,7 +1455,7 @@ async fn run_challenge(
         save_state.get_upgrades(),
         make_script_configs(&challenge, &all_scripts, botpack_root.as_path()),
     );
-    let launcher_prefs = LauncherConfig::load(window).await;
+    let launcher_prefs = LauncherConfig::load(reporter).await;
 
     let args = [
         "launch_challenge".to_owned(),
@@ -1191,7 +1474,8 @@ async fn run_challenge(
 
     println!("Issuing command: {} | ", args.join(" | "));
 
-    issue_match_handler_command(window, &args, CreateHandler::Yes(true), &*PYTHON_PATH.read().await)
+    issue_match_handler_command::<(), _>(reporter, &args, CreateHandler::Yes(true), &*PYTHON_PATH.read().await)
+        .await
         .map_err(Into::<MatchInteractionError>::into)?;
 
     Ok(())
@@ -1,15 +1,23 @@
 use crate::{
     bot_management::{
+        bot_index::{self, BotIndexError},
         cfg_helper::{self, save_cfg},
         downloader::MapPackUpdater,
     },
     custom_maps,
+    loadout_generator::{self, GeneratedLoadout, LoadoutError, LoadoutMode},
+    loadout_library::{self, LoadoutLibraryError, LoadoutPreset, LOADOUT_EXTENSION, LOOKS_CFG_EXTENSION},
+    presets::{self, MatchPreset, PresetError, PRESET_EXTENSION},
+    random_match::{self, GeneratedMatch, MatchConstraints, RandomMatchError},
+    reporter::Reporter,
     rlbot::{
         agents::runnable::Runnable,
         parsing::{
             agent_config_parser::BotLooksConfig,
             bot_config_bundle::{BotConfigBundle, RLBotCfgParseError, ScriptConfigBundle},
-            directory_scanner::{scan_directory_for_bot_configs, scan_directory_for_script_configs},
+            bot_config_linter::{self, LintReport},
+            config_diagnostics::ConfigDiagnostic,
+            item_catalog::{Item, ItemError, ItemSlot, ITEM_CATALOG},
             match_settings_config_parser::MatchOptions,
         },
     },
@@ -19,8 +27,7 @@ use crate::{
 };
 use configparser::ini::Ini;
 use futures_util::future::join_all;
-use glob::glob;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
     fs::{create_dir_all, read_to_string},
@@ -47,8 +54,8 @@ fn set_gui_config_to_default(conf: &mut Ini) {
 ///
 /// # Arguments
 ///
-/// * `window` - A reference to the GUI, obtained from a `#[tauri::command]` function
-pub async fn load_gui_config(window: &Window) -> Ini {
+/// * `reporter` - Where to send console output and events, like the GUI
+pub async fn load_gui_config(reporter: &dyn Reporter) -> Ini {
     let mut conf = Ini::new();
     conf.set_comment_symbols(&[';']);
     let config_path = get_config_path();
@@ -57,20 +64,20 @@ pub async fn load_gui_config(window: &Window) -> Ini {
         match async_fs::read_to_string(config_path).await {
             Ok(s) => {
                 if let Err(e) = conf.read(s) {
-                    ccprintln!(window, "Error reading config file: {e}");
+                    ccprintln!(reporter, "Error reading config file: {e}");
                 }
             }
-            Err(e) => ccprintln!(window, "Error reading config file: {e}"),
+            Err(e) => ccprintln!(reporter, "Error reading config file: {e}"),
         }
     } else {
         if let Err(e) = create_dir_all(config_path.parent().unwrap()) {
-            ccprintln!(window, "Error creating config directory: {e}");
+            ccprintln!(reporter, "Error creating config directory: {e}");
         }
 
         set_gui_config_to_default(&mut conf);
 
         if let Err(e) = save_cfg(&conf, config_path).await {
-            ccprintln!(window, "Error writing config file: {e}");
+            ccprintln!(reporter, "Error writing config file: {e}");
         }
     }
 
@@ -81,24 +88,24 @@ pub async fn load_gui_config(window: &Window) -> Ini {
 ///
 /// # Arguments
 ///
-/// * `window` - A reference to the GUI, obtained from a `#[tauri::command]` function
-pub fn load_gui_config_sync(window: &Window) -> Ini {
+/// * `reporter` - Where to send console output and events, like the GUI
+pub fn load_gui_config_sync(reporter: &dyn Reporter) -> Ini {
     let mut conf = Ini::new();
     conf.set_comment_symbols(&[';']);
     let config_path = get_config_path();
 
     if !config_path.exists() {
         if let Err(e) = create_dir_all(config_path.parent().unwrap()) {
-            ccprintln!(window, "Error creating config directory: {e}");
+            ccprintln!(reporter, "Error creating config directory: {e}");
         }
 
         set_gui_config_to_default(&mut conf);
 
         if let Err(e) = conf.write(&config_path) {
-            ccprintln!(window, "Error writing config file: {e}");
+            ccprintln!(reporter, "Error writing config file: {e}");
         }
     } else if let Err(e) = conf.load(config_path) {
-        ccprintln!(window, "Error loading config: {e}");
+        ccprintln!(reporter, "Error loading config: {e}");
     }
 
     conf
@@ -126,59 +133,48 @@ pub async fn get_folder_settings() -> BotFolders {
     BOT_FOLDER_SETTINGS.read().await.clone()
 }
 
-fn filter_hidden_bundles<I>(bundles: I) -> Vec<I::Item>
-where
-    I: IntoIterator,
-    I::Item: Runnable + Clone,
-{
-    bundles
-        .into_iter()
-        .filter(|b| !b.get_config_file_name().starts_with('_'))
-        .collect()
-}
+/// Find all of the bots in the visible bot folders and files
+///
+/// # Arguments
+///
+/// * `reporter` - Where to send console output and events, like the GUI
+pub async fn scan_for_bots_helper(reporter: &dyn Reporter) -> Vec<BotConfigBundle> {
+    let bfs = BOT_FOLDER_SETTINGS.read().await;
 
-async fn get_bots_from_directory(window: &Window, path: &str) -> Vec<BotConfigBundle> {
-    filter_hidden_bundles(scan_directory_for_bot_configs(window, path).await)
+    bot_index::bots(reporter, &bfs).unwrap_or_else(|e| {
+        ccprintln!(reporter, "Error reading the bot index: {e}");
+        Vec::new()
+    })
 }
 
 #[tauri::command]
 pub async fn scan_for_bots(window: Window) -> Vec<BotConfigBundle> {
-    let bfs = BOT_FOLDER_SETTINGS.read().await;
-    let mut bots = Vec::new();
-
-    for (path, _) in bfs.folders.iter().filter(|(_, props)| props.visible) {
-        bots.extend(get_bots_from_directory(&window, path).await);
-    }
-
-    for (path, _) in bfs.files.iter().filter(|(_, props)| props.visible) {
-        if let Ok(bundle) = BotConfigBundle::minimal_from_path(Path::new(path)).await {
-            bots.push(bundle);
-        }
-    }
+    scan_for_bots_helper(&window).await
+}
 
-    bots
+#[tauri::command]
+pub async fn generate_random_match(
+    window: Window,
+    constraints: MatchConstraints,
+) -> Result<GeneratedMatch, RandomMatchError> {
+    random_match::generate_match(&scan_for_bots_helper(&window).await, &constraints)
 }
 
-async fn get_scripts_from_directory(window: &Window, path: &str) -> Vec<ScriptConfigBundle> {
-    filter_hidden_bundles(scan_directory_for_script_configs(window, path).await)
+/// Find out why bots and scripts in the visible bot folders might not be showing up
+#[tauri::command]
+pub async fn get_scan_diagnostics(window: Window) -> Result<Vec<ConfigDiagnostic>, BotIndexError> {
+    let bfs = BOT_FOLDER_SETTINGS.read().await;
+    bot_index::diagnostics(&window, &bfs)
 }
 
 #[tauri::command]
 pub async fn scan_for_scripts(window: Window) -> Vec<ScriptConfigBundle> {
-    let bfs = BOT_FOLDER_SETTINGS.read().await.clone();
-    let mut scripts = Vec::new();
-
-    for (path, _) in bfs.folders.iter().filter(|(_, props)| props.visible) {
-        scripts.extend(get_scripts_from_directory(&window, path).await);
-    }
-
-    for (path, _) in bfs.files.iter().filter(|(_, props)| props.visible) {
-        if let Ok(bundle) = ScriptConfigBundle::minimal_from_path(Path::new(path)).await {
-            scripts.push(bundle);
-        }
-    }
+    let bfs = BOT_FOLDER_SETTINGS.read().await;
 
-    scripts
+    bot_index::scripts(&window, &bfs).unwrap_or_else(|e| {
+        ccprintln!(&window, "Error reading the bot index: {e}");
+        Vec::new()
+    })
 }
 
 #[tauri::command]
@@ -260,9 +256,77 @@ pub async fn get_looks(path: String) -> Result<BotLooksConfig, cfg_helper::Error
     BotLooksConfig::from_path(&path).await
 }
 
+#[derive(Debug, Error)]
+pub enum SaveLooksError {
+    #[error(transparent)]
+    Item(#[from] ItemError),
+    #[error(transparent)]
+    Cfg(#[from] cfg_helper::Error),
+}
+
+impl_serialize_from_display!(SaveLooksError);
+
 #[tauri::command]
-pub async fn save_looks(window: Window, path: String, config: BotLooksConfig) {
-    config.save_to_path(&window, &path).await;
+pub async fn save_looks(window: Window, path: String, mut config: BotLooksConfig) -> Result<(), SaveLooksError> {
+    // The item list can be behind the game, so unknown items are saved anyway
+    for unknown in config.normalize_items(&ITEM_CATALOG)? {
+        ccprintln!(&window, "Warning while saving {path}: {unknown}");
+    }
+
+    Ok(config.save_to_path(&path).await?)
+}
+
+/// Generate new looks for a bot and save them to its looks config
+///
+/// Bots with a loadout generator get the looks that it makes instead, since that's what RLBot would use in a match
+///
+/// # Arguments
+///
+/// * `path` - The path to the bot's config file
+/// * `mode` - How the items are picked
+/// * `seed` - The seed to generate the looks with, or `None` to use a random one
+#[tauri::command]
+pub async fn generate_loadout(
+    window: Window,
+    path: String,
+    mode: LoadoutMode,
+    seed: Option<u64>,
+) -> Result<GeneratedLoadout, LoadoutError> {
+    let paths = loadout_generator::get_loadout_paths(Path::new(&path)).await?;
+    let looks_path = paths.looks_config.to_string_lossy().to_string();
+
+    let loadout = if let Some(script) = paths.loadout_generator {
+        let python = PYTHON_PATH.read().await.to_owned();
+
+        GeneratedLoadout {
+            seed: 0,
+            from_script: true,
+            looks: loadout_generator::run_loadout_script(&python, &script).await?,
+        }
+    } else {
+        let current = BotLooksConfig::from_path(&looks_path).await.unwrap_or_default();
+        loadout_generator::generate_looks(mode, seed, &current)
+    };
+
+    save_looks(window, looks_path, loadout.looks.clone()).await?;
+    Ok(loadout)
+}
+
+/// Search the items that can go in a slot by name or ID
+#[tauri::command]
+pub async fn search_items(slot: ItemSlot, query: String) -> Vec<Item> {
+    ITEM_CATALOG.search(slot, &query).into_iter().cloned().collect()
+}
+
+#[tauri::command]
+pub async fn get_item(id: u32) -> Option<Item> {
+    ITEM_CATALOG.get(id).cloned()
+}
+
+/// Check a bot's config for everything that should be fixed before it's submitted to the botpack
+#[tauri::command]
+pub async fn lint_bot_config(path: String) -> LintReport {
+    bot_config_linter::lint_bot_config(Path::new(&path)).await
 }
 
 #[tauri::command]
@@ -293,13 +357,16 @@ async fn trimmed_to_bundle((skill, path): (Option<f32>, String)) -> Result<BotCo
     }
 }
 
-async fn trimmed_to_bot_bundles(window: &Window, trimmed_bundles: Vec<(Option<f32>, String)>) -> Vec<BotConfigBundle> {
+async fn trimmed_to_bot_bundles(
+    reporter: &dyn Reporter,
+    trimmed_bundles: Vec<(Option<f32>, String)>,
+) -> Vec<BotConfigBundle> {
     join_all(trimmed_bundles.into_iter().map(trimmed_to_bundle))
         .await
         .into_iter()
         .flat_map(|f| {
             if let Err(e) = &f {
-                ccprintln!(window, "Error loading bot config: {e}");
+                ccprintln!(reporter, "Error loading bot config: {e}");
             }
 
             f
@@ -307,12 +374,16 @@ async fn trimmed_to_bot_bundles(window: &Window, trimmed_bundles: Vec<(Option<f3
         .collect()
 }
 
-#[tauri::command]
-pub async fn get_team_settings(window: Window) -> HashMap<String, Vec<BotConfigBundle>> {
-    let config = load_gui_config(&window).await;
+/// Load the bots on each team from the GUI config, with "blue_team" and "orange_team" as the keys
+///
+/// # Arguments
+///
+/// * `reporter` - Where to send console output and events, like the GUI
+pub async fn get_team_settings_helper(reporter: &dyn Reporter) -> HashMap<String, Vec<BotConfigBundle>> {
+    let config = load_gui_config(reporter).await;
 
     let blue_team = trimmed_to_bot_bundles(
-        &window,
+        reporter,
         serde_json::from_str(
             &config
                 .get("team_settings", "blue_team")
@@ -323,7 +394,7 @@ pub async fn get_team_settings(window: Window) -> HashMap<String, Vec<BotConfigB
     .await;
 
     let orange_team = trimmed_to_bot_bundles(
-        &window,
+        reporter,
         serde_json::from_str(&config.get("team_settings", "orange_team").unwrap_or_else(|| "[]".to_owned()))
             .unwrap_or_default(),
     )
@@ -336,6 +407,11 @@ pub async fn get_team_settings(window: Window) -> HashMap<String, Vec<BotConfigB
     bots
 }
 
+#[tauri::command]
+pub async fn get_team_settings(window: Window) -> HashMap<String, Vec<BotConfigBundle>> {
+    get_team_settings_helper(&window).await
+}
+
 fn trim_bot_bundles(bundles: Vec<BotConfigBundle>) -> Vec<(Option<f32>, String)> {
     bundles
         .into_iter()
@@ -349,25 +425,205 @@ fn trim_bot_bundles(bundles: Vec<BotConfigBundle>) -> Vec<(Option<f32>, String)>
         .collect()
 }
 
-#[tauri::command]
-pub async fn save_team_settings(window: Window, blue_team: Vec<BotConfigBundle>, orange_team: Vec<BotConfigBundle>) {
-    let mut config = load_gui_config(&window).await;
-    config.set(
-        "team_settings",
-        "blue_team",
-        Some(serde_json::to_string(&trim_bot_bundles(blue_team)).unwrap()),
-    );
+async fn save_trimmed_team_settings(
+    reporter: &dyn Reporter,
+    blue_team: &[(Option<f32>, String)],
+    orange_team: &[(Option<f32>, String)],
+) {
+    let mut config = load_gui_config(reporter).await;
+    config.set("team_settings", "blue_team", Some(serde_json::to_string(blue_team).unwrap()));
     config.set(
         "team_settings",
         "orange_team",
-        Some(serde_json::to_string(&trim_bot_bundles(orange_team)).unwrap()),
+        Some(serde_json::to_string(orange_team).unwrap()),
     );
 
     if let Err(e) = save_cfg(&config, get_config_path()).await {
-        ccprintln!(&window, "Error saving team settings: {e}");
+        ccprintln!(reporter, "Error saving team settings: {e}");
     }
 }
 
+#[tauri::command]
+pub async fn save_team_settings(window: Window, blue_team: Vec<BotConfigBundle>, orange_team: Vec<BotConfigBundle>) {
+    save_trimmed_team_settings(&window, &trim_bot_bundles(blue_team), &trim_bot_bundles(orange_team)).await;
+}
+
+#[tauri::command]
+pub async fn list_presets(window: Window) -> Result<Vec<MatchPreset>, PresetError> {
+    presets::list_presets(&window)
+}
+
+#[tauri::command]
+pub async fn save_preset(
+    name: String,
+    match_settings: MiniMatchConfig,
+    blue_team: Vec<BotConfigBundle>,
+    orange_team: Vec<BotConfigBundle>,
+) -> Result<(), PresetError> {
+    presets::save_preset(MatchPreset {
+        name,
+        match_settings,
+        blue_team: trim_bot_bundles(blue_team),
+        orange_team: trim_bot_bundles(orange_team),
+    })
+}
+
+/// A preset with the bots and scripts loaded, ready to be shown in the GUI
+#[derive(Serialize, Clone, Debug)]
+pub struct LoadedPreset {
+    pub name: String,
+    pub match_settings: MatchConfig,
+    pub blue_team: Vec<BotConfigBundle>,
+    pub orange_team: Vec<BotConfigBundle>,
+}
+
+/// Load a preset and make it the current match setup, saving it to `config.ini` like the GUI does
+#[tauri::command]
+pub async fn load_preset(window: Window, name: String) -> Result<LoadedPreset, PresetError> {
+    let preset = presets::get_preset(&name)?;
+
+    save_trimmed_team_settings(&window, &preset.blue_team, &preset.orange_team).await;
+    let match_settings = MatchConfig::from_mini(&window, preset.match_settings).await;
+    match_settings.save_config(&window).await;
+
+    Ok(LoadedPreset {
+        name: preset.name,
+        match_settings,
+        blue_team: trimmed_to_bot_bundles(&window, preset.blue_team).await,
+        orange_team: trimmed_to_bot_bundles(&window, preset.orange_team).await,
+    })
+}
+
+#[tauri::command]
+pub async fn rename_preset(old_name: String, new_name: String) -> Result<(), PresetError> {
+    presets::rename_preset(&old_name, &new_name)
+}
+
+#[tauri::command]
+pub async fn delete_preset(name: String) -> Result<(), PresetError> {
+    presets::delete_preset(&name)
+}
+
+#[tauri::command]
+pub fn export_preset(window: Window, name: String) {
+    FileDialogBuilder::new()
+        .add_filter("Match Preset", &[PRESET_EXTENSION])
+        .set_file_name(&format!("{}.{PRESET_EXTENSION}", name.trim()))
+        .save_file(move |path| {
+            let Some(path) = path else {
+                return;
+            };
+
+            if let Err(e) = presets::export_preset(&name, &path) {
+                ccprintln!(&window, "Error exporting preset: {e}");
+            }
+        });
+}
+
+#[tauri::command]
+pub fn import_preset(window: Window) {
+    FileDialogBuilder::new()
+        .add_filter("Match Preset", &[PRESET_EXTENSION])
+        .pick_file(move |path| {
+            let Some(path) = path else {
+                return;
+            };
+
+            match presets::import_preset(&path) {
+                Ok(preset) => {
+                    if let Err(e) = window.emit("preset-imported", preset) {
+                        ccprintln!(&window, "Error emiting preset-imported event: {e}");
+                    }
+                }
+                Err(e) => ccprintln!(&window, "Error importing preset: {e}"),
+            }
+        });
+}
+
+#[tauri::command]
+pub async fn list_loadouts(window: Window) -> Result<Vec<LoadoutPreset>, LoadoutLibraryError> {
+    loadout_library::list_loadouts(&window)
+}
+
+#[tauri::command]
+pub async fn save_loadout(loadout: LoadoutPreset) -> Result<(), LoadoutLibraryError> {
+    loadout_library::save_loadout(loadout)
+}
+
+/// Save the current looks of a bot as a loadout
+///
+/// # Arguments
+///
+/// * `name` - The name of the new loadout
+/// * `looks_path` - The path to the bot's looks config
+#[tauri::command]
+pub async fn save_bot_looks_as_loadout(name: String, looks_path: String) -> Result<(), LoadoutLibraryError> {
+    loadout_library::save_loadout_from_bot(name, &looks_path).await
+}
+
+/// Put a loadout on any number of bots
+///
+/// # Arguments
+///
+/// * `name` - The name of the loadout
+/// * `looks_paths` - The paths to the looks configs of the bots
+#[tauri::command]
+pub async fn apply_loadout(name: String, looks_paths: Vec<String>) -> Result<(), LoadoutLibraryError> {
+    loadout_library::apply_loadout(&name, &looks_paths).await
+}
+
+#[tauri::command]
+pub async fn swap_loadout_teams(name: String) -> Result<LoadoutPreset, LoadoutLibraryError> {
+    loadout_library::swap_loadout_teams(&name)
+}
+
+#[tauri::command]
+pub async fn rename_loadout(old_name: String, new_name: String) -> Result<(), LoadoutLibraryError> {
+    loadout_library::rename_loadout(&old_name, &new_name)
+}
+
+#[tauri::command]
+pub async fn delete_loadout(name: String) -> Result<(), LoadoutLibraryError> {
+    loadout_library::delete_loadout(&name)
+}
+
+#[tauri::command]
+pub fn export_loadout(window: Window, name: String) {
+    FileDialogBuilder::new()
+        .add_filter("Loadout", &[LOADOUT_EXTENSION])
+        .add_filter("Looks Config", &[LOOKS_CFG_EXTENSION])
+        .set_file_name(&format!("{}.{LOADOUT_EXTENSION}", name.trim()))
+        .save_file(move |path| {
+            let Some(path) = path else {
+                return;
+            };
+
+            if let Err(e) = tauri_block_on(loadout_library::export_loadout(&name, &path)) {
+                ccprintln!(&window, "Error exporting loadout: {e}");
+            }
+        });
+}
+
+#[tauri::command]
+pub fn import_loadout(window: Window) {
+    FileDialogBuilder::new()
+        .add_filter("Loadout", &[LOADOUT_EXTENSION, LOOKS_CFG_EXTENSION])
+        .pick_file(move |path| {
+            let Some(path) = path else {
+                return;
+            };
+
+            match tauri_block_on(loadout_library::import_loadout(&path)) {
+                Ok(loadout) => {
+                    if let Err(e) = window.emit("loadout-imported", loadout) {
+                        ccprintln!(&window, "Error emiting loadout-imported event: {e}");
+                    }
+                }
+                Err(e) => ccprintln!(&window, "Error importing loadout: {e}"),
+            }
+        });
+}
+
 #[tauri::command]
 pub async fn get_language_support() -> HashMap<String, bool> {
     let mut lang_support = HashMap::new();
@@ -430,25 +686,22 @@ fn read_recommendations_json<P: AsRef<Path>>(path: P) -> Result<AllRecommendatio
     Ok(serde_json::from_str(&read_to_string(&path)?)?)
 }
 
-fn get_recommendations_json(window: &Window, bfs: &BotFolders) -> Option<AllRecommendations<String>> {
-    bfs.folders
-        .keys()
-        .filter_map(|path| match glob(&format!("{path}/**/recommendations.json")) {
-            Ok(pattern) => Some(pattern),
-            Err(e) => {
-                ccprintln!(window, "{e}");
-                None
-            }
-        })
-        .flatten()
-        .flatten()
-        .find_map(|path| match read_recommendations_json(path) {
-            Ok(recommendations) => Some(recommendations),
-            Err(e) => {
-                ccprintln(window, e.to_string());
-                None
-            }
-        })
+fn get_recommendations_json(reporter: &dyn Reporter, bfs: &BotFolders) -> Option<AllRecommendations<String>> {
+    let files = match bot_index::recommendation_files(reporter, bfs) {
+        Ok(files) => files,
+        Err(e) => {
+            ccprintln!(reporter, "Error reading the bot index: {e}");
+            return None;
+        }
+    };
+
+    files.into_iter().find_map(|path| match read_recommendations_json(path) {
+        Ok(recommendations) => Some(recommendations),
+        Err(e) => {
+            ccprintln(reporter, e.to_string());
+            None
+        }
+    })
 }
 
 #[tauri::command]
@@ -459,39 +712,16 @@ pub async fn get_recommendations(window: Window) -> Option<AllRecommendations<Bo
 
     // If we found the json, return the corresponding BotConfigBundles for the bots
     get_recommendations_json(&window, &bfs).map(|j| {
-        let folders = bfs
-            .folders
-            .iter()
-            .filter(|(_, props)| props.visible)
-            .filter_map(|(path, _)| match glob(&format!("{path}/**/*.cfg")) {
-                Ok(paths) => Some(
-                    paths
-                        .flatten()
-                        .filter_map(|path| BotConfigBundle::name_from_path(path.as_path()).ok())
-                        .collect::<Vec<_>>(),
-                ),
-                Err(e) => {
-                    ccprintln(&window, e.to_string());
-                    None
-                }
-            })
-            .flatten();
-
-        let files = bfs
-            .files
-            .iter()
-            .filter(|(_, props)| props.visible)
-            .filter_map(|(path, _)| BotConfigBundle::name_from_path(Path::new(path)).ok());
-
-        // Get a list of all the bots in (bot name, bot config file path) pairs
-        let name_path_pairs = folders.chain(files).collect::<Vec<_>>();
+        let bots = bot_index::bots(&window, &bfs).unwrap_or_else(|e| {
+            ccprintln!(&window, "Error reading the bot index: {e}");
+            Vec::new()
+        });
 
         // Load all of the bot config bundles
         j.change_generic(&|bot_name| {
-            name_path_pairs
-                .iter()
-                .filter(|(name, _)| name == bot_name)
-                .find_map(|(_, path)| BotConfigBundle::minimal_from_path_sync(Path::new(path)).ok())
+            bots.iter()
+                .find(|bundle| &bundle.name == bot_name)
+                .cloned()
                 .map(|mut bundle| {
                     bundle.logo = bundle.load_logo();
 
@@ -634,18 +864,18 @@ impl GuiTabCategory {
         Self { primary, secondary }
     }
 
-    async fn load_primary(window: &Window) -> Option<PrimaryCategories> {
-        serde_json::from_str(&load_gui_config(window).await.get("gui_state", "selected_tab")?).ok()
+    async fn load_primary(reporter: &dyn Reporter) -> Option<PrimaryCategories> {
+        serde_json::from_str(&load_gui_config(reporter).await.get("gui_state", "selected_tab")?).ok()
     }
 
-    async fn load_seconary(window: &Window) -> Option<usize> {
-        serde_json::from_str(&load_gui_config(window).await.get("gui_state", "selected_tab_secondary")?).ok()
+    async fn load_seconary(reporter: &dyn Reporter) -> Option<usize> {
+        serde_json::from_str(&load_gui_config(reporter).await.get("gui_state", "selected_tab_secondary")?).ok()
     }
 
-    pub async fn load(window: &Window) -> Self {
+    pub async fn load(reporter: &dyn Reporter) -> Self {
         Self {
-            primary: Self::load_primary(window).await.unwrap_or_default(),
-            secondary: Self::load_seconary(window).await.unwrap_or_default(),
+            primary: Self::load_primary(reporter).await.unwrap_or_default(),
+            secondary: Self::load_seconary(reporter).await.unwrap_or_default(),
         }
     }
 
@@ -662,12 +892,12 @@ impl GuiTabCategory {
         );
     }
 
-    pub async fn save(&self, window: &Window) {
-        let mut conf = load_gui_config(window).await;
+    pub async fn save(&self, reporter: &dyn Reporter) {
+        let mut conf = load_gui_config(reporter).await;
         self.save_to_config(&mut conf);
 
         if let Err(e) = save_cfg(&conf, get_config_path()).await {
-            ccprintln!(window, "Error writing config: {e}");
+            ccprintln!(reporter, "Error writing config: {e}");
         }
     }
 }
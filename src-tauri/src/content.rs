@@ -0,0 +1,187 @@
+use crate::{ccprintln, get_content_folder, impl_serialize_from_display, reporter::Reporter};
+use serde::{de::DeserializeOwned, Serialize};
+#[cfg(test)]
+use std::cell::RefCell;
+use std::{
+    ffi::OsStr,
+    fmt::Display,
+    fs::{self, create_dir_all, File},
+    io::{self, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ContentError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+    #[error("\"{1}\" isn't a valid {0} name")]
+    InvalidName(&'static str, String),
+    #[error("There's no {0} called \"{1}\"")]
+    NotFound(&'static str, String),
+    #[error("There's already a {0} called \"{1}\"")]
+    AlreadyExists(&'static str, String),
+}
+
+impl_serialize_from_display!(ContentError);
+
+#[cfg(test)]
+thread_local! {
+    /// Where the content folders are for the test that's running on this thread, instead of the real content folder
+    static TEST_CONTENT_FOLDER: RefCell<Option<PathBuf>> = RefCell::new(None);
+}
+
+/// Put the content folders of the test that's running on this thread in `folder`
+#[cfg(test)]
+pub fn set_test_content_folder(folder: &Path) {
+    TEST_CONTENT_FOLDER.with(|test_folder| *test_folder.borrow_mut() = Some(folder.to_owned()));
+}
+
+/// A folder in the content folder where every item is a file that's named after it, like the match presets
+#[derive(Clone, Copy, Debug)]
+pub struct ContentFolder {
+    /// The name of the folder inside of the content folder
+    pub folder: &'static str,
+    /// The extension of the item files, without the dot
+    pub extension: &'static str,
+    /// What a single item is called in error messages, like "preset"
+    pub kind: &'static str,
+}
+
+impl ContentFolder {
+    pub fn path(self) -> PathBuf {
+        #[cfg(test)]
+        if let Some(folder) = TEST_CONTENT_FOLDER.with(|folder| folder.borrow().clone()) {
+            return folder.join(self.folder);
+        }
+
+        get_content_folder().join(self.folder)
+    }
+
+    /// Get the path to the item with the given name, making sure that the name can't point outside of the folder
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the item, without the file extension
+    pub fn item_path(self, name: &str) -> Result<PathBuf, ContentError> {
+        content_item_path(&self.path(), name, self.extension)
+            .ok_or_else(|| ContentError::InvalidName(self.kind, name.to_owned()))
+    }
+
+    /// Get the path to an item that has to exist already
+    pub fn existing_item_path(self, name: &str) -> Result<PathBuf, ContentError> {
+        let path = self.item_path(name)?;
+
+        if path.is_file() {
+            Ok(path)
+        } else {
+            Err(ContentError::NotFound(self.kind, name.to_owned()))
+        }
+    }
+
+    /// Get the path for an item that can't exist yet, like the new name of an item that's being renamed
+    pub fn new_item_path(self, name: &str) -> Result<PathBuf, ContentError> {
+        let path = self.item_path(name)?;
+
+        if path.exists() {
+            Err(ContentError::AlreadyExists(self.kind, name.to_owned()))
+        } else {
+            Ok(path)
+        }
+    }
+
+    /// Read every item in the folder, skipping the ones that can't be read
+    ///
+    /// # Arguments
+    ///
+    /// * `reporter` - Where to report the items that were skipped, and why
+    /// * `read` - How to read a single item from its path
+    pub fn list<T, E: Display>(
+        self,
+        reporter: &dyn Reporter,
+        read: impl Fn(&Path) -> Result<T, E>,
+    ) -> Result<Vec<T>, ContentError> {
+        let folder = self.path();
+        if !folder.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut items = Vec::new();
+
+        for entry in fs::read_dir(folder)? {
+            let path = entry?.path();
+            if path.extension() != Some(OsStr::new(self.extension)) {
+                continue;
+            }
+
+            match read(&path) {
+                Ok(item) => items.push(item),
+                Err(e) => ccprintln!(reporter, "Skipping {} {}: {e}", self.kind, path.display()),
+            }
+        }
+
+        Ok(items)
+    }
+}
+
+/// Check that a name can be used as a file name without pointing outside of the folder it's meant to be in
+///
+/// Returns the name without any surrounding whitespace, or `None` if it can't be used
+pub fn valid_name(name: &str) -> Option<&str> {
+    let trimmed = name.trim();
+    let is_valid =
+        !trimmed.is_empty() && !trimmed.starts_with('.') && Path::new(trimmed).file_name() == Some(OsStr::new(trimmed));
+
+    is_valid.then_some(trimmed)
+}
+
+/// Get the path to a file named after an item in `folder`, or `None` if the name isn't valid
+///
+/// # Arguments
+///
+/// * `folder` - The folder that the item is in
+/// * `name` - The name of the item, without the file extension
+/// * `extension` - The extension of the file, without the dot
+pub fn content_item_path(folder: &Path, name: &str, extension: &str) -> Option<PathBuf> {
+    valid_name(name).map(|name| folder.join(format!("{name}.{extension}")))
+}
+
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, ContentError> {
+    Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+}
+
+/// Write a value as pretty JSON, creating the folder that it's in if needed
+pub fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), ContentError> {
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent)?;
+    }
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut writer, value)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_trimmed() {
+        assert_eq!(valid_name("  My preset "), Some("My preset"));
+        assert_eq!(
+            content_item_path(Path::new("presets"), " My preset", "json"),
+            Some(Path::new("presets").join("My preset.json"))
+        );
+    }
+
+    #[test]
+    fn names_cant_leave_the_folder() {
+        for name in ["", "   ", ".hidden", "..", "../escape", "nested/name", "/absolute"] {
+            assert_eq!(valid_name(name), None, "{name:?} should be rejected");
+        }
+    }
+}
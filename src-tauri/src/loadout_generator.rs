@@ -0,0 +1,360 @@
+use crate::{
+    bot_management::cfg_helper::{self, load_cfg},
+    get_command, impl_serialize_from_display,
+    rlbot::parsing::{
+        agent_config_parser::{BotLooksConfig, BotTeamLooksConfig, CUSTOM_COLOR_COUNT, PAINT_COUNT, TEAM_COLOR_COUNT},
+        bot_config_bundle::{BOT_CONFIG_MODULE_HEADER, LOADOUT_GENERATOR_FILE_KEY, LOOKS_CONFIG_KEY},
+        item_catalog::{Item, ItemError, ItemSlot, ITEM_CATALOG},
+    },
+};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{self, Read},
+    path::{Path, PathBuf},
+    process::{Child, Output, Stdio},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+use tauri::async_runtime::spawn_blocking;
+use thiserror::Error;
+
+/// How long a bot's loadout generator gets to pick its looks before it's stopped
+const SCRIPT_TIMEOUT: Duration = Duration::from_secs(30);
+/// How often to check whether a loadout generator has finished
+const SCRIPT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Runs a bot's loadout generator for both teams and prints the loadouts as JSON
+///
+/// The generator is a `BaseLoadoutGenerator` from the RLBot framework, which RLBot otherwise only runs when a match starts
+const LOADOUT_SCRIPT: &str = r#"
+import json, sys
+from pathlib import Path
+from rlbot.agents.base_loadout_generator import BaseLoadoutGenerator
+from rlbot.utils.class_importer import import_class_with_base
+
+generator_path = Path(sys.argv[1])
+generator_class = import_class_with_base(generator_path, BaseLoadoutGenerator).get_loaded_class()
+generator = generator_class(generator_path.parent)
+
+def to_strings(config):
+    return {key: "" if value is None else str(value) for key, value in vars(config).items() if key != "paint_config"}
+
+looks = {}
+for team, name in ((0, "blue"), (1, "orange")):
+    loadout = generator.generate_loadout(0, team)
+    looks[name] = {**to_strings(loadout), **to_strings(loadout.paint_config)}
+
+print(json.dumps(looks))
+"#;
+
+/// The slots that are picked at random in every mode, since they can go on any car
+const COSMETIC_SLOTS: [ItemSlot; 8] = [
+    ItemSlot::Wheels,
+    ItemSlot::Boost,
+    ItemSlot::Antenna,
+    ItemSlot::Hat,
+    ItemSlot::PaintFinish,
+    ItemSlot::EngineAudio,
+    ItemSlot::SupersonicTrail,
+    ItemSlot::GoalExplosion,
+];
+
+#[derive(Debug, Error)]
+pub enum LoadoutError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+    #[error(transparent)]
+    Cfg(#[from] cfg_helper::Error),
+    #[error(transparent)]
+    Item(#[from] ItemError),
+    #[error("The bot config {0} doesn't have a looks config")]
+    NoLooksConfig(String),
+    #[error("The loadout generator {0} failed: {1}")]
+    Script(String, String),
+    #[error("The loadout generator {0} didn't finish within {1} seconds")]
+    TimedOut(String, u64),
+    #[error(transparent)]
+    Tauri(#[from] tauri::Error),
+}
+
+impl_serialize_from_display!(LoadoutError);
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoadoutMode {
+    /// Every item and color is picked at random, with decals that fit the body
+    Random,
+    /// Both teams get the same body with one of its own decals, and each team's items share one paint color
+    Theme,
+    /// The bodies from the current looks are kept, and everything else is picked at random
+    KeepBody,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GeneratedLoadout {
+    /// The seed that was used, which can be passed back in to get the same looks again
+    pub seed: u64,
+    /// Whether the bot's own loadout generator made the looks, in which case the mode and seed weren't used
+    pub from_script: bool,
+    pub looks: BotLooksConfig,
+}
+
+fn pick(rng: &mut ChaCha8Rng, slot: ItemSlot) -> Option<&'static Item> {
+    ITEM_CATALOG.in_slot(slot).collect::<Vec<_>>().choose(rng).copied()
+}
+
+/// The decals that were made for a body, which are named like `Octane: Flames`
+fn decals_for(body: &Item) -> Vec<&'static Item> {
+    let prefix = format!("{}: ", body.name);
+    ITEM_CATALOG
+        .in_slot(ItemSlot::Skin)
+        .filter(|decal| decal.name.starts_with(&prefix))
+        .collect()
+}
+
+/// Pick a decal that fits the body, either one that was made for it or one that goes on every body
+fn pick_decal(rng: &mut ChaCha8Rng, body: Option<&Item>, own_only: bool) -> String {
+    let mut decals = body.map(decals_for).unwrap_or_default();
+
+    if !own_only {
+        decals.extend(
+            ITEM_CATALOG
+                .in_slot(ItemSlot::Skin)
+                .filter(|decal| !decal.name.contains(": ")),
+        );
+    }
+
+    decals
+        .choose(rng)
+        .map_or_else(|| "0".to_owned(), |decal| decal.id.to_string())
+}
+
+fn item_id(item: Option<&Item>) -> String {
+    item.map_or_else(|| "0".to_owned(), |item| item.id.to_string())
+}
+
+fn generate_team(
+    rng: &mut ChaCha8Rng,
+    mode: LoadoutMode,
+    body: Option<&Item>,
+    current: &BotTeamLooksConfig,
+) -> BotTeamLooksConfig {
+    let mut looks = BotTeamLooksConfig {
+        team_color_id: rng.gen_range(0..TEAM_COLOR_COUNT).to_string(),
+        custom_color_id: rng.gen_range(0..CUSTOM_COLOR_COUNT).to_string(),
+        // The body is kept as it was even if it isn't in the item list, which is only needed to pick the decal
+        car_id: if mode == LoadoutMode::KeepBody {
+            current.car_id.clone()
+        } else {
+            item_id(body)
+        },
+        decal_id: pick_decal(rng, body, mode == LoadoutMode::Theme),
+        primary_color_lookup: current.primary_color_lookup.clone(),
+        secondary_color_lookup: current.secondary_color_lookup.clone(),
+        ..Default::default()
+    };
+
+    for slot in COSMETIC_SLOTS {
+        let id = item_id(pick(rng, slot));
+        match slot {
+            ItemSlot::Wheels => looks.wheels_id = id,
+            ItemSlot::Boost => looks.boost_id = id,
+            ItemSlot::Antenna => looks.antenna_id = id,
+            ItemSlot::Hat => looks.hat_id = id,
+            ItemSlot::PaintFinish => {
+                looks.custom_finish_id = item_id(pick(rng, slot));
+                looks.paint_finish_id = id;
+            }
+            ItemSlot::EngineAudio => looks.engine_audio_id = id,
+            ItemSlot::SupersonicTrail => looks.trails_id = id,
+            ItemSlot::GoalExplosion => looks.goal_explosion_id = id,
+            ItemSlot::Body | ItemSlot::Skin => {}
+        }
+    }
+
+    // A theme uses one paint color for everything
+    let theme_paint = rng.gen_range(0..PAINT_COUNT);
+    let mut paint = || {
+        if mode == LoadoutMode::Theme {
+            theme_paint.to_string()
+        } else {
+            rng.gen_range(0..PAINT_COUNT).to_string()
+        }
+    };
+
+    looks.car_paint_id = paint();
+    looks.decal_paint_id = paint();
+    looks.wheels_paint_id = paint();
+    looks.boost_paint_id = paint();
+    looks.antenna_paint_id = paint();
+    looks.hat_paint_id = paint();
+    looks.trails_paint_id = paint();
+    looks.goal_explosion_paint_id = paint();
+
+    looks
+}
+
+/// Generate looks for both teams from the item catalog
+///
+/// # Arguments
+///
+/// * `mode` - How the items are picked
+/// * `seed` - The same seed and mode always give the same looks, or a random seed is used if this is `None`
+/// * `current` - The bot's current looks, which is where the bodies come from in `KeepBody` mode
+pub fn generate_looks(mode: LoadoutMode, seed: Option<u64>, current: &BotLooksConfig) -> GeneratedLoadout {
+    let seed = seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+    let current_body = |team: &BotTeamLooksConfig| {
+        let id = team.car_id.trim().parse().ok()?;
+        ITEM_CATALOG.get(id).filter(|item| item.slot == ItemSlot::Body)
+    };
+    let (blue_body, orange_body) = match mode {
+        LoadoutMode::Random => (pick(&mut rng, ItemSlot::Body), pick(&mut rng, ItemSlot::Body)),
+        LoadoutMode::Theme => {
+            let body = pick(&mut rng, ItemSlot::Body);
+            (body, body)
+        }
+        LoadoutMode::KeepBody => (current_body(&current.blue), current_body(&current.orange)),
+    };
+
+    let blue = generate_team(&mut rng, mode, blue_body, &current.blue);
+    let orange = generate_team(&mut rng, mode, orange_body, &current.orange);
+
+    GeneratedLoadout {
+        seed,
+        from_script: false,
+        looks: BotLooksConfig { blue, orange },
+    }
+}
+
+/// Run a bot's own loadout generator script to get its looks
+///
+/// The script is stopped if it hasn't finished after [`SCRIPT_TIMEOUT`]
+///
+/// # Arguments
+///
+/// * `python` - The Python executable that has RLBot installed
+/// * `script` - The path to the loadout generator
+pub async fn run_loadout_script(python: &str, script: &Path) -> Result<BotLooksConfig, LoadoutError> {
+    let script_str = script.to_string_lossy().to_string();
+    let child = get_command(python, ["-c", LOADOUT_SCRIPT, script_str.as_str()])
+        .current_dir(script.parent().unwrap_or_else(|| Path::new(".")))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Waiting for the script blocks, so keep it off of the async runtime
+    let Some(output) = spawn_blocking(move || output_with_timeout(child, SCRIPT_TIMEOUT)).await?? else {
+        return Err(LoadoutError::TimedOut(script_str, SCRIPT_TIMEOUT.as_secs()));
+    };
+
+    if !output.status.success() {
+        return Err(LoadoutError::Script(
+            script_str,
+            String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        ));
+    }
+
+    // Anything that the generator printed comes before the JSON, which is on the last line
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json = stdout.lines().last().unwrap_or_default();
+
+    Ok(serde_json::from_str(json)?)
+}
+
+/// Wait for a process to exit and collect everything that it printed, killing it if it takes longer than `timeout`
+///
+/// Returns `None` if the process was killed
+///
+/// # Arguments
+///
+/// * `child` - The process, with its stdout and stderr piped
+/// * `timeout` - How long the process gets to exit by itself
+fn output_with_timeout(mut child: Child, timeout: Duration) -> io::Result<Option<Output>> {
+    // The pipes are read while waiting, so that a script that prints a lot can't fill them up and get stuck
+    let stdout = child.stdout.take().map(|pipe| thread::spawn(move || read_pipe(pipe)));
+    let stderr = child.stderr.take().map(|pipe| thread::spawn(move || read_pipe(pipe)));
+
+    let start = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+
+        if start.elapsed() >= timeout {
+            child.kill()?;
+            child.wait()?;
+            return Ok(None);
+        }
+
+        thread::sleep(SCRIPT_POLL_INTERVAL);
+    };
+
+    let collect = |reader: Option<JoinHandle<Vec<u8>>>| reader.and_then(|reader| reader.join().ok()).unwrap_or_default();
+
+    Ok(Some(Output {
+        status,
+        stdout: collect(stdout),
+        stderr: collect(stderr),
+    }))
+}
+
+fn read_pipe(mut pipe: impl Read) -> Vec<u8> {
+    let mut buf = Vec::new();
+    // Whatever was read before an error is still worth showing
+    let _ = pipe.read_to_end(&mut buf);
+    buf
+}
+
+/// The paths to a bot's looks config and, if it has one, its loadout generator
+pub struct BotLoadoutPaths {
+    pub looks_config: PathBuf,
+    pub loadout_generator: Option<PathBuf>,
+}
+
+/// Find the looks config and loadout generator of a bot
+///
+/// # Arguments
+///
+/// * `config_path` - The path to the bot's config file
+pub async fn get_loadout_paths(config_path: &Path) -> Result<BotLoadoutPaths, LoadoutError> {
+    let conf = load_cfg(config_path).await?;
+    let config_directory = config_path.parent().unwrap_or_else(|| Path::new(""));
+
+    let looks_config = conf
+        .get(BOT_CONFIG_MODULE_HEADER, LOOKS_CONFIG_KEY)
+        .map(|path| config_directory.join(path.trim()))
+        .ok_or_else(|| LoadoutError::NoLooksConfig(config_path.display().to_string()))?;
+
+    let loadout_generator = conf
+        .get(BOT_CONFIG_MODULE_HEADER, LOADOUT_GENERATOR_FILE_KEY)
+        .map(|path| config_directory.join(path.trim()))
+        .filter(|path| path.is_file());
+
+    Ok(BotLoadoutPaths {
+        looks_config,
+        loadout_generator,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keep_body_keeps_bodies_that_arent_in_the_item_list() {
+        let mut current = BotLooksConfig::default();
+        current.blue.car_id = "999999".to_owned();
+        current.orange.car_id = "23".to_owned();
+
+        let generated = generate_looks(LoadoutMode::KeepBody, Some(1), &current);
+
+        assert_eq!(generated.looks.blue.car_id, "999999");
+        assert_eq!(generated.looks.orange.car_id, "23");
+    }
+}
@@ -0,0 +1,185 @@
+use crate::{
+    bot_management::{cfg_document::CfgDocument, cfg_helper},
+    content::{read_json, write_json, ContentError, ContentFolder},
+    impl_serialize_from_display,
+    reporter::Reporter,
+    rlbot::parsing::{
+        agent_config_parser::BotLooksConfig,
+        item_catalog::{ItemError, ITEM_CATALOG},
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::{ffi::OsStr, fs, io, path::Path};
+use thiserror::Error;
+
+pub const LOADOUT_EXTENSION: &str = "json";
+pub const LOOKS_CFG_EXTENSION: &str = "cfg";
+pub const LOADOUTS: ContentFolder = ContentFolder {
+    folder: "loadouts",
+    extension: LOADOUT_EXTENSION,
+    kind: "loadout",
+};
+
+#[derive(Debug, Error)]
+pub enum LoadoutLibraryError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Content(#[from] ContentError),
+    #[error(transparent)]
+    Cfg(#[from] cfg_helper::Error),
+    #[error(transparent)]
+    Item(#[from] ItemError),
+    #[error("Couldn't put the loadout on every bot: {}", .0.join("; "))]
+    Apply(Vec<String>),
+}
+
+impl_serialize_from_display!(LoadoutLibraryError);
+
+/// Named looks that can be put on any number of bots, or shared as a standalone file
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LoadoutPreset {
+    pub name: String,
+    pub looks: BotLooksConfig,
+}
+
+/// Get all of the saved loadouts, sorted by name
+///
+/// # Arguments
+///
+/// * `reporter` - Where to report the loadouts that couldn't be read
+pub fn list_loadouts(reporter: &dyn Reporter) -> Result<Vec<LoadoutPreset>, LoadoutLibraryError> {
+    let mut loadouts = LOADOUTS.list(reporter, read_json::<LoadoutPreset>)?;
+    loadouts.sort_by_key(|loadout| loadout.name.to_lowercase());
+
+    Ok(loadouts)
+}
+
+/// Save a loadout, replacing any existing loadout with the same name
+///
+/// The items are checked first, so that a loadout can always be put on a bot
+pub fn save_loadout(mut loadout: LoadoutPreset) -> Result<(), LoadoutLibraryError> {
+    let path = LOADOUTS.item_path(&loadout.name)?;
+    loadout.name = loadout.name.trim().to_owned();
+    loadout.looks.normalize_items(&ITEM_CATALOG)?;
+
+    Ok(write_json(&path, &loadout)?)
+}
+
+/// Save the current looks of a bot as a loadout, replacing any existing loadout with the same name
+///
+/// # Arguments
+///
+/// * `name` - The name of the new loadout
+/// * `looks_path` - The path to the bot's looks config
+pub async fn save_loadout_from_bot(name: String, looks_path: &str) -> Result<(), LoadoutLibraryError> {
+    let looks = BotLooksConfig::from_path(looks_path).await?;
+    save_loadout(LoadoutPreset { name, looks })
+}
+
+pub fn get_loadout(name: &str) -> Result<LoadoutPreset, LoadoutLibraryError> {
+    Ok(read_json(&LOADOUTS.existing_item_path(name)?)?)
+}
+
+/// Put a loadout on bots, keeping everything else in their looks configs
+///
+/// Every bot is tried even if some of them fail, and the failures are returned together
+///
+/// # Arguments
+///
+/// * `name` - The name of the loadout
+/// * `looks_paths` - The paths to the looks configs of the bots
+pub async fn apply_loadout(name: &str, looks_paths: &[String]) -> Result<(), LoadoutLibraryError> {
+    let loadout = get_loadout(name)?;
+    let mut failures = Vec::new();
+
+    for path in looks_paths {
+        if let Err(e) = loadout.looks.save_to_path(path).await {
+            failures.push(format!("{path}: {e}"));
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(LoadoutLibraryError::Apply(failures))
+    }
+}
+
+/// Swap the looks of the blue and orange teams in a loadout
+pub fn swap_loadout_teams(name: &str) -> Result<LoadoutPreset, LoadoutLibraryError> {
+    let path = LOADOUTS.existing_item_path(name)?;
+
+    let mut loadout: LoadoutPreset = read_json(&path)?;
+    loadout.looks.swap_teams();
+    write_json(&path, &loadout)?;
+
+    Ok(loadout)
+}
+
+pub fn rename_loadout(old_name: &str, new_name: &str) -> Result<(), LoadoutLibraryError> {
+    let old_path = LOADOUTS.existing_item_path(old_name)?;
+    let new_path = LOADOUTS.new_item_path(new_name)?;
+
+    // The name is also stored in the file, so that it survives being exported
+    let mut loadout: LoadoutPreset = read_json(&old_path)?;
+    loadout.name = new_name.trim().to_owned();
+    write_json(&new_path, &loadout)?;
+    fs::remove_file(old_path)?;
+
+    Ok(())
+}
+
+pub fn delete_loadout(name: &str) -> Result<(), LoadoutLibraryError> {
+    fs::remove_file(LOADOUTS.existing_item_path(name)?)?;
+    Ok(())
+}
+
+/// Copy a loadout to a standalone file
+///
+/// Files that end in `.cfg` get the looks in the same format as a looks config, and anything else gets the loadout as JSON
+///
+/// # Arguments
+///
+/// * `name` - The name of the loadout to export
+/// * `destination` - Where to write the loadout to
+pub async fn export_loadout(name: &str, destination: &Path) -> Result<(), LoadoutLibraryError> {
+    if destination.extension() != Some(OsStr::new(LOOKS_CFG_EXTENSION)) {
+        fs::copy(LOADOUTS.existing_item_path(name)?, destination)?;
+        return Ok(());
+    }
+
+    let mut config = CfgDocument::default();
+    get_loadout(name)?.looks.save_to_config(&mut config);
+    config.save(destination).await?;
+
+    Ok(())
+}
+
+/// Add a loadout from a standalone file, like one that was exported on another computer
+///
+/// Looks configs are named after the file, since they don't have a name in them
+///
+/// Returns the imported loadout
+///
+/// # Arguments
+///
+/// * `source` - The `.json` loadout or `.cfg` looks config to import the loadout from
+pub async fn import_loadout(source: &Path) -> Result<LoadoutPreset, LoadoutLibraryError> {
+    let mut loadout = if source.extension() == Some(OsStr::new(LOOKS_CFG_EXTENSION)) {
+        LoadoutPreset {
+            name: source.file_stem().unwrap_or_default().to_string_lossy().to_string(),
+            looks: BotLooksConfig::from_path(&source.to_string_lossy()).await?,
+        }
+    } else {
+        read_json(source)?
+    };
+
+    let path = LOADOUTS.new_item_path(&loadout.name)?;
+
+    loadout.name = loadout.name.trim().to_owned();
+    loadout.looks.normalize_items(&ITEM_CATALOG)?;
+    write_json(&path, &loadout)?;
+
+    Ok(loadout)
+}
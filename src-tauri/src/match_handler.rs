@@ -0,0 +1,516 @@
+pub(crate) mod protocol;
+pub(crate) mod supervisor;
+
+use crate::{
+    capture_handler_output, ccprintln, config_handles::load_gui_config_sync, get_maybe_capture_command,
+    impl_serialize_from_display, reporter::Reporter, settings::MatchHandlerConfig, CommandError, CAPTURE_PIPE_WRITER,
+    MATCH_HANDLER, MATCH_HANDLER_STARTUP,
+};
+use crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender};
+use os_pipe::PipeWriter;
+use protocol::{
+    encode_legacy, listen_script, parse_frame, ErrorPayload, Frame, ProtocolError, ReadyInfo, Reply, ReplyBody, Request,
+    PROTOCOL_VERSION,
+};
+use serde::de::DeserializeOwned;
+use std::{
+    collections::{HashMap, VecDeque},
+    ffi::OsStr,
+    io::{BufRead, BufReader, Write},
+    process::{Child, ChildStderr, ChildStdin, ChildStdout, Stdio},
+    sync::{Arc, Mutex, MutexGuard},
+    thread,
+    time::{Duration, Instant},
+};
+use tauri::async_runtime::spawn_blocking;
+use thiserror::Error;
+
+/// How long to wait for most commands to be acknowledged by the match handler
+const DEFAULT_REPLY_TIMEOUT: Duration = Duration::from_secs(10);
+/// Starting a match might include launching Rocket League and waiting for it to load, which can take a while
+const START_MATCH_REPLY_TIMEOUT: Duration = Duration::from_secs(180);
+/// How often to check if the match handler has exited while waiting for it to be ready
+const STARTUP_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How many of the most recent lines from the match handler's stderr to keep around for error reports
+const MAX_STDERR_LINES: usize = 200;
+
+/// Why the match handler couldn't be started
+#[derive(Debug, Error)]
+pub enum StartupFailure {
+    #[error("it exited with code {}", .0.map_or_else(|| "unknown".to_string(), |code| code.to_string()))]
+    Exited(Option<i32>),
+    #[error("it didn't say it was ready within {} seconds", .0.as_secs())]
+    TimedOut(Duration),
+    #[error("it speaks protocol version {found}, but version {expected} was expected")]
+    VersionMismatch { expected: u32, found: u32 },
+}
+
+#[derive(Debug, Error)]
+pub enum MatchHandlerError {
+    #[error("Couldn't start match handler: {0}")]
+    Command(#[from] CommandError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Couldn't find STDIN in match handler")]
+    NoStdin,
+    #[error("Couldn't find STDOUT in match handler")]
+    NoStdout,
+    #[error("Couldn't find STDERR in match handler")]
+    NoStderr,
+    #[error("Failed to write to match handler's STDIN")]
+    NoWrite,
+    #[error("Mutex {0} was poisoned")]
+    Poisoned(&'static str),
+    #[error("No command was given to send to the match handler")]
+    NoCommand,
+    #[error(transparent)]
+    Protocol(#[from] ProtocolError),
+    #[error("Couldn't parse the match handler's reply: {0}")]
+    InvalidReply(#[from] serde_json::Error),
+    #[error("The match handler speaks protocol version {found}, but version {expected} was expected")]
+    VersionMismatch { expected: u32, found: u32 },
+    #[error("The match handler didn't reply to '{0}' in time")]
+    Timeout(String),
+    #[error("The match handler exited before replying")]
+    Disconnected,
+    #[error("The match handler failed to run '{command}': {}", error.message)]
+    Handler { command: String, error: ErrorPayload },
+    #[error("The match handler failed to start because {reason}\n{stderr}")]
+    Startup { reason: StartupFailure, stderr: String },
+    #[error(transparent)]
+    Tauri(#[from] tauri::Error),
+}
+
+impl_serialize_from_display!(MatchHandlerError);
+
+type PendingReplies = Arc<Mutex<HashMap<u64, Sender<Reply>>>>;
+
+/// What the match handler said once it was ready to accept commands
+#[derive(Debug, Clone)]
+pub enum Handshake {
+    Ready(ReadyInfo),
+    /// The installed `rlbot_smh` is too old to speak the request/response protocol,
+    /// so commands are sent as plain lines and nothing replies to them
+    Legacy,
+}
+type StderrLines = Arc<Mutex<VecDeque<String>>>;
+
+/// A running instance of the match handler, which is written in Python so it can use the `RLBot` package (also written in Python)
+pub struct MatchHandler {
+    python_path: String,
+    use_pipe: bool,
+    child: Child,
+    stdin: ChildStdin,
+    next_id: u64,
+    pending: PendingReplies,
+    handshake: Handshake,
+    started_at: Instant,
+    /// How many times the supervisor had to restart the match handler before this instance
+    restarts: u32,
+}
+
+impl MatchHandler {
+    /// Starts the match handler, then waits for it to say that it's ready to accept commands
+    ///
+    /// # Arguments
+    ///
+    /// * `use_pipe` - Whether or not the output of the match handler should be sent to the GUI console
+    /// * `python_path` - The Python executable to run the match handler with
+    /// * `startup_timeout` - How long to wait for the match handler to be ready before giving up
+    fn spawn(use_pipe: bool, python_path: &OsStr, startup_timeout: Duration) -> Result<Self, MatchHandlerError> {
+        let mut child = get_maybe_capture_command(python_path, ["-u", "-c", &listen_script()], use_pipe)?
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        let stdin = child.stdin.take().ok_or(MatchHandlerError::NoStdin)?;
+        let stdout = child.stdout.take().ok_or(MatchHandlerError::NoStdout)?;
+        let stderr = child.stderr.take().ok_or(MatchHandlerError::NoStderr)?;
+
+        let console = if use_pipe {
+            let pipe = CAPTURE_PIPE_WRITER
+                .lock()
+                .map_err(|_| MatchHandlerError::Poisoned("CAPTURE_PIPE_WRITER"))?;
+            pipe.as_ref().map(PipeWriter::try_clone).transpose()?
+        } else {
+            None
+        };
+        let stderr_console = console.as_ref().map(PipeWriter::try_clone).transpose()?;
+
+        let stderr_lines = StderrLines::default();
+        let reader_stderr_lines = stderr_lines.clone();
+        let stderr_reader = thread::spawn(move || read_handler_stderr(stderr, stderr_console, &reader_stderr_lines));
+
+        let pending = PendingReplies::default();
+        let reader_pending = pending.clone();
+        let (ready_sender, ready_receiver) = bounded(1);
+        thread::spawn(move || read_handler_stdout(stdout, console, &reader_pending, ready_sender));
+
+        let handshake = match wait_until_ready(&mut child, &ready_receiver, startup_timeout) {
+            Ok(handshake) => handshake,
+            Err(reason) => {
+                if !matches!(reason, StartupFailure::Exited(_)) {
+                    let _ = child.kill();
+                }
+                let _ = child.wait();
+
+                // Give the stderr reader a moment to catch the last of the output
+                let start_time = Instant::now();
+                while !stderr_reader.is_finished() && start_time.elapsed() < Duration::from_secs(1) {
+                    thread::sleep(STARTUP_POLL_INTERVAL);
+                }
+
+                let stderr = stderr_lines
+                    .lock()
+                    .map(|lines| lines.iter().map(String::as_str).collect())
+                    .unwrap_or_default();
+
+                return Err(MatchHandlerError::Startup { reason, stderr });
+            }
+        };
+
+        Ok(Self {
+            python_path: python_path.to_string_lossy().to_string(),
+            use_pipe,
+            child,
+            stdin,
+            next_id: 0,
+            pending,
+            handshake,
+            started_at: Instant::now(),
+            restarts: 0,
+        })
+    }
+
+    pub const fn handshake(&self) -> &Handshake {
+        &self.handshake
+    }
+
+    /// Writes a request to the match handler's stdin, returning the channel that the reply will arrive on
+    fn send(&mut self, command: &str, args: &[String]) -> Result<Receiver<Reply>, MatchHandlerError> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        if let Handshake::Legacy = self.handshake {
+            let line = encode_legacy(command, args).map_err(ProtocolError::from)?;
+            if writeln!(self.stdin, "{line}").and_then(|_| self.stdin.flush()).is_err() {
+                return Err(MatchHandlerError::NoWrite);
+            }
+
+            // Nothing will reply, so treat the command as done as soon as it's been written, like the GUI used to
+            let (sender, receiver) = bounded(1);
+            let _ = sender.send(Reply::acknowledged(id));
+            return Ok(receiver);
+        }
+
+        let line = Request::new(id, command, args).encode()?;
+        let (sender, receiver) = bounded(1);
+        self.pending
+            .lock()
+            .map_err(|_| MatchHandlerError::Poisoned("pending replies"))?
+            .insert(id, sender);
+
+        if writeln!(self.stdin, "{line}").and_then(|_| self.stdin.flush()).is_err() {
+            if let Ok(mut pending) = self.pending.lock() {
+                pending.remove(&id);
+            }
+
+            return Err(MatchHandlerError::NoWrite);
+        }
+
+        Ok(receiver)
+    }
+}
+
+/// Sends text to the GUI console if the match handler's output is being captured, otherwise to the real console
+///
+/// A copy is also kept for regression suites, which look through it for crashes
+fn forward_to_console(console: &mut Option<PipeWriter>, text: &str) {
+    capture_handler_output(text);
+
+    match console {
+        Some(pipe) => {
+            if pipe.write_all(text.as_bytes()).is_err() {
+                print!("{text}");
+            }
+        }
+        None => print!("{text}"),
+    }
+}
+
+/// Waits for the match handler to announce that it's ready, or for it to exit
+fn wait_until_ready(
+    child: &mut Child,
+    ready_receiver: &Receiver<Handshake>,
+    startup_timeout: Duration,
+) -> Result<Handshake, StartupFailure> {
+    let start_time = Instant::now();
+
+    while start_time.elapsed() < startup_timeout {
+        match ready_receiver.recv_timeout(STARTUP_POLL_INTERVAL) {
+            Ok(Handshake::Ready(ready_info)) if ready_info.protocol_version != PROTOCOL_VERSION => {
+                return Err(StartupFailure::VersionMismatch {
+                    expected: PROTOCOL_VERSION,
+                    found: ready_info.protocol_version,
+                })
+            }
+            Ok(handshake) => return Ok(handshake),
+            Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => {}
+        }
+
+        if let Ok(Some(status)) = child.try_wait() {
+            return Err(StartupFailure::Exited(status.code()));
+        }
+    }
+
+    Err(StartupFailure::TimedOut(startup_timeout))
+}
+
+/// Reads the match handler's stderr line by line, sending it to the console
+/// and remembering the most recent lines in case the match handler fails to start
+fn read_handler_stderr(stderr: ChildStderr, mut console: Option<PipeWriter>, lines: &StderrLines) {
+    let mut reader = BufReader::new(stderr);
+    let mut buf = Vec::new();
+
+    loop {
+        buf.clear();
+        match reader.read_until(b'\n', &mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+
+        let line = String::from_utf8_lossy(&buf).to_string();
+        forward_to_console(&mut console, &line);
+
+        if let Ok(mut lines) = lines.lock() {
+            if lines.len() == MAX_STDERR_LINES {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+        }
+    }
+}
+
+/// Reads the match handler's stdout line by line, routing replies to whoever is waiting on them
+/// and everything else to the console
+fn read_handler_stdout(
+    stdout: ChildStdout,
+    mut console: Option<PipeWriter>,
+    pending: &PendingReplies,
+    ready_sender: Sender<Handshake>,
+) {
+    let mut ready_sender = Some(ready_sender);
+
+    let mut reader = BufReader::new(stdout);
+    let mut buf = Vec::new();
+
+    loop {
+        buf.clear();
+        match reader.read_until(b'\n', &mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+
+        let line = String::from_utf8_lossy(&buf);
+        match parse_frame(&line) {
+            Some(Ok(frame @ (Frame::Ready(_) | Frame::Legacy))) => match ready_sender.take() {
+                Some(sender) => {
+                    let _ = sender.send(match frame {
+                        Frame::Ready(ready_info) => Handshake::Ready(ready_info),
+                        _ => Handshake::Legacy,
+                    });
+                }
+                None => forward_to_console(&mut console, "The match handler said it was ready more than once\n"),
+            },
+            Some(Ok(Frame::Reply(reply))) => {
+                let sender = pending.lock().ok().and_then(|mut pending| pending.remove(&reply.id));
+                match sender {
+                    Some(sender) => {
+                        let _ = sender.send(reply);
+                    }
+                    None => forward_to_console(
+                        &mut console,
+                        &format!("Got a reply from the match handler for unknown request {}\n", reply.id),
+                    ),
+                }
+            }
+            Some(Err(e)) => forward_to_console(&mut console, &format!("Error parsing frame from match handler: {e}\n")),
+            None => forward_to_console(&mut console, &line),
+        }
+    }
+
+    // The match handler's stdout has closed, so nobody that's still waiting will ever get a reply
+    if let Ok(mut pending) = pending.lock() {
+        pending.clear();
+    }
+}
+
+pub enum CreateHandler {
+    /// The bool is whether is not a pipe should be attached to the process
+    Yes(bool),
+    No,
+}
+
+fn reply_timeout(command: &str) -> Duration {
+    match command {
+        "start_match" | "launch_challenge" | "spawn_car_for_viewing" => START_MATCH_REPLY_TIMEOUT,
+        _ => DEFAULT_REPLY_TIMEOUT,
+    }
+}
+
+fn lock_match_handler() -> Result<MutexGuard<'static, Option<MatchHandler>>, MatchHandlerError> {
+    MATCH_HANDLER.lock().map_err(|_| MatchHandlerError::Poisoned("MATCH_HANDLER"))
+}
+
+/// Start the match handler if it isn't already running, and wait for it to be ready
+///
+/// `MATCH_HANDLER` isn't locked while waiting, so other commands can see that the match handler is down
+/// instead of being stuck behind the startup for as long as the startup timeout
+///
+/// # Arguments
+///
+/// * `use_pipe` - Whether or not the match handler's output should be sent to the GUI's console
+/// * `python_path` - The Python executable to run the match handler with
+fn start_match_handler(reporter: &dyn Reporter, use_pipe: bool, python_path: &OsStr) -> Result<(), MatchHandlerError> {
+    let _startup_lock = MATCH_HANDLER_STARTUP
+        .lock()
+        .map_err(|_| MatchHandlerError::Poisoned("MATCH_HANDLER_STARTUP"))?;
+
+    // Something else might have started the match handler while we were waiting for our turn
+    if lock_match_handler()?.is_some() {
+        return Ok(());
+    }
+
+    ccprintln(reporter, "Starting match handler!");
+    let config = MatchHandlerConfig::load_from_conf(&load_gui_config_sync(reporter));
+    let handler = MatchHandler::spawn(use_pipe, python_path, config.startup_timeout())?;
+
+    match handler.handshake() {
+        Handshake::Ready(ready_info) => ccprintln!(
+            reporter,
+            "Match handler is ready (protocol v{}, RLBot v{}, Python {})",
+            ready_info.protocol_version,
+            ready_info.rlbot_version,
+            ready_info.python_version
+        ),
+        Handshake::Legacy => ccprintln(
+            reporter,
+            "Match handler is ready, but rlbot_smh is too old to reply to commands, so errors won't be reported",
+        ),
+    }
+
+    supervisor::watch(reporter.boxed(), handler.child.id());
+    *lock_match_handler()? = Some(handler);
+
+    Ok(())
+}
+
+/// Write a command to the match handler, starting it first if requested
+///
+/// Returns `None` if the match handler isn't running and we were told not to start it
+fn send_match_handler_request<S: AsRef<OsStr>>(
+    reporter: &dyn Reporter,
+    command_parts: &[String],
+    mut create_handler: CreateHandler,
+    python_path: S,
+) -> Result<Option<Receiver<Reply>>, MatchHandlerError> {
+    let (command, args) = command_parts.split_first().ok_or(MatchHandlerError::NoCommand)?;
+
+    if lock_match_handler()?.is_none() {
+        let CreateHandler::Yes(use_pipe) = create_handler else {
+            ccprintln(
+                reporter,
+                "Not issuing command to handler as it's down and I was told to not start it",
+            );
+            return Ok(None);
+        };
+
+        start_match_handler(reporter, use_pipe, python_path.as_ref())?;
+        create_handler = CreateHandler::No;
+    }
+
+    let mut handler_lock = lock_match_handler()?;
+    let handler = handler_lock.as_mut().ok_or(MatchHandlerError::NoStdin)?;
+
+    ccprintln!(reporter, "Issuing command: {command}");
+
+    match handler.send(command, args) {
+        Ok(receiver) => Ok(Some(receiver)),
+        Err(MatchHandlerError::NoWrite) => {
+            handler_lock.take();
+            drop(handler_lock);
+
+            if matches!(create_handler, CreateHandler::Yes(_)) {
+                ccprintln(reporter, "Failed to write to match handler, trying to restart...");
+                send_match_handler_request(reporter, command_parts, create_handler, python_path)
+            } else {
+                Err(MatchHandlerError::NoWrite)
+            }
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Get the pid of the match handler if it's running and can reply to commands
+pub fn replying_handler_pid() -> Option<u32> {
+    let handler_lock = MATCH_HANDLER.lock().ok()?;
+    let handler = handler_lock.as_ref()?;
+
+    matches!(handler.handshake, Handshake::Ready(_)).then(|| handler.child.id())
+}
+
+/// Send a command to the match handler and wait for its reply
+///
+/// Returns `None` if the match handler isn't running and we were told not to start it,
+/// otherwise the data that the match handler replied with
+///
+/// # Arguments
+///
+/// * `reporter` - Where to send console output and events, like the GUI
+/// * `command_parts` - The command to send to the match handler, followed by its arguments
+/// * `create_handler` - If the match handler should be started if it's down
+/// * `python_path` - The Python executable to start the match handler with
+pub async fn issue_match_handler_command<T: DeserializeOwned, S: AsRef<OsStr>>(
+    reporter: &dyn Reporter,
+    command_parts: &[String],
+    create_handler: CreateHandler,
+    python_path: S,
+) -> Result<Option<T>, MatchHandlerError> {
+    let command = command_parts.first().ok_or(MatchHandlerError::NoCommand)?.clone();
+    let timeout = reply_timeout(&command);
+
+    // Starting the match handler and waiting for its reply both block, so keep them off of the async runtime
+    let reporter = reporter.boxed();
+    let command_parts = command_parts.to_vec();
+    let python_path = python_path.as_ref().to_os_string();
+    let reply_command = command.clone();
+
+    let reply = spawn_blocking(move || -> Result<Option<Reply>, MatchHandlerError> {
+        let Some(receiver) = send_match_handler_request(&*reporter, &command_parts, create_handler, &python_path)? else {
+            return Ok(None);
+        };
+
+        receiver.recv_timeout(timeout).map(Some).map_err(|e| match e {
+            RecvTimeoutError::Timeout => MatchHandlerError::Timeout(reply_command),
+            RecvTimeoutError::Disconnected => MatchHandlerError::Disconnected,
+        })
+    })
+    .await??;
+
+    let Some(reply) = reply else {
+        return Ok(None);
+    };
+
+    if reply.version != PROTOCOL_VERSION {
+        return Err(MatchHandlerError::VersionMismatch {
+            expected: PROTOCOL_VERSION,
+            found: reply.version,
+        });
+    }
+
+    match reply.body {
+        ReplyBody::Ok { data } => Ok(Some(serde_json::from_value(data)?)),
+        ReplyBody::Error { error } => Err(MatchHandlerError::Handler { command, error }),
+    }
+}
@@ -0,0 +1,267 @@
+use base64::{prelude::BASE64_STANDARD, Engine};
+use flate2::{write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use std::io::{Result as IoResult, Write};
+use thiserror::Error;
+
+/// The version of the request/response protocol spoken with `rlbot_smh`'s match handler
+///
+/// Bump this whenever the shape of `Request` or `Reply` changes
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Every frame from the match handler is printed to its stdout as a single line surrounded by these markers
+const READY_FRAME_PREFIX: &str = "-|-*|MH_READY ";
+const REPLY_FRAME_PREFIX: &str = "-|-*|MH_REPLY ";
+const FRAME_SUFFIX: &str = "|*-|-";
+/// Printed by `LISTEN_SCRIPT` when the installed `rlbot_smh` doesn't speak this protocol yet
+const LEGACY_FRAME: &str = "-|-*|MH_LEGACY|*-|-";
+
+/// Starts the match handler with this protocol if the installed `rlbot_smh` supports it,
+/// and otherwise with the old protocol of plain command lines without any replies
+const LISTEN_SCRIPT: &str = r#"
+import inspect
+from rlbot_smh.match_handler import listen
+
+if "protocol_version" in inspect.signature(listen).parameters:
+    listen(is_raw_json=False, protocol_version=PROTOCOL_VERSION)
+else:
+    print("-|-*|MH_LEGACY|*-|-", flush=True)
+    listen(is_raw_json=False)
+"#;
+
+/// The Python code that the match handler is started with
+pub fn listen_script() -> String {
+    LISTEN_SCRIPT.replace("PROTOCOL_VERSION", &PROTOCOL_VERSION.to_string())
+}
+
+/// Use flate2 to encode a string with gzip then encode the binary with base64 back into a string
+pub fn gzip_encode(s: &str) -> IoResult<String> {
+    let mut e = GzEncoder::new(Vec::new(), Compression::best());
+    e.write_all(s.as_bytes())?;
+    Ok(BASE64_STANDARD.encode(e.finish()?))
+}
+
+#[derive(Debug, Error)]
+pub enum ProtocolError {
+    #[error("Couldn't serialize request: {0}")]
+    Serialize(#[from] serde_json::Error),
+    #[error("Couldn't encode request: {0}")]
+    Encode(#[from] std::io::Error),
+}
+
+/// A single command for the match handler
+///
+/// Sent over the handler's stdin as one line of gzipped & base64-encoded JSON
+#[derive(Debug, Clone, Serialize)]
+pub struct Request<'a> {
+    pub version: u32,
+    pub id: u64,
+    pub command: &'a str,
+    pub args: &'a [String],
+}
+
+impl<'a> Request<'a> {
+    pub const fn new(id: u64, command: &'a str, args: &'a [String]) -> Self {
+        Self {
+            version: PROTOCOL_VERSION,
+            id,
+            command,
+            args,
+        }
+    }
+
+    /// Encode the request into the line that gets written to the match handler's stdin (without the trailing newline)
+    pub fn encode(&self) -> Result<String, ProtocolError> {
+        Ok(gzip_encode(&serde_json::to_string(self)?)?)
+    }
+}
+
+/// Encode a command the way that versions of `rlbot_smh` without this protocol expect it
+///
+/// These versions never reply, so there's no `id` or `version`
+pub fn encode_legacy(command: &str, args: &[String]) -> IoResult<String> {
+    let mut line = format!("{command} | ");
+    for arg in args {
+        line.push_str(arg);
+        line.push_str(" | ");
+    }
+
+    gzip_encode(&line)
+}
+
+/// The category of failure that the match handler reported
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// The match handler doesn't know the command that was sent
+    UnknownCommand,
+    /// The arguments for the command couldn't be parsed
+    InvalidArguments,
+    /// The match handler doesn't speak the same version of the protocol
+    VersionMismatch,
+    /// The command was understood, but RLBot failed to carry it out
+    Rlbot,
+    /// Any other exception raised inside of the match handler
+    #[serde(other)]
+    Internal,
+}
+
+/// Describes why the match handler failed to carry out a command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorPayload {
+    pub kind: ErrorKind,
+    pub message: String,
+    #[serde(default)]
+    pub traceback: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ReplyBody {
+    Ok {
+        #[serde(default)]
+        data: serde_json::Value,
+    },
+    Error {
+        error: ErrorPayload,
+    },
+}
+
+/// The match handler's answer to a `Request` with the same `id`
+#[derive(Debug, Clone, Deserialize)]
+pub struct Reply {
+    pub version: u32,
+    pub id: u64,
+    #[serde(flatten)]
+    pub body: ReplyBody,
+}
+
+impl Reply {
+    /// The reply for a command sent to a version of `rlbot_smh` that never replies
+    pub const fn acknowledged(id: u64) -> Self {
+        Self {
+            version: PROTOCOL_VERSION,
+            id,
+            body: ReplyBody::Ok {
+                data: serde_json::Value::Null,
+            },
+        }
+    }
+}
+
+/// Announced by the match handler once it's ready to accept commands
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadyInfo {
+    pub protocol_version: u32,
+    pub rlbot_version: String,
+    pub python_version: String,
+}
+
+/// A message from the match handler
+#[derive(Debug, Clone)]
+pub enum Frame {
+    Ready(ReadyInfo),
+    Reply(Reply),
+    /// The match handler doesn't speak this protocol, and is ready for plain command lines
+    Legacy,
+}
+
+fn strip_frame<'a>(line: &'a str, prefix: &str) -> Option<&'a str> {
+    line.trim_end().strip_prefix(prefix)?.strip_suffix(FRAME_SUFFIX)
+}
+
+/// Try to parse a line from the match handler's stdout as a frame
+///
+/// Returns `None` if the line isn't a frame, and should instead be treated as regular console output
+pub fn parse_frame(line: &str) -> Option<Result<Frame, serde_json::Error>> {
+    if line.trim_end() == LEGACY_FRAME {
+        Some(Ok(Frame::Legacy))
+    } else if let Some(json) = strip_frame(line, READY_FRAME_PREFIX) {
+        Some(serde_json::from_str(json).map(Frame::Ready))
+    } else {
+        strip_frame(line, REPLY_FRAME_PREFIX).map(|json| serde_json::from_str(json).map(Frame::Reply))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ready_frames_are_parsed() {
+        let line = r#"-|-*|MH_READY {"protocol_version": 1, "rlbot_version": "1.67.4", "python_version": "3.11.4"}|*-|-"#;
+
+        let Some(Ok(Frame::Ready(info))) = parse_frame(line) else {
+            panic!("Expected a ready frame");
+        };
+        assert_eq!(info.protocol_version, PROTOCOL_VERSION);
+        assert_eq!(info.rlbot_version, "1.67.4");
+        assert_eq!(info.python_version, "3.11.4");
+    }
+
+    #[test]
+    fn reply_frames_are_parsed() {
+        let ok = r#"-|-*|MH_REPLY {"version": 1, "id": 3, "status": "ok", "data": [1, 2]}|*-|-"#;
+        let Some(Ok(Frame::Reply(reply))) = parse_frame(ok) else {
+            panic!("Expected a reply frame");
+        };
+        assert_eq!(reply.id, 3);
+        assert!(matches!(reply.body, ReplyBody::Ok { data } if data == serde_json::json!([1, 2])));
+
+        // Windows line endings shouldn't get in the way
+        let error = "-|-*|MH_REPLY {\"version\": 1, \"id\": 4, \"status\": \"error\", \
+            \"error\": {\"kind\": \"something_new\", \"message\": \"oops\"}}|*-|-\r\n";
+        let Some(Ok(Frame::Reply(reply))) = parse_frame(error) else {
+            panic!("Expected a reply frame");
+        };
+        let ReplyBody::Error { error } = reply.body else {
+            panic!("Expected an error reply");
+        };
+        assert_eq!(error.kind, ErrorKind::Internal);
+        assert_eq!(error.message, "oops");
+        assert!(error.traceback.is_none());
+    }
+
+    #[test]
+    fn legacy_frames_are_parsed() {
+        assert!(matches!(parse_frame("-|-*|MH_LEGACY|*-|-\n"), Some(Ok(Frame::Legacy))));
+    }
+
+    #[test]
+    fn unframed_lines_are_console_output() {
+        assert!(parse_frame("Starting match handler...").is_none());
+        assert!(parse_frame("").is_none());
+        // Missing the suffix, so it's not a whole frame
+        assert!(parse_frame(r#"-|-*|MH_REPLY {"version": 1, "id": 3, "status": "ok"}"#).is_none());
+        // The prefix has to be at the start of the line
+        assert!(parse_frame(r#"log: -|-*|MH_READY {}|*-|-"#).is_none());
+    }
+
+    #[test]
+    fn malformed_frames_are_errors() {
+        assert!(matches!(parse_frame("-|-*|MH_READY {not json}|*-|-"), Some(Err(_))));
+        assert!(matches!(
+            parse_frame(r#"-|-*|MH_REPLY {"version": 1, "id": 3}|*-|-"#),
+            Some(Err(_))
+        ));
+        assert!(matches!(
+            parse_frame(r#"-|-*|MH_REPLY {"version": 1, "id": 3, "status": "maybe"}|*-|-"#),
+            Some(Err(_))
+        ));
+    }
+
+    #[test]
+    fn requests_round_trip() {
+        let args = ["blue".to_owned(), "2".to_owned()];
+        let encoded = Request::new(7, "spawn_bot", &args).encode().unwrap();
+
+        let gzipped = BASE64_STANDARD.decode(encoded).unwrap();
+        let mut json = String::new();
+        std::io::Read::read_to_string(&mut flate2::read::GzDecoder::new(&gzipped[..]), &mut json).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+            serde_json::json!({ "version": PROTOCOL_VERSION, "id": 7, "command": "spawn_bot", "args": ["blue", "2"] })
+        );
+    }
+}
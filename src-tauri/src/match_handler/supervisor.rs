@@ -0,0 +1,289 @@
+use super::{lock_match_handler, MatchHandler, MatchHandlerError};
+use crate::{
+    ccprintln, config_handles::load_gui_config_sync, reporter::Reporter, settings::MatchHandlerConfig, MATCH_HANDLER_STARTUP,
+};
+use serde::Serialize;
+use std::{
+    ffi::OsStr,
+    thread,
+    time::{Duration, Instant},
+};
+
+pub const EXITED_SIGNAL: &str = "match-handler-exited";
+pub const RESTARTED_SIGNAL: &str = "match-handler-restarted";
+
+/// How often the supervisor checks if the match handler is still running
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// If the match handler ran for at least this long before exiting, it isn't counted as a crash loop
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+/// How long to wait for the match handler to exit on it's own after being told to shut down
+const SHUT_DOWN_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ExitedPayload {
+    pub code: Option<i32>,
+    /// If the supervisor is going to try to restart the match handler
+    pub restarting: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RestartedPayload {
+    /// The exit code of the match handler that was replaced
+    pub code: Option<i32>,
+    pub pid: u32,
+    pub restarts: u32,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct MatchHandlerStatus {
+    pub running: bool,
+    pub pid: Option<u32>,
+    /// How many seconds the current match handler has been running for
+    pub uptime: Option<u64>,
+    pub python_path: Option<String>,
+    /// How many times the supervisor has restarted the match handler since it was first started
+    pub restarts: u32,
+}
+
+fn emit<S: Serialize>(reporter: &dyn Reporter, signal: &str, payload: S) {
+    if let Err(e) = reporter.emit(signal, payload) {
+        ccprintln!(reporter, "Failed to emit {signal}: {e}");
+    }
+}
+
+/// What the supervisor found when it last checked on the match handler
+enum Check {
+    Running,
+    /// The match handler was shut down or replaced, so this supervisor's job is done
+    Gone,
+    Exited {
+        code: Option<i32>,
+        handler: MatchHandler,
+    },
+}
+
+fn check(pid: u32) -> Result<Check, MatchHandlerError> {
+    let mut handler_lock = lock_match_handler()?;
+
+    let Some(handler) = handler_lock.as_mut() else {
+        return Ok(Check::Gone);
+    };
+
+    if handler.child.id() != pid {
+        return Ok(Check::Gone);
+    }
+
+    let Some(status) = handler.child.try_wait()? else {
+        return Ok(Check::Running);
+    };
+
+    Ok(handler_lock.take().map_or(Check::Gone, |handler| Check::Exited {
+        code: status.code(),
+        handler,
+    }))
+}
+
+/// Start the match handler again after it exited
+///
+/// Returns `None` if something else already started a new match handler in the meantime
+///
+/// Like when the match handler is first started, `MATCH_HANDLER` is only locked once the new match handler is ready
+fn restart(
+    reporter: &dyn Reporter,
+    old: &MatchHandler,
+    config: &MatchHandlerConfig,
+) -> Result<Option<u32>, MatchHandlerError> {
+    let _startup_lock = MATCH_HANDLER_STARTUP
+        .lock()
+        .map_err(|_| MatchHandlerError::Poisoned("MATCH_HANDLER_STARTUP"))?;
+
+    if lock_match_handler()?.is_some() {
+        return Ok(None);
+    }
+
+    ccprintln(reporter, "Restarting match handler...");
+    let mut handler = MatchHandler::spawn(old.use_pipe, OsStr::new(&old.python_path), config.startup_timeout())?;
+    handler.restarts = old.restarts + 1;
+
+    let pid = handler.child.id();
+    *lock_match_handler()? = Some(handler);
+
+    Ok(Some(pid))
+}
+
+/// Watch the match handler with the given pid from a background thread,
+/// restarting it according to the configured `RestartPolicy` if it exits on it's own
+pub fn watch(reporter: Box<dyn Reporter>, pid: u32) {
+    thread::spawn(move || supervise(&*reporter, pid));
+}
+
+fn supervise(reporter: &dyn Reporter, mut pid: u32) {
+    let mut consecutive_restarts = 0;
+
+    loop {
+        thread::sleep(POLL_INTERVAL);
+
+        let (code, handler) = match check(pid) {
+            Ok(Check::Running) => continue,
+            Ok(Check::Gone) => return,
+            Ok(Check::Exited { code, handler }) => (code, handler),
+            Err(e) => {
+                ccprintln!(reporter, "Match handler supervisor stopped: {e}");
+                return;
+            }
+        };
+
+        if handler.started_at.elapsed() >= STABLE_UPTIME {
+            consecutive_restarts = 0;
+        }
+
+        let config = MatchHandlerConfig::load_from_conf(&load_gui_config_sync(reporter));
+        let restarting = config.restart_policy.should_restart(code) && consecutive_restarts < config.max_restarts;
+
+        ccprintln!(
+            reporter,
+            "Match handler exited with code {}",
+            code.map_or_else(|| "unknown".to_string(), |code| code.to_string())
+        );
+        emit(reporter, EXITED_SIGNAL, ExitedPayload { code, restarting });
+
+        if !restarting {
+            if consecutive_restarts >= config.max_restarts {
+                ccprintln!(
+                    reporter,
+                    "Not restarting match handler after {consecutive_restarts} restarts in a row"
+                );
+            }
+            return;
+        }
+
+        // Keep trying until a restart succeeds or we run out of attempts
+        loop {
+            thread::sleep(config.restart_delay(consecutive_restarts));
+            consecutive_restarts += 1;
+
+            match restart(reporter, &handler, &config) {
+                Ok(Some(new_pid)) => {
+                    pid = new_pid;
+                    emit(
+                        reporter,
+                        RESTARTED_SIGNAL,
+                        RestartedPayload {
+                            code,
+                            pid,
+                            restarts: handler.restarts + 1,
+                        },
+                    );
+                    break;
+                }
+                Ok(None) => return,
+                Err(e) => {
+                    ccprintln!(reporter, "Failed to restart match handler: {e}");
+                    if consecutive_restarts >= config.max_restarts {
+                        ccprintln!(
+                            reporter,
+                            "Not restarting match handler after {consecutive_restarts} restarts in a row"
+                        );
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Get information about the currently running match handler
+pub fn status() -> Result<MatchHandlerStatus, MatchHandlerError> {
+    let handler_lock = lock_match_handler()?;
+
+    Ok(handler_lock
+        .as_ref()
+        .map_or_else(MatchHandlerStatus::default, |handler| MatchHandlerStatus {
+            running: true,
+            pid: Some(handler.child.id()),
+            uptime: Some(handler.started_at.elapsed().as_secs()),
+            python_path: Some(handler.python_path.clone()),
+            restarts: handler.restarts,
+        }))
+}
+
+/// Tells the match handler to shut down, then waits for it to exit
+///
+/// Taking the match handler out of `MATCH_HANDLER` also stops the supervisor, so it won't be restarted.
+/// The match handler is killed if it's still running after 15 seconds
+pub fn shut_down() -> Result<(), MatchHandlerError> {
+    let handler = lock_match_handler()?.take();
+
+    let Some(mut handler) = handler else {
+        return Ok(());
+    };
+
+    // The match handler exits right after getting this command, so there won't be a reply
+    if let Err(e) = handler.send("shut_down", &[]) {
+        println!("Couldn't tell the match handler to shut down: {e}");
+    }
+
+    let MatchHandler { mut child, stdin, .. } = handler;
+    drop(stdin);
+
+    // Wait for the child to exit on it's own, then kill it if it's still running
+    let start_time = Instant::now();
+    let pause_duration = Duration::from_secs_f32(0.25);
+
+    while start_time.elapsed() < SHUT_DOWN_TIMEOUT {
+        if let Ok(Some(_)) = child.try_wait() {
+            return Ok(());
+        }
+
+        thread::sleep(pause_duration);
+    }
+
+    child.kill()?;
+    child.wait()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reporter::MemoryReporter;
+
+    #[test]
+    fn payloads_are_sent_to_the_reporter() {
+        let reporter = MemoryReporter::new();
+
+        emit(
+            &reporter,
+            EXITED_SIGNAL,
+            ExitedPayload {
+                code: Some(1),
+                restarting: true,
+            },
+        );
+        emit(
+            &reporter,
+            RESTARTED_SIGNAL,
+            RestartedPayload {
+                code: Some(1),
+                pid: 1234,
+                restarts: 1,
+            },
+        );
+
+        let events = reporter.log().events;
+        assert_eq!(events[0].0, EXITED_SIGNAL);
+        assert_eq!(events[0].1["restarting"], true);
+        assert_eq!(events[1].0, RESTARTED_SIGNAL);
+        assert_eq!(events[1].1["pid"], 1234);
+    }
+
+    #[test]
+    fn status_without_a_match_handler() {
+        let status = status().unwrap();
+
+        assert!(!status.running);
+        assert_eq!(status.pid, None);
+        assert_eq!(status.restarts, 0);
+    }
+}
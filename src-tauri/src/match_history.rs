@@ -0,0 +1,573 @@
+use crate::{
+    ccprintln, get_content_folder, impl_serialize_from_display, ratings,
+    recordings::unix_time,
+    reporter::Reporter,
+    rlbot::{
+        packet_stream::latest_game_tick_packet,
+        parsing::match_settings_config_parser::{GameMode, MapType},
+    },
+    settings::{GameTickPacket, MatchPhase, MiniMatchConfig, ScoreInfo, ScoreResult, Team, TeamBotBundle},
+    MATCH_TRACKER,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+use tauri::async_runtime::block_on as tauri_block_on;
+use thiserror::Error;
+
+pub const HISTORY_FILE: &str = "match_history.ndjson";
+pub const MATCH_RECORDED_SIGNAL: &str = "match-recorded";
+
+/// How often the tracker checks on the match
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// How long to wait for the match to start before giving up, which includes the time it takes to launch Rocket League
+const MATCH_START_TIMEOUT: Duration = Duration::from_secs(300);
+/// How long there can be no packets at all before the match is assumed to have been closed
+const PACKET_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Error)]
+pub enum HistoryError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+    #[error("There's no match with the id \"{0}\" in the history")]
+    NotFound(String),
+    #[error("The match tracking thread panicked")]
+    ThreadPanicked,
+    #[error("Mutex {0} was poisoned")]
+    Poisoned(&'static str),
+}
+
+impl_serialize_from_display!(HistoryError);
+
+/// A bot, human or Psyonix bot that played in a match
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Participant {
+    pub name: String,
+    pub team: Team,
+    pub runnable_type: String,
+    pub skill: f32,
+    /// The path to the bot's config file, if it has one
+    pub path: Option<String>,
+    /// The player's stats at the end of the match, if their car could be found
+    pub stats: Option<ScoreInfo>,
+}
+
+impl Participant {
+    /// If `bot` is this participant's name (ignoring case) or the path to their config file
+    pub fn is(&self, bot: &str) -> bool {
+        self.name.eq_ignore_ascii_case(bot) || self.path.as_deref() == Some(bot)
+    }
+}
+
+/// A match that was played, and how it went
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MatchRecord {
+    pub id: String,
+    /// When the match was started, in seconds since the Unix epoch
+    pub started_at: u64,
+    /// When the match ended, in seconds since the Unix epoch
+    pub ended_at: u64,
+    /// How much game time the match lasted, in seconds
+    pub duration: f32,
+    /// If the match played until the end, instead of being stopped or closed early
+    pub completed: bool,
+    pub participants: Vec<Participant>,
+    pub score: Vec<ScoreResult>,
+    /// `None` if the match was a draw or didn't finish
+    pub winner: Option<Team>,
+    pub match_settings: MiniMatchConfig,
+    /// If the match was started by an A/B test or a regression suite, which are left out of the ratings
+    #[serde(default)]
+    pub automated: bool,
+}
+
+/// Filters for the match history, where every filter that's set has to match
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct HistoryQuery {
+    /// Only include matches that this bot played in, by name or config path
+    pub bot: Option<String>,
+    /// Only include matches that this bot played in, on the opposite team to `bot` if it's set
+    pub opponent: Option<String>,
+    /// Only include matches that started at or after this time, in seconds since the Unix epoch
+    pub since: Option<u64>,
+    /// Only include matches that started before this time, in seconds since the Unix epoch
+    pub until: Option<u64>,
+    pub map: Option<MapType>,
+    pub game_mode: Option<GameMode>,
+    /// Leave out matches that were stopped or closed early
+    pub completed_only: bool,
+}
+
+impl HistoryQuery {
+    pub fn matches(&self, record: &MatchRecord) -> bool {
+        if self.completed_only && !record.completed {
+            return false;
+        }
+
+        if self.since.map_or(false, |since| record.started_at < since)
+            || self.until.map_or(false, |until| record.started_at >= until)
+        {
+            return false;
+        }
+
+        if self.map.as_ref().map_or(false, |map| &record.match_settings.map != map)
+            || self.game_mode.map_or(false, |mode| record.match_settings.game_mode != mode)
+        {
+            return false;
+        }
+
+        let find = |bot: &str| record.participants.iter().filter(move |participant| participant.is(bot));
+
+        match (&self.bot, &self.opponent) {
+            (Some(bot), Some(opponent)) => {
+                find(bot).any(|participant| find(opponent).any(|other| other.team != participant.team))
+            }
+            (Some(bot), None) | (None, Some(bot)) => find(bot).next().is_some(),
+            (None, None) => true,
+        }
+    }
+}
+
+pub fn get_history_path() -> PathBuf {
+    get_content_folder().join(HISTORY_FILE)
+}
+
+fn score_of(packet: &GameTickPacket, team: &Team) -> usize {
+    let index = team.clone() as i32;
+    packet
+        .teams
+        .iter()
+        .find(|info| info.team_index == index)
+        .map_or(0, |info| info.score.max(0) as usize)
+}
+
+impl MatchRecord {
+    /// Put together the record of a match from its last packet
+    ///
+    /// # Arguments
+    ///
+    /// * `bots` - The bots that were in the match, in the same order as the cars in the packet
+    /// * `match_settings` - The settings that the match was started with
+    /// * `started_at` - When the match was started, in seconds since the Unix epoch
+    /// * `duration` - How much game time the match lasted, in seconds
+    /// * `packet` - The last packet of the match
+    /// * `automated` - If the match was started by an A/B test or a regression suite
+    fn new(
+        bots: Vec<TeamBotBundle>,
+        match_settings: MiniMatchConfig,
+        started_at: u64,
+        duration: f32,
+        packet: &GameTickPacket,
+        automated: bool,
+    ) -> Self {
+        let completed = packet.game_info.phase == MatchPhase::Ended;
+        // RLBot spawns the cars in the same order as the bots, so they can only be matched up if nothing was added or removed
+        let cars_match = packet.game_cars.len() == bots.len();
+
+        let participants = bots
+            .into_iter()
+            .enumerate()
+            .map(|(index, bot)| Participant {
+                stats: cars_match.then(|| packet.game_cars[index].score_info),
+                name: bot.name,
+                team: bot.team,
+                runnable_type: bot.runnable_type,
+                skill: bot.skill,
+                path: bot.path,
+            })
+            .collect();
+
+        let score = [Team::Blue, Team::Orange]
+            .into_iter()
+            .map(|team| ScoreResult {
+                score: score_of(packet, &team),
+                team_index: team,
+            })
+            .collect::<Vec<_>>();
+
+        let winner = if completed {
+            match score[0].score.cmp(&score[1].score) {
+                std::cmp::Ordering::Greater => Some(Team::Blue),
+                std::cmp::Ordering::Less => Some(Team::Orange),
+                std::cmp::Ordering::Equal => None,
+            }
+        } else {
+            None
+        };
+
+        Self {
+            id: format!("{started_at}-{:08x}", rand::thread_rng().gen::<u32>()),
+            started_at,
+            ended_at: unix_time(),
+            duration,
+            completed,
+            participants,
+            score,
+            winner,
+            match_settings,
+            automated,
+        }
+    }
+}
+
+fn append_record(record: &MatchRecord) -> Result<(), HistoryError> {
+    let path = get_history_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    let mut line = serde_json::to_vec(record)?;
+    line.push(b'\n');
+    file.write_all(&line)?;
+
+    Ok(())
+}
+
+/// Read every match in the history, in the order that they were played
+///
+/// Lines that aren't a match are skipped, and reported to `reporter`
+fn read_history(reporter: &dyn Reporter) -> Result<Vec<MatchRecord>, HistoryError> {
+    let path = get_history_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut records = Vec::new();
+
+    for (index, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str(&line) {
+            Ok(record) => records.push(record),
+            Err(e) => ccprintln!(reporter, "Skipping line {} of the match history: {e}", index + 1),
+        }
+    }
+
+    Ok(records)
+}
+
+/// Get the matches in the history that match the query, newest first
+///
+/// # Arguments
+///
+/// * `reporter` - Where to report lines of the history that couldn't be read
+/// * `query` - The filters that the matches have to match
+pub fn query_history(reporter: &dyn Reporter, query: &HistoryQuery) -> Result<Vec<MatchRecord>, HistoryError> {
+    let mut records = read_history(reporter)?;
+    records.retain(|record| query.matches(record));
+    records.reverse();
+
+    Ok(records)
+}
+
+pub fn get_record(reporter: &dyn Reporter, id: &str) -> Result<MatchRecord, HistoryError> {
+    read_history(reporter)?
+        .into_iter()
+        .find(|record| record.id == id)
+        .ok_or_else(|| HistoryError::NotFound(id.to_owned()))
+}
+
+pub fn delete_record(reporter: &dyn Reporter, id: &str) -> Result<(), HistoryError> {
+    let mut records = read_history(reporter)?;
+    let count = records.len();
+    records.retain(|record| record.id != id);
+
+    if records.len() == count {
+        return Err(HistoryError::NotFound(id.to_owned()));
+    }
+
+    let mut writer = BufWriter::new(File::create(get_history_path())?);
+    for record in &records {
+        serde_json::to_writer(&mut writer, record)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Watches a match from a background thread and adds it to the history when it ends
+pub struct MatchTracker {
+    running: Arc<AtomicBool>,
+    thread: JoinHandle<Result<Option<MatchRecord>, HistoryError>>,
+}
+
+impl MatchTracker {
+    fn start(
+        reporter: Box<dyn Reporter>,
+        bots: Vec<TeamBotBundle>,
+        match_settings: MiniMatchConfig,
+        automated: bool,
+    ) -> Self {
+        let running = Arc::new(AtomicBool::new(true));
+        let thread_running = running.clone();
+
+        let thread = thread::spawn(move || {
+            let started_at = unix_time();
+            let start_time = Instant::now();
+
+            // The first packet could be left over from the last match, so the match only counts as started once it changes
+            let mut first_key = None;
+            let mut start_seconds = None;
+            let mut last_packet = None;
+            let mut last_seen = Instant::now();
+
+            while thread_running.load(Ordering::Relaxed) {
+                match tauri_block_on(latest_game_tick_packet(&*reporter)) {
+                    Ok(Some(packet)) => {
+                        last_seen = Instant::now();
+                        let key = (packet.game_info.frame_num, packet.game_info.seconds_elapsed.to_bits());
+
+                        if start_seconds.is_none() && *first_key.get_or_insert(key) != key {
+                            if packet.game_info.phase != MatchPhase::Ended {
+                                start_seconds = Some(packet.game_info.seconds_elapsed);
+                            } else {
+                                // The last match ended, but this one hasn't started yet
+                                first_key = Some(key);
+                            }
+                        }
+
+                        if start_seconds.is_some() {
+                            let ended = packet.game_info.phase == MatchPhase::Ended;
+                            last_packet = Some(packet);
+
+                            if ended {
+                                break;
+                            }
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => ccprintln!(&*reporter, "Couldn't get game tick packet to track the match: {e}"),
+                }
+
+                if start_seconds.is_none() && start_time.elapsed() >= MATCH_START_TIMEOUT {
+                    ccprintln(
+                        &*reporter,
+                        "The match didn't start, so it won't be added to the match history",
+                    );
+                    return Ok(None);
+                }
+
+                if start_seconds.is_some() && last_seen.elapsed() >= PACKET_TIMEOUT {
+                    break;
+                }
+
+                thread::sleep(POLL_INTERVAL);
+            }
+
+            let (Some(start_seconds), Some(packet)) = (start_seconds, last_packet) else {
+                return Ok(None);
+            };
+
+            let duration = (packet.game_info.seconds_elapsed - start_seconds).max(0.);
+            let record = MatchRecord::new(bots, match_settings, started_at, duration, &packet, automated);
+            append_record(&record)?;
+
+            if let Err(e) = ratings::rate_match(&record) {
+                ccprintln!(&*reporter, "Couldn't update the ratings with the match: {e}");
+            }
+
+            if let Err(e) = reporter.emit(MATCH_RECORDED_SIGNAL, &record) {
+                ccprintln!(&*reporter, "Failed to emit {MATCH_RECORDED_SIGNAL}: {e}");
+            }
+
+            Ok(Some(record))
+        });
+
+        Self { running, thread }
+    }
+
+    /// Stop tracking the match, adding it to the history as unfinished if it had started
+    fn stop(self) -> Result<Option<MatchRecord>, HistoryError> {
+        self.running.store(false, Ordering::Relaxed);
+        self.wait()
+    }
+
+    /// Wait for the match to end, and get its record
+    fn wait(self) -> Result<Option<MatchRecord>, HistoryError> {
+        self.thread.join().map_err(|_| HistoryError::ThreadPanicked)?
+    }
+}
+
+/// Start tracking a match that was just started, replacing the tracker of the previous match
+///
+/// # Arguments
+///
+/// * `reporter` - Where to send console output and events, like the GUI
+/// * `bots` - The bots in the match
+/// * `match_settings` - The settings that the match was started with
+/// * `automated` - If the match was started by an A/B test or a regression suite, so it won't be rated
+pub fn start_tracking(
+    reporter: Box<dyn Reporter>,
+    bots: Vec<TeamBotBundle>,
+    match_settings: MiniMatchConfig,
+    automated: bool,
+) -> Result<(), HistoryError> {
+    let mut tracker = MATCH_TRACKER.lock().map_err(|_| HistoryError::Poisoned("MATCH_TRACKER"))?;
+
+    if let Some(old_tracker) = tracker.take() {
+        if let Err(e) = old_tracker.stop() {
+            ccprintln!(&*reporter, "Couldn't add the previous match to the match history: {e}");
+        }
+    }
+
+    *tracker = Some(MatchTracker::start(reporter, bots, match_settings, automated));
+
+    Ok(())
+}
+
+/// Block until the match that's being tracked ends, and get its record
+///
+/// Returns `None` if no match is being tracked, or if the match never started
+pub fn await_match_result() -> Result<Option<MatchRecord>, HistoryError> {
+    let tracker = MATCH_TRACKER
+        .lock()
+        .map_err(|_| HistoryError::Poisoned("MATCH_TRACKER"))?
+        .take();
+
+    tracker.map_or(Ok(None), MatchTracker::wait)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn participant(name: &str, team: Team) -> Participant {
+        Participant {
+            name: name.to_owned(),
+            team,
+            runnable_type: "rlbot".to_owned(),
+            skill: 1.,
+            path: Some(format!("bots/{name}.cfg")),
+            stats: None,
+        }
+    }
+
+    /// Atba on blue against Nexto and Kamael on orange, in a completed soccer match on DFH Stadium
+    fn record() -> MatchRecord {
+        MatchRecord {
+            id: "100-00000000".to_owned(),
+            started_at: 100,
+            ended_at: 400,
+            duration: 300.,
+            completed: true,
+            participants: vec![
+                participant("Atba", Team::Blue),
+                participant("Nexto", Team::Orange),
+                participant("Kamael", Team::Orange),
+            ],
+            score: Vec::new(),
+            winner: Some(Team::Orange),
+            match_settings: MiniMatchConfig::default(),
+            automated: false,
+        }
+    }
+
+    #[test]
+    fn empty_query_matches_everything() {
+        assert!(HistoryQuery::default().matches(&record()));
+    }
+
+    #[test]
+    fn bots_are_found_by_name_or_path() {
+        let query = |bot: &str| HistoryQuery {
+            bot: Some(bot.to_owned()),
+            ..Default::default()
+        };
+
+        assert!(query("nexto").matches(&record()));
+        assert!(query("bots/Kamael.cfg").matches(&record()));
+        assert!(!query("Botimus").matches(&record()));
+        assert!(!query("bots/nexto.cfg").matches(&record()));
+    }
+
+    #[test]
+    fn opponents_have_to_be_on_the_other_team() {
+        let query = |bot: Option<&str>, opponent: &str| HistoryQuery {
+            bot: bot.map(str::to_owned),
+            opponent: Some(opponent.to_owned()),
+            ..Default::default()
+        };
+
+        assert!(query(Some("Atba"), "Nexto").matches(&record()));
+        assert!(query(Some("Kamael"), "Atba").matches(&record()));
+        assert!(!query(Some("Nexto"), "Kamael").matches(&record()));
+        assert!(!query(Some("Atba"), "Botimus").matches(&record()));
+        // Without a bot, the opponent only has to have played
+        assert!(query(None, "Kamael").matches(&record()));
+    }
+
+    #[test]
+    fn mirror_matches_have_opponents() {
+        let mut mirror = record();
+        mirror.participants = vec![participant("Nexto", Team::Blue), participant("Nexto", Team::Orange)];
+
+        let query = HistoryQuery {
+            bot: Some("Nexto".to_owned()),
+            opponent: Some("Nexto".to_owned()),
+            ..Default::default()
+        };
+        assert!(query.matches(&mirror));
+        assert!(!query.matches(&record()));
+    }
+
+    #[test]
+    fn time_range_includes_since_but_not_until() {
+        let query = |since: Option<u64>, until: Option<u64>| HistoryQuery {
+            since,
+            until,
+            ..Default::default()
+        };
+
+        assert!(query(Some(100), None).matches(&record()));
+        assert!(!query(Some(101), None).matches(&record()));
+        assert!(query(None, Some(101)).matches(&record()));
+        assert!(!query(None, Some(100)).matches(&record()));
+        assert!(query(Some(50), Some(150)).matches(&record()));
+    }
+
+    #[test]
+    fn map_and_game_mode_have_to_match() {
+        let query = |map: Option<MapType>, game_mode: Option<GameMode>| HistoryQuery {
+            map,
+            game_mode,
+            ..Default::default()
+        };
+
+        assert!(query(Some(MapType::DfhStadium), Some(GameMode::Soccer)).matches(&record()));
+        assert!(!query(Some(MapType::Mannfield), None).matches(&record()));
+        assert!(!query(None, Some(GameMode::Hoops)).matches(&record()));
+    }
+
+    #[test]
+    fn completed_only_leaves_out_unfinished_matches() {
+        let mut unfinished = record();
+        unfinished.completed = false;
+        unfinished.winner = None;
+
+        let query = HistoryQuery {
+            completed_only: true,
+            ..Default::default()
+        };
+        assert!(query.matches(&record()));
+        assert!(!query.matches(&unfinished));
+        assert!(HistoryQuery::default().matches(&unfinished));
+    }
+}
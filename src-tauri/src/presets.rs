@@ -0,0 +1,193 @@
+use crate::{
+    content::{read_json, write_json, ContentError, ContentFolder},
+    impl_serialize_from_display,
+    reporter::Reporter,
+    settings::MiniMatchConfig,
+};
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::Path};
+use thiserror::Error;
+
+pub const PRESET_EXTENSION: &str = "json";
+pub const PRESETS: ContentFolder = ContentFolder {
+    folder: "presets",
+    extension: PRESET_EXTENSION,
+    kind: "preset",
+};
+
+#[derive(Debug, Error)]
+pub enum PresetError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Content(#[from] ContentError),
+}
+
+impl_serialize_from_display!(PresetError);
+
+/// A named match setup that can be loaded again later, or shared as a standalone file
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MatchPreset {
+    pub name: String,
+    /// The match settings, including the mutators and the paths of the scripts
+    pub match_settings: MiniMatchConfig,
+    /// The bots on the blue team, in the same `(skill, path)` form that `config.ini` uses
+    pub blue_team: Vec<(Option<f32>, String)>,
+    /// The bots on the orange team, in the same `(skill, path)` form that `config.ini` uses
+    pub orange_team: Vec<(Option<f32>, String)>,
+}
+
+/// Get all of the saved presets, sorted by name
+///
+/// # Arguments
+///
+/// * `reporter` - Where to report the presets that couldn't be read
+pub fn list_presets(reporter: &dyn Reporter) -> Result<Vec<MatchPreset>, PresetError> {
+    let mut presets = PRESETS.list(reporter, read_json::<MatchPreset>)?;
+    presets.sort_by_key(|preset| preset.name.to_lowercase());
+
+    Ok(presets)
+}
+
+/// Save a preset, replacing any existing preset with the same name
+pub fn save_preset(mut preset: MatchPreset) -> Result<(), PresetError> {
+    let path = PRESETS.item_path(&preset.name)?;
+    preset.name = preset.name.trim().to_owned();
+
+    Ok(write_json(&path, &preset)?)
+}
+
+pub fn get_preset(name: &str) -> Result<MatchPreset, PresetError> {
+    Ok(read_json(&PRESETS.existing_item_path(name)?)?)
+}
+
+pub fn rename_preset(old_name: &str, new_name: &str) -> Result<(), PresetError> {
+    let old_path = PRESETS.existing_item_path(old_name)?;
+    let new_path = PRESETS.new_item_path(new_name)?;
+
+    // The name is also stored in the file, so that it survives being exported
+    let mut preset: MatchPreset = read_json(&old_path)?;
+    preset.name = new_name.trim().to_owned();
+    write_json(&new_path, &preset)?;
+    fs::remove_file(old_path)?;
+
+    Ok(())
+}
+
+pub fn delete_preset(name: &str) -> Result<(), PresetError> {
+    fs::remove_file(PRESETS.existing_item_path(name)?)?;
+    Ok(())
+}
+
+/// Copy a preset to a standalone file
+///
+/// # Arguments
+///
+/// * `name` - The name of the preset to export
+/// * `destination` - Where to write the preset to
+pub fn export_preset(name: &str, destination: &Path) -> Result<(), PresetError> {
+    fs::copy(PRESETS.existing_item_path(name)?, destination)?;
+    Ok(())
+}
+
+/// Add a preset from a standalone file, like one that was exported on another computer
+///
+/// Returns the imported preset
+///
+/// # Arguments
+///
+/// * `source` - The file to import the preset from
+pub fn import_preset(source: &Path) -> Result<MatchPreset, PresetError> {
+    let mut preset: MatchPreset = read_json(source)?;
+    let path = PRESETS.new_item_path(&preset.name)?;
+
+    preset.name = preset.name.trim().to_owned();
+    write_json(&path, &preset)?;
+
+    Ok(preset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{content::set_test_content_folder, reporter::MemoryReporter};
+    use tempfile::TempDir;
+
+    fn preset(name: &str) -> MatchPreset {
+        MatchPreset {
+            name: name.to_owned(),
+            match_settings: MiniMatchConfig::default(),
+            blue_team: vec![(None, "bots/Nexto/bot.cfg".to_owned()), (Some(1.), "psyonix".to_owned())],
+            orange_team: vec![(Some(0.5), "psyonix".to_owned())],
+        }
+    }
+
+    /// Presets don't implement `PartialEq`, so they're compared as JSON
+    fn json(preset: &MatchPreset) -> serde_json::Value {
+        serde_json::to_value(preset).unwrap()
+    }
+
+    #[test]
+    fn exported_presets_can_be_imported() {
+        let content_folder = TempDir::new().unwrap();
+        set_test_content_folder(content_folder.path());
+
+        save_preset(preset(" Weekend league ")).unwrap();
+        let saved = get_preset("Weekend league").unwrap();
+        assert_eq!(saved.name, "Weekend league");
+
+        let exported = content_folder.path().join("Weekend league.json");
+        export_preset("Weekend league", &exported).unwrap();
+        delete_preset("Weekend league").unwrap();
+        assert!(matches!(
+            get_preset("Weekend league"),
+            Err(PresetError::Content(ContentError::NotFound(..)))
+        ));
+
+        let imported = import_preset(&exported).unwrap();
+        assert_eq!(json(&imported), json(&saved));
+        assert_eq!(json(&get_preset("Weekend league").unwrap()), json(&saved));
+    }
+
+    #[test]
+    fn imports_dont_replace_existing_presets() {
+        let content_folder = TempDir::new().unwrap();
+        set_test_content_folder(content_folder.path());
+
+        save_preset(preset("Weekend league")).unwrap();
+        let source = content_folder.path().join("import.json");
+        // The name is trimmed before it's checked
+        write_json(&source, &preset("Weekend league ")).unwrap();
+        assert!(matches!(
+            import_preset(&source),
+            Err(PresetError::Content(ContentError::AlreadyExists(..)))
+        ));
+
+        write_json(&source, &preset("../Weekend league")).unwrap();
+        assert!(matches!(
+            import_preset(&source),
+            Err(PresetError::Content(ContentError::InvalidName(..)))
+        ));
+    }
+
+    #[test]
+    fn broken_presets_are_skipped_and_reported() {
+        let content_folder = TempDir::new().unwrap();
+        set_test_content_folder(content_folder.path());
+
+        save_preset(preset("b")).unwrap();
+        save_preset(preset("A")).unwrap();
+        fs::write(PRESETS.item_path("broken").unwrap(), "{").unwrap();
+
+        let reporter = MemoryReporter::new();
+        let names = list_presets(&reporter)
+            .unwrap()
+            .into_iter()
+            .map(|preset| preset.name)
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, ["A", "b"]);
+        assert_eq!(reporter.lines().len(), 1);
+        assert!(reporter.lines()[0].starts_with("Skipping preset "));
+    }
+}
@@ -0,0 +1,310 @@
+use crate::{
+    impl_serialize_from_display,
+    rlbot::parsing::bot_config_bundle::BotConfigBundle,
+    settings::{Team, TeamBotBundle},
+};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The skills of the Psyonix bots that can be picked: Rookie, Pro and Allstar
+const PSYONIX_SKILLS: [f32; 3] = [0., 0.5, 1.];
+/// RLBot supports at most 64 players in a match
+const MAX_TEAM_SIZE: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum RandomMatchError {
+    #[error("Team size must be between 1 and {MAX_TEAM_SIZE}, but was {0}")]
+    InvalidTeamSize(usize),
+    #[error("{0} bots are needed, but only {1} match the constraints")]
+    NotEnoughBots(usize, usize),
+    #[error("{0} bots were given to include, but there's only room for {1}")]
+    TooManyIncluded(usize, usize),
+    #[error("The bot {0} couldn't be found")]
+    UnknownBot(String),
+}
+
+impl_serialize_from_display!(RandomMatchError);
+
+/// What the bots in a randomly generated match have to be like
+///
+/// Language and tag constraints only apply to scanned bots, since Psyonix bots don't have any dev info
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct MatchConstraints {
+    /// How many players go on each team
+    pub team_size: usize,
+    /// Languages from the bots' dev info, ignoring case - any language is allowed if this is empty
+    pub languages: Vec<String>,
+    /// Tags that every bot has to have, ignoring case
+    pub required_tags: Vec<String>,
+    /// Tags that no bot can have, ignoring case
+    pub excluded_tags: Vec<String>,
+    /// Whether or not Psyonix bots can be picked
+    pub include_psyonix: bool,
+    /// Paths to the config files of bots that are always in the match, like our own bot, which go on blue first
+    pub include: Vec<String>,
+    /// Whether or not the same bot can be picked more than once
+    pub allow_duplicates: bool,
+    /// The same seed with the same bots and constraints always gives the same match
+    pub seed: Option<u64>,
+}
+
+impl Default for MatchConstraints {
+    fn default() -> Self {
+        Self {
+            team_size: 1,
+            languages: Vec::new(),
+            required_tags: Vec::new(),
+            excluded_tags: Vec::new(),
+            include_psyonix: false,
+            include: Vec::new(),
+            allow_duplicates: false,
+            seed: None,
+        }
+    }
+}
+
+impl MatchConstraints {
+    fn allows(&self, bot: &BotConfigBundle) -> bool {
+        if self.include.contains(&bot.path) {
+            return false;
+        }
+
+        let language = bot.info.as_ref().map_or("", |info| info.language.as_str());
+        let has_tag = |tag: &String| {
+            bot.info.as_ref().map_or(false, |info| {
+                info.tags
+                    .iter()
+                    .any(|bot_tag| bot_tag.trim().eq_ignore_ascii_case(tag.trim()))
+            })
+        };
+
+        (self.languages.is_empty()
+            || self
+                .languages
+                .iter()
+                .any(|allowed| allowed.trim().eq_ignore_ascii_case(language.trim())))
+            && self.required_tags.iter().all(has_tag)
+            && !self.excluded_tags.iter().any(has_tag)
+    }
+}
+
+/// A randomly generated match, ready to be passed to `start_match`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GeneratedMatch {
+    /// The seed that was used, which can be passed back in to get the same match again
+    pub seed: u64,
+    pub bot_list: Vec<TeamBotBundle>,
+}
+
+/// Pick the bots for a match at random
+///
+/// # Arguments
+///
+/// * `pool` - The bots to pick from, like the ones found by `scan_for_bots`
+/// * `constraints` - What the picked bots have to be like
+pub fn generate_match(pool: &[BotConfigBundle], constraints: &MatchConstraints) -> Result<GeneratedMatch, RandomMatchError> {
+    if constraints.team_size == 0 || constraints.team_size > MAX_TEAM_SIZE {
+        return Err(RandomMatchError::InvalidTeamSize(constraints.team_size));
+    }
+
+    let needed = constraints.team_size * 2;
+    if constraints.include.len() > needed {
+        return Err(RandomMatchError::TooManyIncluded(constraints.include.len(), needed));
+    }
+
+    let included = constraints
+        .include
+        .iter()
+        .map(|path| {
+            pool.iter()
+                .find(|bot| &bot.path == path)
+                .cloned()
+                .ok_or_else(|| RandomMatchError::UnknownBot(path.clone()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut candidates = pool.iter().filter(|bot| constraints.allows(bot)).cloned().collect::<Vec<_>>();
+
+    if constraints.include_psyonix {
+        candidates.extend(PSYONIX_SKILLS.into_iter().map(BotConfigBundle::new_psyonix));
+    }
+
+    // The scan order isn't stable, so sort the pool to make sure that a seed always gives the same match
+    candidates.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.name.cmp(&b.name)));
+    candidates.dedup_by(|a, b| a.path == b.path && a.name == b.name);
+
+    let to_pick = needed - included.len();
+    if (candidates.is_empty() && to_pick > 0) || (!constraints.allow_duplicates && candidates.len() < to_pick) {
+        return Err(RandomMatchError::NotEnoughBots(to_pick, candidates.len()));
+    }
+
+    let seed = constraints.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    // Unlike StdRng, ChaCha8Rng is guaranteed to give the same numbers in every version, so seeds stay reproducible
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+    let picked = if constraints.allow_duplicates {
+        (0..to_pick)
+            .filter_map(|_| candidates.choose(&mut rng).cloned())
+            .collect::<Vec<_>>()
+    } else {
+        candidates.shuffle(&mut rng);
+        candidates.truncate(to_pick);
+        candidates
+    };
+
+    let bot_list = included
+        .iter()
+        .chain(&picked)
+        .enumerate()
+        .map(|(index, bot)| {
+            let team = if index < constraints.team_size {
+                Team::Blue
+            } else {
+                Team::Orange
+            };
+            TeamBotBundle::from_bundle(bot, team)
+        })
+        .collect();
+
+    Ok(GeneratedMatch { seed, bot_list })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rlbot::parsing::bot_config_bundle::DevInfo;
+
+    fn bot(name: &str, language: &str, tags: &[&str]) -> BotConfigBundle {
+        BotConfigBundle {
+            name: name.to_owned(),
+            path: format!("bots/{name}/bot.cfg"),
+            runnable_type: "rlbot".to_owned(),
+            info: Some(DevInfo {
+                developer: String::new(),
+                description: String::new(),
+                fun_fact: String::new(),
+                github: String::new(),
+                language: language.to_owned(),
+                tags: tags.iter().map(|tag| (*tag).to_owned()).collect(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn pool() -> Vec<BotConfigBundle> {
+        vec![
+            bot("Atba", "python", &["1v1"]),
+            bot("Botimus", "python", &["1v1", "teamplay"]),
+            bot("Kamael", "python", &["teamplay"]),
+            bot("Nexto", "python", &["1v1", "teamplay"]),
+            bot("Penguin", "rust", &["1v1"]),
+            bot("Snek", "java", &["memebot"]),
+        ]
+    }
+
+    fn names(generated: &GeneratedMatch) -> Vec<(&str, Team)> {
+        generated
+            .bot_list
+            .iter()
+            .map(|bot| (bot.name.as_str(), bot.team.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_match() {
+        let constraints = MatchConstraints {
+            team_size: 2,
+            excluded_tags: vec!["memebot".to_owned()],
+            include_psyonix: true,
+            seed: Some(42),
+            ..Default::default()
+        };
+
+        let generated = generate_match(&pool(), &constraints).unwrap();
+        assert_eq!(generated.seed, 42);
+
+        // The order that the bots were scanned in doesn't matter
+        let mut reversed = pool();
+        reversed.reverse();
+        assert_eq!(names(&generate_match(&reversed, &constraints).unwrap()), names(&generated));
+
+        // ChaCha8Rng gives the same numbers in every version of rand, so this can't change without changing the picks
+        assert_eq!(
+            names(&generated),
+            [
+                ("Psyonix Allstar", Team::Blue),
+                ("Atba", Team::Blue),
+                ("Botimus", Team::Orange),
+                ("Nexto", Team::Orange),
+            ]
+        );
+    }
+
+    #[test]
+    fn constraints_are_followed() {
+        let constraints = MatchConstraints {
+            languages: vec![" Python".to_owned()],
+            required_tags: vec!["TEAMPLAY".to_owned()],
+            include: vec!["bots/Snek/bot.cfg".to_owned()],
+            ..Default::default()
+        };
+
+        for seed in 0..20 {
+            let generated = generate_match(
+                &pool(),
+                &MatchConstraints {
+                    seed: Some(seed),
+                    ..constraints.clone()
+                },
+            )
+            .unwrap();
+            let names = names(&generated);
+
+            assert_eq!(names[0], ("Snek", Team::Blue));
+            assert!(["Botimus", "Kamael", "Nexto"].contains(&names[1].0));
+            assert_eq!(names[1].1, Team::Orange);
+        }
+    }
+
+    #[test]
+    fn impossible_constraints_are_errors() {
+        let too_picky = MatchConstraints {
+            languages: vec!["cobol".to_owned()],
+            ..Default::default()
+        };
+        assert!(matches!(
+            generate_match(&pool(), &too_picky),
+            Err(RandomMatchError::NotEnoughBots(2, 0))
+        ));
+
+        let too_big = MatchConstraints {
+            team_size: 4,
+            ..Default::default()
+        };
+        assert!(matches!(
+            generate_match(&pool(), &too_big),
+            Err(RandomMatchError::NotEnoughBots(8, 6))
+        ));
+
+        let empty = MatchConstraints {
+            team_size: 0,
+            ..Default::default()
+        };
+        assert!(matches!(
+            generate_match(&pool(), &empty),
+            Err(RandomMatchError::InvalidTeamSize(0))
+        ));
+
+        let unknown = MatchConstraints {
+            include: vec!["bots/Missing/bot.cfg".to_owned()],
+            ..Default::default()
+        };
+        assert!(matches!(
+            generate_match(&pool(), &unknown),
+            Err(RandomMatchError::UnknownBot(path)) if path == "bots/Missing/bot.cfg"
+        ));
+    }
+}
@@ -0,0 +1,494 @@
+use crate::{
+    get_content_folder, impl_serialize_from_display,
+    match_history::{self, HistoryError, HistoryQuery, MatchRecord, Participant},
+    reporter::Reporter,
+    rlbot::parsing::match_settings_config_parser::GameMode,
+    settings::Team,
+    RATINGS,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::MutexGuard,
+};
+use thiserror::Error;
+
+pub const RATINGS_FILE: &str = "ratings.json";
+pub const INITIAL_RATING: f64 = 1500.;
+/// How much a single match can move a rating
+const K_FACTOR: f64 = 32.;
+
+#[derive(Debug, Error)]
+pub enum RatingError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+    #[error(transparent)]
+    History(#[from] HistoryError),
+    #[error("Mutex {0} was poisoned")]
+    Poisoned(&'static str),
+}
+
+impl_serialize_from_display!(RatingError);
+
+/// Identifies a bot the same way that `BotConfigBundle` does, so that renamed copies of a bot are kept apart
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BotIdentity {
+    pub name: String,
+    /// The path to the bot's config file, or `None` for humans and Psyonix bots
+    pub path: Option<String>,
+}
+
+impl From<&Participant> for BotIdentity {
+    fn from(participant: &Participant) -> Self {
+        Self {
+            name: participant.name.clone(),
+            path: participant.path.clone().filter(|path| !path.is_empty()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BotRating {
+    #[serde(flatten)]
+    pub bot: BotIdentity,
+    pub rating: f64,
+    pub matches: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    /// When the bot last played a rated match, in seconds since the Unix epoch
+    pub last_played: u64,
+}
+
+impl BotRating {
+    fn new(bot: BotIdentity) -> Self {
+        Self {
+            bot,
+            rating: INITIAL_RATING,
+            matches: 0,
+            wins: 0,
+            losses: 0,
+            draws: 0,
+            last_played: 0,
+        }
+    }
+}
+
+/// The ratings of bots in a single game mode and team size, since a 1v1 soccer rating says little about 3v3 hoops
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Ladder {
+    pub game_mode: GameMode,
+    /// How many players there were on the biggest team
+    pub team_size: usize,
+    pub ratings: Vec<BotRating>,
+}
+
+impl Ladder {
+    fn rating_mut(&mut self, bot: BotIdentity) -> &mut BotRating {
+        let index = match self.ratings.iter().position(|rating| rating.bot == bot) {
+            Some(index) => index,
+            None => {
+                self.ratings.push(BotRating::new(bot));
+                self.ratings.len() - 1
+            }
+        };
+
+        &mut self.ratings[index]
+    }
+
+    fn rating(&self, bot: &BotIdentity) -> f64 {
+        self.ratings
+            .iter()
+            .find(|rating| &rating.bot == bot)
+            .map_or(INITIAL_RATING, |rating| rating.rating)
+    }
+
+    /// Get the ratings from highest to lowest
+    pub fn leaderboard(&self) -> Vec<BotRating> {
+        let mut ratings = self.ratings.clone();
+        ratings.sort_by(|a, b| b.rating.total_cmp(&a.rating));
+        ratings
+    }
+
+    fn average_rating(&self, team: &[BotIdentity]) -> f64 {
+        team.iter().map(|bot| self.rating(bot)).sum::<f64>() / team.len() as f64
+    }
+
+    /// Update the ratings of everyone in the match, treating each team as a single player with their average rating
+    ///
+    /// A bot that played on both teams, like in a mirror match, is left alone since it both won and lost
+    fn apply(&mut self, record: &MatchRecord) {
+        let team = |team: Team| {
+            record
+                .participants
+                .iter()
+                .filter(|participant| participant.team == team)
+                .map(BotIdentity::from)
+                .collect::<Vec<_>>()
+        };
+        let blue = team(Team::Blue);
+        let orange = team(Team::Orange);
+
+        let blue_rating = self.average_rating(&blue);
+        let orange_rating = self.average_rating(&orange);
+        let blue_expected = 1. / (1. + 10_f64.powf((orange_rating - blue_rating) / 400.));
+        let blue_score = match record.winner {
+            Some(Team::Blue) => 1.,
+            Some(Team::Orange) => 0.,
+            None => 0.5,
+        };
+        let change = K_FACTOR * (blue_score - blue_expected);
+        let mirrored = blue.iter().filter(|bot| orange.contains(bot)).cloned().collect::<Vec<_>>();
+
+        for (bots, change, score) in [(blue, change, blue_score), (orange, -change, 1. - blue_score)] {
+            for bot in bots.into_iter().filter(|bot| !mirrored.contains(bot)) {
+                let rating = self.rating_mut(bot);
+                rating.rating += change;
+                rating.matches += 1;
+                rating.last_played = rating.last_played.max(record.ended_at);
+
+                if score > 0.5 {
+                    rating.wins += 1;
+                } else if score < 0.5 {
+                    rating.losses += 1;
+                } else {
+                    rating.draws += 1;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Ratings {
+    pub ladders: Vec<Ladder>,
+}
+
+impl Ratings {
+    fn ladder_mut(&mut self, game_mode: GameMode, team_size: usize) -> &mut Ladder {
+        let index = match self
+            .ladders
+            .iter()
+            .position(|ladder| ladder.game_mode == game_mode && ladder.team_size == team_size)
+        {
+            Some(index) => index,
+            None => {
+                self.ladders.push(Ladder {
+                    game_mode,
+                    team_size,
+                    ratings: Vec::new(),
+                });
+                self.ladders.len() - 1
+            }
+        };
+
+        &mut self.ladders[index]
+    }
+
+    pub fn ladder(&self, game_mode: GameMode, team_size: usize) -> Option<&Ladder> {
+        self.ladders
+            .iter()
+            .find(|ladder| ladder.game_mode == game_mode && ladder.team_size == team_size)
+    }
+
+    /// Rate a match, if it finished, there was someone on both teams and it wasn't from an A/B test or regression suite
+    fn apply(&mut self, record: &MatchRecord) {
+        if !record.completed || record.automated {
+            return;
+        }
+
+        let team_size = |team: Team| {
+            record
+                .participants
+                .iter()
+                .filter(|participant| participant.team == team)
+                .count()
+        };
+        let (blue_size, orange_size) = (team_size(Team::Blue), team_size(Team::Orange));
+        if blue_size == 0 || orange_size == 0 {
+            return;
+        }
+
+        self.ladder_mut(record.match_settings.game_mode, blue_size.max(orange_size))
+            .apply(record);
+    }
+}
+
+pub fn get_ratings_path() -> PathBuf {
+    get_content_folder().join(RATINGS_FILE)
+}
+
+fn read_ratings() -> Result<Ratings, RatingError> {
+    let path = get_ratings_path();
+    if !path.exists() {
+        return Ok(Ratings::default());
+    }
+
+    Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+}
+
+fn write_ratings(ratings: &Ratings) -> Result<(), RatingError> {
+    let path = get_ratings_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer(&mut writer, ratings)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Lock the ratings, loading them from disk if they haven't been yet
+fn lock_ratings() -> Result<MutexGuard<'static, Option<Ratings>>, RatingError> {
+    let mut ratings = RATINGS.lock().map_err(|_| RatingError::Poisoned("RATINGS"))?;
+
+    if ratings.is_none() {
+        *ratings = Some(read_ratings()?);
+    }
+
+    Ok(ratings)
+}
+
+/// Update the ratings with a match that just finished
+pub fn rate_match(record: &MatchRecord) -> Result<(), RatingError> {
+    let mut lock = lock_ratings()?;
+    let ratings = lock.get_or_insert_with(Ratings::default);
+    ratings.apply(record);
+
+    write_ratings(ratings)
+}
+
+/// Throw away the current ratings and rate every match in the history again, in the order they were played
+///
+/// # Arguments
+///
+/// * `reporter` - Where to report matches in the history that couldn't be read
+pub fn recompute_ratings(reporter: &dyn Reporter) -> Result<(), RatingError> {
+    let mut records = match_history::query_history(reporter, &HistoryQuery::default())?;
+    records.reverse();
+
+    let mut ratings = Ratings::default();
+    for record in &records {
+        ratings.apply(record);
+    }
+
+    write_ratings(&ratings)?;
+    *RATINGS.lock().map_err(|_| RatingError::Poisoned("RATINGS"))? = Some(ratings);
+
+    Ok(())
+}
+
+/// Get the ratings in a ladder from highest to lowest, which is empty if nobody has played in it yet
+pub fn get_leaderboard(game_mode: GameMode, team_size: usize) -> Result<Vec<BotRating>, RatingError> {
+    Ok(lock_ratings()?
+        .as_ref()
+        .and_then(|ratings| ratings.ladder(game_mode, team_size))
+        .map(Ladder::leaderboard)
+        .unwrap_or_default())
+}
+
+/// Get every ladder, each with its ratings from highest to lowest
+pub fn get_ladders() -> Result<Vec<Ladder>, RatingError> {
+    Ok(lock_ratings()?
+        .as_ref()
+        .map(|ratings| {
+            ratings
+                .ladders
+                .iter()
+                .map(|ladder| Ladder {
+                    ratings: ladder.leaderboard(),
+                    ..ladder.clone()
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Write a leaderboard to a CSV file
+///
+/// # Arguments
+///
+/// * `game_mode` - The game mode of the ladder
+/// * `team_size` - How many players there were on the biggest team
+/// * `destination` - Where to write the CSV file to
+pub fn export_csv(game_mode: GameMode, team_size: usize, destination: &Path) -> Result<(), RatingError> {
+    let mut writer = BufWriter::new(File::create(destination)?);
+    writeln!(writer, "rank,name,path,rating,matches,wins,losses,draws,last_played")?;
+
+    for (index, rating) in get_leaderboard(game_mode, team_size)?.iter().enumerate() {
+        writeln!(
+            writer,
+            "{},{},{},{:.1},{},{},{},{},{}",
+            index + 1,
+            csv_field(&rating.bot.name),
+            csv_field(rating.bot.path.as_deref().unwrap_or_default()),
+            rating.rating,
+            rating.matches,
+            rating.wins,
+            rating.losses,
+            rating.draws,
+            rating.last_played
+        )?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::MiniMatchConfig;
+
+    fn participant(name: &str, team: Team) -> Participant {
+        Participant {
+            name: name.to_owned(),
+            team,
+            runnable_type: "rlbot".to_owned(),
+            skill: 1.,
+            path: Some(format!("bots/{name}.cfg")),
+            stats: None,
+        }
+    }
+
+    fn record(participants: Vec<Participant>, winner: Option<Team>, completed: bool) -> MatchRecord {
+        MatchRecord {
+            id: "1".to_owned(),
+            started_at: 100,
+            ended_at: 400,
+            duration: 300.,
+            completed,
+            participants,
+            score: Vec::new(),
+            winner,
+            match_settings: MiniMatchConfig::default(),
+            automated: false,
+        }
+    }
+
+    fn bot(name: &str) -> BotIdentity {
+        BotIdentity {
+            name: name.to_owned(),
+            path: Some(format!("bots/{name}.cfg")),
+        }
+    }
+
+    #[test]
+    fn win_between_equal_ratings() {
+        let mut ratings = Ratings::default();
+        ratings.apply(&record(
+            vec![participant("Nexto", Team::Blue), participant("Necto", Team::Orange)],
+            Some(Team::Blue),
+            true,
+        ));
+
+        let ladder = ratings.ladder(GameMode::default(), 1).unwrap();
+        assert!((ladder.rating(&bot("Nexto")) - (INITIAL_RATING + K_FACTOR / 2.)).abs() < 1e-9);
+        assert!((ladder.rating(&bot("Necto")) - (INITIAL_RATING - K_FACTOR / 2.)).abs() < 1e-9);
+
+        let leaderboard = ladder.leaderboard();
+        assert_eq!(leaderboard[0].bot, bot("Nexto"));
+        assert_eq!((leaderboard[0].wins, leaderboard[1].losses), (1, 1));
+        assert_eq!(leaderboard[0].last_played, 400);
+    }
+
+    #[test]
+    fn draw_between_equal_ratings_changes_nothing() {
+        let mut ratings = Ratings::default();
+        ratings.apply(&record(
+            vec![participant("Nexto", Team::Blue), participant("Necto", Team::Orange)],
+            None,
+            true,
+        ));
+
+        let ladder = ratings.ladder(GameMode::default(), 1).unwrap();
+        assert!((ladder.rating(&bot("Nexto")) - INITIAL_RATING).abs() < 1e-9);
+        assert_eq!(ladder.leaderboard()[0].draws, 1);
+    }
+
+    #[test]
+    fn unfinished_and_one_sided_matches_are_not_rated() {
+        let mut ratings = Ratings::default();
+        ratings.apply(&record(
+            vec![participant("Nexto", Team::Blue), participant("Necto", Team::Orange)],
+            Some(Team::Blue),
+            false,
+        ));
+        ratings.apply(&record(vec![participant("Nexto", Team::Blue)], Some(Team::Blue), true));
+
+        assert!(ratings.ladders.is_empty());
+    }
+
+    #[test]
+    fn team_sizes_have_their_own_ladders() {
+        let mut ratings = Ratings::default();
+        ratings.apply(&record(
+            vec![participant("Nexto", Team::Blue), participant("Necto", Team::Orange)],
+            Some(Team::Blue),
+            true,
+        ));
+        ratings.apply(&record(
+            vec![
+                participant("Nexto", Team::Blue),
+                participant("Kamael", Team::Blue),
+                participant("Necto", Team::Orange),
+            ],
+            Some(Team::Orange),
+            true,
+        ));
+
+        assert_eq!(ratings.ladders.len(), 2);
+        assert_eq!(ratings.ladder(GameMode::default(), 2).unwrap().ratings.len(), 3);
+    }
+
+    #[test]
+    fn automated_matches_are_not_rated() {
+        let mut ratings = Ratings::default();
+        ratings.apply(&MatchRecord {
+            automated: true,
+            ..record(
+                vec![participant("Nexto", Team::Blue), participant("Necto", Team::Orange)],
+                Some(Team::Blue),
+                true,
+            )
+        });
+
+        assert!(ratings.ladders.is_empty());
+    }
+
+    #[test]
+    fn mirrored_bots_keep_their_rating() {
+        let mut ratings = Ratings::default();
+        ratings.apply(&record(
+            vec![
+                participant("Nexto", Team::Blue),
+                participant("Kamael", Team::Blue),
+                participant("Nexto", Team::Orange),
+                participant("Necto", Team::Orange),
+            ],
+            Some(Team::Blue),
+            true,
+        ));
+
+        let ladder = ratings.ladder(GameMode::default(), 2).unwrap();
+        assert!((ladder.rating(&bot("Nexto")) - INITIAL_RATING).abs() < 1e-9);
+        assert!(ladder.rating(&bot("Kamael")) > INITIAL_RATING);
+        assert!(ladder.rating(&bot("Necto")) < INITIAL_RATING);
+        assert_eq!(ladder.ratings.len(), 2);
+    }
+}
@@ -0,0 +1,522 @@
+use crate::{
+    ccprintln,
+    content::{ContentError, ContentFolder},
+    impl_serialize_from_display,
+    reporter::Reporter,
+    rlbot::packet_stream::latest_game_tick_packet,
+    settings::{GameTickPacket, MiniMatchConfig, TeamBotBundle},
+    OPEN_RECORDING, RECORDER,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, create_dir_all, File},
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tauri::async_runtime::block_on as tauri_block_on;
+use thiserror::Error;
+
+pub const RECORDINGS: ContentFolder = ContentFolder {
+    folder: "recordings",
+    extension: "ndjson",
+    kind: "recording",
+};
+pub const DEFAULT_RECORDING_RATE: f32 = 30.;
+/// RLBot doesn't produce packets any faster than this, so there's no point in recording them faster
+const MAX_RECORDING_RATE: f32 = 120.;
+/// How often the recording is written to disk, which is the most that can be lost if the GUI is closed while recording
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Error)]
+pub enum RecordingError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+    #[error(transparent)]
+    Content(#[from] ContentError),
+    #[error("Recordings can't be made at {0} packets per second")]
+    InvalidRate(f32),
+    #[error("A match is already being recorded")]
+    AlreadyRecording,
+    #[error("No match is being recorded")]
+    NotRecording,
+    #[error("The recording thread panicked")]
+    ThreadPanicked,
+    #[error("No recording has been opened")]
+    NoneOpen,
+    #[error("There's no frame {index}, the recording only has {count} frames")]
+    FrameIndex { index: usize, count: usize },
+    #[error("Line {0} of the recording is malformed: {1}")]
+    Malformed(usize, serde_json::Error),
+    #[error("The recording doesn't start with its metadata")]
+    NoMetadata,
+    #[error("Mutex {0} was poisoned")]
+    Poisoned(&'static str),
+}
+
+impl_serialize_from_display!(RecordingError);
+
+/// Information about the match that was recorded
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RecordingMetadata {
+    pub bots: Vec<TeamBotBundle>,
+    pub match_settings: MiniMatchConfig,
+    /// When the recording started, in seconds since the Unix epoch
+    pub started_at: u64,
+}
+
+/// A single line of a recording file
+///
+/// The first line is always the metadata, followed by a line for each packet, and then a line for when the recording ended.
+/// The last line is missing if the GUI was closed before the recording was stopped, along with up to a second of packets.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum RecordingLine {
+    Metadata(RecordingMetadata),
+    Packet(GameTickPacket),
+    End { ended_at: u64 },
+}
+
+/// What's shown in the list of recordings
+#[derive(Serialize, Clone, Debug)]
+pub struct RecordingSummary {
+    pub name: String,
+    pub metadata: RecordingMetadata,
+    /// When the recording ended, in seconds since the Unix epoch
+    pub ended_at: Option<u64>,
+    pub frame_count: usize,
+    /// How much game time the recording covers, in seconds
+    pub duration: f32,
+}
+
+/// A recording that's been read into memory, so that it can be scrubbed through
+pub struct Recording {
+    pub summary: RecordingSummary,
+    pub frames: Vec<GameTickPacket>,
+}
+
+pub fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .unwrap_or_default()
+}
+
+fn write_line(writer: &mut impl Write, line: &RecordingLine) -> Result<(), RecordingError> {
+    serde_json::to_writer(&mut *writer, line)?;
+    writer.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Writes game tick packets to a recording file from a background thread until it's stopped
+pub struct Recorder {
+    name: String,
+    running: Arc<AtomicBool>,
+    thread: JoinHandle<Result<(), RecordingError>>,
+}
+
+impl Recorder {
+    fn start(
+        reporter: Box<dyn Reporter>,
+        name: String,
+        metadata: RecordingMetadata,
+        rate: f32,
+    ) -> Result<Self, RecordingError> {
+        let name = name.trim().to_owned();
+        let path = RECORDINGS.new_item_path(&name)?;
+
+        create_dir_all(RECORDINGS.path())?;
+        let mut writer = BufWriter::new(File::create(path)?);
+        write_line(&mut writer, &RecordingLine::Metadata(metadata))?;
+
+        let running = Arc::new(AtomicBool::new(true));
+        let thread_running = running.clone();
+        let interval = Duration::from_secs_f32(1. / rate);
+
+        let thread = thread::spawn(move || {
+            // The same packet shouldn't be recorded twice, like when the game is paused
+            let mut last_recorded = None;
+            let mut last_flush = Instant::now();
+
+            while thread_running.load(Ordering::Relaxed) {
+                let start_time = Instant::now();
+
+                match tauri_block_on(latest_game_tick_packet(&*reporter)) {
+                    Ok(Some(packet)) => {
+                        let key = (packet.game_info.frame_num, packet.game_info.seconds_elapsed.to_bits());
+
+                        if last_recorded != Some(key) {
+                            last_recorded = Some(key);
+                            write_line(&mut writer, &RecordingLine::Packet(packet))?;
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => ccprintln!(&*reporter, "Couldn't get game tick packet to record: {e}"),
+                }
+
+                if last_flush.elapsed() >= FLUSH_INTERVAL {
+                    writer.flush()?;
+                    last_flush = Instant::now();
+                }
+
+                thread::sleep(interval.saturating_sub(start_time.elapsed()));
+            }
+
+            write_line(&mut writer, &RecordingLine::End { ended_at: unix_time() })?;
+            writer.flush()?;
+
+            Ok(())
+        });
+
+        Ok(Self { name, running, thread })
+    }
+
+    /// Stop recording and wait for the rest of the recording to be written to disk
+    fn stop(self) -> Result<String, RecordingError> {
+        self.running.store(false, Ordering::Relaxed);
+        self.thread.join().map_err(|_| RecordingError::ThreadPanicked)??;
+
+        Ok(self.name)
+    }
+}
+
+/// Start recording the current match
+///
+/// # Arguments
+///
+/// * `reporter` - Where to send console output and events, like the GUI
+/// * `name` - The name of the recording, which can't already be in use
+/// * `metadata` - Information about the match that's being recorded
+/// * `rate` - The most packets to record per second
+pub fn start_recording(
+    reporter: Box<dyn Reporter>,
+    name: String,
+    metadata: RecordingMetadata,
+    rate: f32,
+) -> Result<(), RecordingError> {
+    if !rate.is_finite() || rate <= 0. {
+        return Err(RecordingError::InvalidRate(rate));
+    }
+
+    let mut recorder = RECORDER.lock().map_err(|_| RecordingError::Poisoned("RECORDER"))?;
+    if recorder.as_ref().map_or(false, |recorder| !recorder.thread.is_finished()) {
+        return Err(RecordingError::AlreadyRecording);
+    }
+
+    // a recorder that stopped by itself, like because of a write error, gets replaced
+    if let Some(old_recorder) = recorder.take() {
+        if let Err(e) = old_recorder.stop() {
+            ccprintln!(&*reporter, "The previous recording stopped early: {e}");
+        }
+    }
+
+    *recorder = Some(Recorder::start(reporter, name, metadata, rate.min(MAX_RECORDING_RATE))?);
+
+    Ok(())
+}
+
+/// Stop recording the current match
+///
+/// Returns the name of the recording that was stopped
+pub fn stop_recording() -> Result<String, RecordingError> {
+    let recorder = RECORDER
+        .lock()
+        .map_err(|_| RecordingError::Poisoned("RECORDER"))?
+        .take()
+        .ok_or(RecordingError::NotRecording)?;
+
+    recorder.stop()
+}
+
+/// Get the name of the recording that's currently being made, if there is one
+pub fn current_recording() -> Result<Option<String>, RecordingError> {
+    Ok(RECORDER
+        .lock()
+        .map_err(|_| RecordingError::Poisoned("RECORDER"))?
+        .as_ref()
+        .filter(|recorder| !recorder.thread.is_finished())
+        .map(|recorder| recorder.name.clone()))
+}
+
+/// Read a recording file
+///
+/// # Arguments
+///
+/// * `path` - The path to the recording
+/// * `keep_frames` - Whether to keep the frames in memory, or only count them
+fn read_recording(path: &Path, keep_frames: bool) -> Result<Recording, RecordingError> {
+    let name = path
+        .file_stem()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut lines = BufReader::new(File::open(path)?).lines().enumerate();
+
+    let metadata = match lines.next() {
+        Some((_, line)) => match serde_json::from_str(&line?) {
+            Ok(RecordingLine::Metadata(metadata)) => metadata,
+            Ok(_) => return Err(RecordingError::NoMetadata),
+            Err(e) => return Err(RecordingError::Malformed(1, e)),
+        },
+        None => return Err(RecordingError::NoMetadata),
+    };
+
+    let mut frames = Vec::new();
+    let mut frame_count = 0;
+    let mut first_time = None;
+    let mut last_time = None;
+    let mut ended_at = None;
+
+    let mut lines = lines.peekable();
+    while let Some((index, line)) = lines.next() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let line = match serde_json::from_str(&line) {
+            Ok(line) => line,
+            // The GUI was closed partway through writing the last packet, so the recording ends before it
+            Err(_) if lines.peek().is_none() => break,
+            Err(e) => return Err(RecordingError::Malformed(index + 1, e)),
+        };
+
+        match line {
+            RecordingLine::Packet(packet) => {
+                frame_count += 1;
+                first_time.get_or_insert(packet.game_info.seconds_elapsed);
+                last_time = Some(packet.game_info.seconds_elapsed);
+
+                if keep_frames {
+                    frames.push(packet);
+                }
+            }
+            RecordingLine::End { ended_at: time } => ended_at = Some(time),
+            RecordingLine::Metadata(_) => {}
+        }
+    }
+
+    let duration = match (first_time, last_time) {
+        (Some(first), Some(last)) => (last - first).max(0.),
+        _ => 0.,
+    };
+
+    Ok(Recording {
+        summary: RecordingSummary {
+            name,
+            metadata,
+            ended_at,
+            frame_count,
+            duration,
+        },
+        frames,
+    })
+}
+
+/// Get a summary of every recording in the recordings folder, newest first
+///
+/// # Arguments
+///
+/// * `reporter` - Where to report the recordings that couldn't be read
+pub fn list_recordings(reporter: &dyn Reporter) -> Result<Vec<RecordingSummary>, RecordingError> {
+    let mut recordings = RECORDINGS.list(reporter, |path| {
+        read_recording(path, false).map(|recording| recording.summary)
+    })?;
+    recordings.sort_by(|a, b| b.metadata.started_at.cmp(&a.metadata.started_at));
+
+    Ok(recordings)
+}
+
+/// Make sure that a recording isn't being written to before it's changed
+fn ensure_not_recording(name: &str) -> Result<(), RecordingError> {
+    if current_recording()?.as_deref() == Some(name.trim()) {
+        return Err(RecordingError::AlreadyRecording);
+    }
+
+    Ok(())
+}
+
+pub fn rename_recording(old_name: &str, new_name: &str) -> Result<(), RecordingError> {
+    ensure_not_recording(old_name)?;
+
+    let old_path = RECORDINGS.existing_item_path(old_name)?;
+    let new_path = RECORDINGS.new_item_path(new_name)?;
+
+    fs::rename(old_path, new_path)?;
+
+    if let Some(recording) = OPEN_RECORDING
+        .lock()
+        .map_err(|_| RecordingError::Poisoned("OPEN_RECORDING"))?
+        .as_mut()
+        .filter(|recording| recording.summary.name == old_name.trim())
+    {
+        recording.summary.name = new_name.trim().to_owned();
+    }
+
+    Ok(())
+}
+
+pub fn delete_recording(name: &str) -> Result<(), RecordingError> {
+    ensure_not_recording(name)?;
+
+    fs::remove_file(RECORDINGS.existing_item_path(name)?)?;
+
+    let mut open_recording = OPEN_RECORDING
+        .lock()
+        .map_err(|_| RecordingError::Poisoned("OPEN_RECORDING"))?;
+    if open_recording
+        .as_ref()
+        .map_or(false, |recording| recording.summary.name == name.trim())
+    {
+        *open_recording = None;
+    }
+
+    Ok(())
+}
+
+/// Copy a recording to somewhere outside of the recordings folder
+///
+/// # Arguments
+///
+/// * `name` - The name of the recording
+/// * `destination` - The file to copy the recording to
+pub fn export_recording(name: &str, destination: &Path) -> Result<(), RecordingError> {
+    fs::copy(RECORDINGS.existing_item_path(name)?, destination)?;
+    Ok(())
+}
+
+/// Read a recording into memory so that its frames can be scrubbed through, replacing any other open recording
+pub fn open_recording(name: &str) -> Result<RecordingSummary, RecordingError> {
+    let recording = read_recording(&RECORDINGS.existing_item_path(name)?, true)?;
+    let summary = recording.summary.clone();
+
+    *OPEN_RECORDING
+        .lock()
+        .map_err(|_| RecordingError::Poisoned("OPEN_RECORDING"))? = Some(recording);
+
+    Ok(summary)
+}
+
+pub fn close_recording() -> Result<(), RecordingError> {
+    OPEN_RECORDING
+        .lock()
+        .map_err(|_| RecordingError::Poisoned("OPEN_RECORDING"))?
+        .take();
+
+    Ok(())
+}
+
+/// Get a frame from the open recording
+pub fn get_frame(index: usize) -> Result<GameTickPacket, RecordingError> {
+    let open_recording = OPEN_RECORDING
+        .lock()
+        .map_err(|_| RecordingError::Poisoned("OPEN_RECORDING"))?;
+    let recording = open_recording.as_ref().ok_or(RecordingError::NoneOpen)?;
+
+    recording.frames.get(index).cloned().ok_or(RecordingError::FrameIndex {
+        index,
+        count: recording.frames.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::{Ball, GameInfo};
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn packet(frame_num: i32) -> GameTickPacket {
+        GameTickPacket {
+            game_ball: Ball::default(),
+            game_cars: Vec::new(),
+            game_boosts: Vec::new(),
+            teams: Vec::new(),
+            game_info: GameInfo {
+                seconds_elapsed: frame_num as f32 / 120.,
+                frame_num,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Write a recording with the metadata and a packet for each frame, followed by `rest` as is
+    fn write_recording(folder: &TempDir, frames: &[i32], rest: &str) -> PathBuf {
+        let path = folder.path().join("test.ndjson");
+        let mut writer = BufWriter::new(File::create(&path).unwrap());
+
+        let metadata = RecordingMetadata {
+            bots: Vec::new(),
+            match_settings: MiniMatchConfig::default(),
+            started_at: 100,
+        };
+        write_line(&mut writer, &RecordingLine::Metadata(metadata)).unwrap();
+
+        for frame_num in frames {
+            write_line(&mut writer, &RecordingLine::Packet(packet(*frame_num))).unwrap();
+        }
+
+        writer.write_all(rest.as_bytes()).unwrap();
+        writer.flush().unwrap();
+
+        path
+    }
+
+    #[test]
+    fn finished_recordings_are_read() {
+        let folder = TempDir::new().unwrap();
+        let path = write_recording(&folder, &[0, 60, 120], "{\"type\":\"end\",\"ended_at\":400}\n");
+
+        let recording = read_recording(&path, true).unwrap();
+        assert_eq!(recording.summary.name, "test");
+        assert_eq!(recording.summary.metadata.started_at, 100);
+        assert_eq!(recording.summary.ended_at, Some(400));
+        assert_eq!(recording.summary.frame_count, 3);
+        assert!((recording.summary.duration - 1.).abs() < f32::EPSILON);
+        assert_eq!(recording.frames.len(), 3);
+
+        assert!(read_recording(&path, false).unwrap().frames.is_empty());
+    }
+
+    #[test]
+    fn truncated_last_line_is_ignored() {
+        let folder = TempDir::new().unwrap();
+        let path = write_recording(&folder, &[0, 60], "{\"type\":\"packet\",\"game_ball\":{\"phys");
+
+        let recording = read_recording(&path, true).unwrap();
+        assert_eq!(recording.summary.ended_at, None);
+        assert_eq!(recording.summary.frame_count, 2);
+        assert_eq!(recording.frames.len(), 2);
+    }
+
+    #[test]
+    fn malformed_lines_before_the_end_are_errors() {
+        let folder = TempDir::new().unwrap();
+        let path = write_recording(
+            &folder,
+            &[0],
+            "{\"type\":\"packet\",\"game_ball\"\n{\"type\":\"end\",\"ended_at\":400}\n",
+        );
+
+        assert!(matches!(read_recording(&path, false), Err(RecordingError::Malformed(3, _))));
+    }
+
+    #[test]
+    fn recordings_start_with_their_metadata() {
+        let folder = TempDir::new().unwrap();
+        let path = folder.path().join("test.ndjson");
+
+        fs::write(&path, "").unwrap();
+        assert!(matches!(read_recording(&path, false), Err(RecordingError::NoMetadata)));
+
+        fs::write(&path, "{\"type\":\"end\",\"ended_at\":400}\n").unwrap();
+        assert!(matches!(read_recording(&path, false), Err(RecordingError::NoMetadata)));
+    }
+}
@@ -0,0 +1,1019 @@
+use crate::{
+    automation::{Automation, AutomationError, AutomationSlot},
+    ccprintln,
+    commands::{kill_bots_helper, start_match_helper},
+    content::{self, read_json, write_json, ContentError},
+    get_content_folder, impl_serialize_from_display,
+    match_handler::supervisor,
+    match_history::{self, MatchRecord},
+    recordings::unix_time,
+    reporter::Reporter,
+    rlbot::parsing::bot_config_bundle::BotConfigBundle,
+    settings::{MiniMatchConfig, Team},
+    start_handler_output_capture, take_handler_output_capture,
+    tournament::Entrant,
+    REGRESSION_RUNNER,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    ffi::OsStr,
+    fmt::Write as _,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+use tauri::async_runtime::block_on as tauri_block_on;
+use thiserror::Error;
+
+pub const REGRESSION_FOLDER: &str = "regression_suites";
+/// What a regression suite is called in error messages
+const SUITE_KIND: &str = "regression suite";
+const SUITE_FILE: &str = "suite.json";
+const BASELINE_FILE: &str = "baseline.json";
+const REPORTS_FOLDER: &str = "reports";
+pub const REGRESSION_UPDATED_SIGNAL: &str = "regression-updated";
+
+/// How long to wait after a game ends before starting the next one, so that the bots have time to shut down
+const BETWEEN_GAMES: Duration = Duration::from_secs(5);
+/// The most lines of crash output that are kept from a single game
+const MAX_CRASH_LINES: usize = 100;
+/// What the runtimes that bots are written in print when a bot crashes
+const CRASH_MARKERS: [&str; 5] = [
+    "Traceback (most recent call last)",
+    "panicked at",
+    "Unhandled exception",
+    "Exception in thread \"",
+    "Segmentation fault",
+];
+
+/// How much the win rate can drop compared to the baseline before it's flagged as a regression
+const WIN_RATE_TOLERANCE: f64 = 0.1;
+/// How much the average goal difference per game can drop compared to the baseline before it's flagged as a regression
+const GOAL_DIFFERENCE_TOLERANCE: f64 = 0.5;
+
+#[derive(Debug, Error)]
+pub enum RegressionError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Content(#[from] ContentError),
+    #[error("The regression suite \"{0}\" doesn't have a report called \"{1}\"")]
+    ReportNotFound(String, String),
+    #[error("A regression suite needs at least one opponent and one game per opponent")]
+    Empty,
+    #[error("The regression suite \"{0}\" is already running")]
+    AlreadyRunning(String),
+    #[error("No regression suite is running")]
+    NotRunning,
+    #[error(transparent)]
+    Automation(#[from] AutomationError),
+    #[error("Mutex {0} was poisoned")]
+    Poisoned(&'static str),
+}
+
+impl_serialize_from_display!(RegressionError);
+
+/// Our bot against a fixed list of opponents, a set number of games each
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RegressionSuite {
+    pub name: String,
+    /// The bot that's being tested
+    pub bot: Entrant,
+    pub opponents: Vec<Entrant>,
+    /// The bot switches sides every game, starting on blue
+    pub games_per_opponent: u32,
+    /// The map, mutators and other settings that every game is started with
+    pub match_settings: MiniMatchConfig,
+    /// When the suite was created, in seconds since the Unix epoch
+    pub created_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Win,
+    Loss,
+    Draw,
+    /// The game couldn't be started or didn't reach the end
+    Unfinished,
+}
+
+/// How a single game of a regression suite went, from the point of view of the bot being tested
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GameResult {
+    /// The index of the opponent in the suite
+    pub opponent: usize,
+    /// Starts at 0 for each opponent
+    pub game: u32,
+    pub bot_team: Team,
+    pub outcome: Outcome,
+    pub bot_goals: usize,
+    pub opponent_goals: usize,
+    /// The id of the game in the match history
+    pub record_id: Option<String>,
+    /// Lines from the console that look like they came from a crash
+    pub crash_output: Vec<String>,
+    /// Why the game couldn't be started or tracked
+    pub error: Option<String>,
+}
+
+impl GameResult {
+    fn goal_difference(&self) -> i64 {
+        self.bot_goals as i64 - self.opponent_goals as i64
+    }
+
+    fn crashed(&self) -> bool {
+        self.outcome == Outcome::Unfinished || !self.crash_output.is_empty()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Summary {
+    pub played: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    pub unfinished: u32,
+    /// Games that didn't finish or printed something that looked like a crash
+    pub crashes: u32,
+    /// Wins plus half of the draws, out of the games that finished
+    pub win_rate: f64,
+    /// Goals scored minus goals conceded, in the games that finished
+    pub goal_difference: i64,
+}
+
+impl Summary {
+    fn from_games<'a>(games: impl Iterator<Item = &'a GameResult>) -> Self {
+        let mut summary = Self::default();
+
+        for game in games {
+            summary.played += 1;
+            match game.outcome {
+                Outcome::Win => summary.wins += 1,
+                Outcome::Loss => summary.losses += 1,
+                Outcome::Draw => summary.draws += 1,
+                Outcome::Unfinished => summary.unfinished += 1,
+            }
+
+            if game.crashed() {
+                summary.crashes += 1;
+            }
+
+            if game.outcome != Outcome::Unfinished {
+                summary.goal_difference += game.goal_difference();
+            }
+        }
+
+        let finished = summary.played - summary.unfinished;
+        if finished > 0 {
+            summary.win_rate = (summary.wins as f64 + summary.draws as f64 / 2.) / finished as f64;
+        }
+
+        summary
+    }
+
+    fn finished(&self) -> u32 {
+        self.played - self.unfinished
+    }
+
+    fn goal_difference_per_game(&self) -> f64 {
+        match self.finished() {
+            0 => 0.,
+            finished => self.goal_difference as f64 / finished as f64,
+        }
+    }
+
+    fn crash_rate(&self) -> f64 {
+        match self.played {
+            0 => 0.,
+            played => self.crashes as f64 / played as f64,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OpponentSummary {
+    pub name: String,
+    pub path: Option<String>,
+    #[serde(flatten)]
+    pub summary: Summary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    WinRate,
+    GoalDifferencePerGame,
+    CrashRate,
+}
+
+/// Something that got worse compared to the baseline
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RegressionFlag {
+    /// The opponent that the regression was against, or `None` if it's across every opponent
+    pub opponent: Option<String>,
+    pub metric: Metric,
+    pub baseline: f64,
+    pub current: f64,
+}
+
+impl RegressionFlag {
+    /// Compare two summaries, flagging anything that got noticeably worse
+    fn compare(opponent: Option<&str>, baseline: &Summary, current: &Summary) -> Vec<Self> {
+        let flag = |metric, baseline, current| Self {
+            opponent: opponent.map(str::to_owned),
+            metric,
+            baseline,
+            current,
+        };
+
+        let mut flags = Vec::new();
+
+        if baseline.finished() > 0 && current.finished() > 0 {
+            if baseline.win_rate - current.win_rate > WIN_RATE_TOLERANCE {
+                flags.push(flag(Metric::WinRate, baseline.win_rate, current.win_rate));
+            }
+
+            let (baseline_gd, current_gd) = (baseline.goal_difference_per_game(), current.goal_difference_per_game());
+            if baseline_gd - current_gd > GOAL_DIFFERENCE_TOLERANCE {
+                flags.push(flag(Metric::GoalDifferencePerGame, baseline_gd, current_gd));
+            }
+        }
+
+        if current.crash_rate() > baseline.crash_rate() {
+            flags.push(flag(Metric::CrashRate, baseline.crash_rate(), current.crash_rate()));
+        }
+
+        flags
+    }
+}
+
+/// The results of running a regression suite once
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RegressionReport {
+    /// The time that the run started, which is also the name of the report
+    pub id: String,
+    pub suite: String,
+    pub bot: Entrant,
+    pub started_at: u64,
+    pub ended_at: Option<u64>,
+    /// If the run was stopped before every game was played
+    pub stopped: bool,
+    pub games: Vec<GameResult>,
+    pub overall: Summary,
+    pub opponents: Vec<OpponentSummary>,
+    /// The id of the report that this one was compared against
+    pub baseline: Option<String>,
+    pub regressions: Vec<RegressionFlag>,
+}
+
+impl RegressionReport {
+    fn new(suite: &RegressionSuite) -> Self {
+        let started_at = unix_time();
+
+        Self {
+            id: started_at.to_string(),
+            suite: suite.name.clone(),
+            bot: suite.bot.clone(),
+            started_at,
+            ended_at: None,
+            stopped: false,
+            games: Vec::new(),
+            overall: Summary::default(),
+            opponents: Vec::new(),
+            baseline: None,
+            regressions: Vec::new(),
+        }
+    }
+
+    /// Recalculate the summaries from the games, and compare them to the baseline if there is one
+    fn summarize(&mut self, suite: &RegressionSuite, baseline: Option<&Self>) {
+        self.overall = Summary::from_games(self.games.iter());
+        self.opponents = suite
+            .opponents
+            .iter()
+            .enumerate()
+            .map(|(index, opponent)| OpponentSummary {
+                name: opponent.name.clone(),
+                path: opponent.path.clone(),
+                summary: Summary::from_games(self.games.iter().filter(|game| game.opponent == index)),
+            })
+            .collect();
+
+        let Some(baseline) = baseline else {
+            return;
+        };
+
+        self.baseline = Some(baseline.id.clone());
+        self.regressions = RegressionFlag::compare(None, &baseline.overall, &self.overall);
+
+        for current in self.opponents.iter().filter(|opponent| opponent.summary.played > 0) {
+            let previous = baseline
+                .opponents
+                .iter()
+                .find(|previous| previous.name == current.name && previous.path == current.path);
+
+            if let Some(previous) = previous {
+                self.regressions.extend(RegressionFlag::compare(
+                    Some(&current.name),
+                    &previous.summary,
+                    &current.summary,
+                ));
+            }
+        }
+    }
+
+    /// Render the report as Markdown, for sharing or reading outside of the GUI
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+
+        let _ = writeln!(markdown, "# Regression report for {}\n", self.suite);
+        let _ = writeln!(markdown, "- Bot: {}", self.bot.name);
+        let _ = writeln!(markdown, "- Started at: {}", self.started_at);
+        if let Some(ended_at) = self.ended_at {
+            let _ = writeln!(markdown, "- Ended at: {ended_at}");
+        }
+        if self.stopped {
+            let _ = writeln!(markdown, "- Stopped before every game was played");
+        }
+        if let Some(baseline) = &self.baseline {
+            let _ = writeln!(markdown, "- Baseline: {baseline}");
+        }
+
+        let _ = writeln!(markdown, "\n## Results\n");
+        let _ = writeln!(
+            markdown,
+            "| Opponent | Played | Wins | Losses | Draws | Unfinished | Crashes | Win rate | Goal difference |"
+        );
+        let _ = writeln!(markdown, "| --- | --- | --- | --- | --- | --- | --- | --- | --- |");
+        for (name, summary) in self
+            .opponents
+            .iter()
+            .map(|opponent| (opponent.name.as_str(), &opponent.summary))
+            .chain([("**Overall**", &self.overall)])
+        {
+            let _ = writeln!(
+                markdown,
+                "| {name} | {} | {} | {} | {} | {} | {} | {:.1}% | {:+} |",
+                summary.played,
+                summary.wins,
+                summary.losses,
+                summary.draws,
+                summary.unfinished,
+                summary.crashes,
+                summary.win_rate * 100.,
+                summary.goal_difference
+            );
+        }
+
+        if self.baseline.is_some() {
+            let _ = writeln!(markdown, "\n## Regressions\n");
+            if self.regressions.is_empty() {
+                let _ = writeln!(markdown, "None found.");
+            }
+
+            for flag in &self.regressions {
+                let _ = writeln!(
+                    markdown,
+                    "- {}: {:?} went from {:.3} to {:.3}",
+                    flag.opponent.as_deref().unwrap_or("Overall"),
+                    flag.metric,
+                    flag.baseline,
+                    flag.current
+                );
+            }
+        }
+
+        let crashes = self.games.iter().filter(|game| game.crashed()).collect::<Vec<_>>();
+        if !crashes.is_empty() {
+            let _ = writeln!(markdown, "\n## Crashes");
+        }
+
+        for game in crashes {
+            let opponent = self
+                .opponents
+                .get(game.opponent)
+                .map_or("Unknown", |opponent| opponent.name.as_str());
+            let _ = writeln!(markdown, "\n### Game {} against {opponent}\n", game.game + 1);
+
+            if let Some(error) = &game.error {
+                let _ = writeln!(markdown, "{error}\n");
+            }
+
+            if !game.crash_output.is_empty() {
+                let _ = writeln!(markdown, "```\n{}\n```", game.crash_output.join("\n"));
+            }
+        }
+
+        markdown
+    }
+}
+
+pub fn get_regression_folder() -> PathBuf {
+    get_content_folder().join(REGRESSION_FOLDER)
+}
+
+/// Get the folder that holds a suite, its baseline and its reports
+///
+/// # Arguments
+///
+/// * `name` - The name of the regression suite
+fn suite_folder(name: &str) -> Result<PathBuf, RegressionError> {
+    let name = content::valid_name(name).ok_or_else(|| ContentError::InvalidName(SUITE_KIND, name.to_owned()))?;
+
+    Ok(get_regression_folder().join(name))
+}
+
+fn report_path(suite: &str, id: &str, extension: &str) -> Result<PathBuf, RegressionError> {
+    Ok(
+        content::content_item_path(&suite_folder(suite)?.join(REPORTS_FOLDER), id, extension)
+            .ok_or_else(|| ContentError::InvalidName("report", id.to_owned()))?,
+    )
+}
+
+fn read_suite(name: &str) -> Result<RegressionSuite, RegressionError> {
+    let path = suite_folder(name)?.join(SUITE_FILE);
+    if !path.is_file() {
+        return Err(ContentError::NotFound(SUITE_KIND, name.to_owned()).into());
+    }
+
+    Ok(read_json(&path)?)
+}
+
+fn read_baseline(name: &str) -> Result<Option<RegressionReport>, RegressionError> {
+    let path = suite_folder(name)?.join(BASELINE_FILE);
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    Ok(Some(read_json(&path)?))
+}
+
+/// Save a report as both JSON and Markdown
+fn write_report(report: &RegressionReport) -> Result<(), RegressionError> {
+    write_json(&report_path(&report.suite, &report.id, "json")?, report)?;
+    fs::write(report_path(&report.suite, &report.id, "md")?, report.to_markdown())?;
+
+    Ok(())
+}
+
+/// Create a new regression suite
+///
+/// # Arguments
+///
+/// * `name` - The name of the suite, which can't already be in use
+/// * `bot` - The bot that's being tested
+/// * `opponents` - The bots that it plays against
+/// * `games_per_opponent` - How many games to play against each opponent
+/// * `match_settings` - The map, mutators and other settings that every game is started with
+pub fn create_suite(
+    name: &str,
+    bot: &BotConfigBundle,
+    opponents: &[BotConfigBundle],
+    games_per_opponent: u32,
+    match_settings: MiniMatchConfig,
+) -> Result<RegressionSuite, RegressionError> {
+    let folder = suite_folder(name)?;
+    if folder.exists() {
+        return Err(ContentError::AlreadyExists(SUITE_KIND, name.to_owned()).into());
+    }
+
+    if opponents.is_empty() || games_per_opponent == 0 {
+        return Err(RegressionError::Empty);
+    }
+
+    let suite = RegressionSuite {
+        name: name.trim().to_owned(),
+        bot: Entrant::from_bundle(bot),
+        opponents: opponents.iter().map(Entrant::from_bundle).collect(),
+        games_per_opponent,
+        match_settings,
+        created_at: unix_time(),
+    };
+    write_json(&folder.join(SUITE_FILE), &suite)?;
+
+    Ok(suite)
+}
+
+pub fn get_suite(name: &str) -> Result<RegressionSuite, RegressionError> {
+    read_suite(name)
+}
+
+/// Get all of the regression suites, sorted by name
+pub fn list_suites() -> Result<Vec<RegressionSuite>, RegressionError> {
+    let folder = get_regression_folder();
+    if !folder.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut suites = Vec::new();
+
+    for entry in fs::read_dir(folder)? {
+        let path = entry?.path().join(SUITE_FILE);
+        if !path.is_file() {
+            continue;
+        }
+
+        match read_json::<RegressionSuite>(&path) {
+            Ok(suite) => suites.push(suite),
+            Err(e) => println!("Skipping regression suite {}: {e}", path.display()),
+        }
+    }
+
+    suites.sort_by_key(|suite| suite.name.to_lowercase());
+
+    Ok(suites)
+}
+
+/// Delete a suite along with its baseline and all of its reports
+pub fn delete_suite(name: &str) -> Result<(), RegressionError> {
+    if current_suite()?.as_deref() == Some(name.trim()) {
+        return Err(RegressionError::AlreadyRunning(name.to_owned()));
+    }
+
+    let folder = suite_folder(name)?;
+    if !folder.join(SUITE_FILE).is_file() {
+        return Err(ContentError::NotFound(SUITE_KIND, name.to_owned()).into());
+    }
+
+    fs::remove_dir_all(folder)?;
+
+    Ok(())
+}
+
+/// Get the ids of every report of a suite, newest first
+pub fn list_reports(name: &str) -> Result<Vec<String>, RegressionError> {
+    let folder = suite_folder(name)?.join(REPORTS_FOLDER);
+    if !folder.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut ids = Vec::new();
+
+    for entry in fs::read_dir(folder)? {
+        let path = entry?.path();
+        if path.extension() != Some(OsStr::new("json")) {
+            continue;
+        }
+
+        if let Some(id) = path.file_stem().and_then(OsStr::to_str) {
+            ids.push(id.to_owned());
+        }
+    }
+
+    ids.sort_by(|a, b| b.cmp(a));
+
+    Ok(ids)
+}
+
+pub fn get_report(name: &str, id: &str) -> Result<RegressionReport, RegressionError> {
+    let path = report_path(name, id, "json")?;
+    if !path.is_file() {
+        return Err(RegressionError::ReportNotFound(name.to_owned(), id.to_owned()));
+    }
+
+    Ok(read_json(&path)?)
+}
+
+/// Get the report that new runs of a suite are compared against, if one has been set
+pub fn get_baseline(name: &str) -> Result<Option<RegressionReport>, RegressionError> {
+    read_baseline(name)
+}
+
+/// Make a report the one that new runs of its suite are compared against
+pub fn set_baseline(name: &str, id: &str) -> Result<(), RegressionError> {
+    let report = get_report(name, id)?;
+    Ok(write_json(&suite_folder(name)?.join(BASELINE_FILE), &report)?)
+}
+
+pub fn clear_baseline(name: &str) -> Result<(), RegressionError> {
+    let path = suite_folder(name)?.join(BASELINE_FILE);
+    if path.is_file() {
+        fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+/// Copy a report to somewhere else, as Markdown if the destination ends in `.md` and as JSON otherwise
+pub fn export_report(name: &str, id: &str, destination: &Path) -> Result<(), RegressionError> {
+    let report = get_report(name, id)?;
+
+    if destination.extension() == Some(OsStr::new("md")) {
+        fs::write(destination, report.to_markdown())?;
+        Ok(())
+    } else {
+        Ok(write_json(destination, &report)?)
+    }
+}
+
+/// Pick out the lines of the match handler's output that are from a crash
+///
+/// A crash is a line with one of the `CRASH_MARKERS`, the indented lines after it,
+/// and the first line that isn't indented, which is the error at the end of a Python traceback
+fn crash_lines(output: Vec<String>) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut in_crash = false;
+
+    for line in output {
+        let is_marked = CRASH_MARKERS.iter().any(|marker| line.contains(marker));
+        let is_continuation = line.starts_with(char::is_whitespace);
+
+        if is_marked || in_crash {
+            in_crash = is_marked || is_continuation;
+            lines.push(line);
+        }
+    }
+
+    if lines.len() > MAX_CRASH_LINES {
+        lines.drain(..lines.len() - MAX_CRASH_LINES);
+    }
+
+    lines
+}
+
+fn goals(record: &MatchRecord, team: &Team) -> usize {
+    record
+        .score
+        .iter()
+        .find(|score| &score.team_index == team)
+        .map_or(0, |score| score.score)
+}
+
+const fn opposing_team(team: &Team) -> Team {
+    match team {
+        Team::Blue => Team::Orange,
+        Team::Orange => Team::Blue,
+    }
+}
+
+/// Play a single game of a suite, restarting the match handler if it couldn't be started
+fn play_game(reporter: &dyn Reporter, suite: &RegressionSuite, opponent: usize, game: u32, use_pipe: bool) -> GameResult {
+    let bot_team = if game % 2 == 0 { Team::Blue } else { Team::Orange };
+
+    let mut result = GameResult {
+        opponent,
+        game,
+        bot_team: bot_team.clone(),
+        outcome: Outcome::Unfinished,
+        bot_goals: 0,
+        opponent_goals: 0,
+        record_id: None,
+        crash_output: Vec::new(),
+        error: None,
+    };
+
+    let bots = vec![
+        suite.bot.on_team(bot_team.clone()),
+        suite.opponents[opponent].on_team(opposing_team(&bot_team)),
+    ];
+    let start = || {
+        tauri_block_on(start_match_helper(
+            reporter,
+            bots.clone(),
+            suite.match_settings.clone(),
+            use_pipe,
+            true,
+        ))
+    };
+
+    start_handler_output_capture();
+
+    let mut started = start();
+    if let Err(e) = &started {
+        ccprintln!(
+            reporter,
+            "Couldn't start the game ({e}), restarting the match handler and trying again"
+        );
+        if let Err(e) = supervisor::shut_down() {
+            ccprintln!(reporter, "Couldn't shut down the match handler: {e}");
+        }
+
+        started = start();
+    }
+
+    match started.map(|_| match_history::await_match_result()) {
+        Ok(Ok(Some(record))) => {
+            result.record_id = Some(record.id.clone());
+            result.bot_goals = goals(&record, &bot_team);
+            result.opponent_goals = goals(&record, &opposing_team(&bot_team));
+
+            if record.completed {
+                result.outcome = match &record.winner {
+                    Some(winner) if winner == &bot_team => Outcome::Win,
+                    Some(_) => Outcome::Loss,
+                    None => Outcome::Draw,
+                };
+            } else {
+                result.error = Some("The game didn't reach the end".to_owned());
+            }
+        }
+        Ok(Ok(None)) => result.error = Some("The game never started".to_owned()),
+        Ok(Err(e)) => result.error = Some(format!("Couldn't get the result of the game: {e}")),
+        Err(e) => result.error = Some(format!("Couldn't start the game: {e}")),
+    }
+
+    if let Err(e) = tauri_block_on(kill_bots_helper(reporter)) {
+        ccprintln!(reporter, "Couldn't stop the bots after the game: {e}");
+    }
+
+    // Start the next game with a fresh match handler, in case this one is stuck
+    if result.outcome == Outcome::Unfinished {
+        if let Err(e) = supervisor::shut_down() {
+            ccprintln!(reporter, "Couldn't shut down the match handler: {e}");
+        }
+    }
+
+    result.crash_output = crash_lines(take_handler_output_capture());
+
+    result
+}
+
+fn emit_update(reporter: &dyn Reporter, report: &RegressionReport) {
+    if let Err(e) = reporter.emit(REGRESSION_UPDATED_SIGNAL, report) {
+        ccprintln!(reporter, "Failed to emit {REGRESSION_UPDATED_SIGNAL}: {e}");
+    }
+}
+
+/// Play every game of a suite, then save the report
+fn run(reporter: &dyn Reporter, suite: RegressionSuite, running: &AtomicBool, use_pipe: bool) {
+    let baseline = read_baseline(&suite.name).unwrap_or_else(|e| {
+        ccprintln!(reporter, "Couldn't read the baseline of \"{}\": {e}", suite.name);
+        None
+    });
+
+    let mut report = RegressionReport::new(&suite);
+    report.summarize(&suite, baseline.as_ref());
+
+    'opponents: for (opponent, entrant) in suite.opponents.iter().enumerate() {
+        for game in 0..suite.games_per_opponent {
+            if !running.load(Ordering::Relaxed) {
+                report.stopped = true;
+                break 'opponents;
+            }
+
+            ccprintln!(
+                reporter,
+                "Regression suite \"{}\": {} vs {}, game {} of {}",
+                suite.name,
+                suite.bot.name,
+                entrant.name,
+                game + 1,
+                suite.games_per_opponent
+            );
+
+            let result = play_game(reporter, &suite, opponent, game, use_pipe);
+            report.games.push(result);
+            report.summarize(&suite, baseline.as_ref());
+            emit_update(reporter, &report);
+
+            // Save after every game, so that the games that were played aren't lost if the GUI is closed
+            if let Err(e) = write_report(&report) {
+                ccprintln!(reporter, "Couldn't save the regression report: {e}");
+            }
+
+            thread::sleep(BETWEEN_GAMES);
+        }
+    }
+
+    report.ended_at = Some(unix_time());
+    if let Err(e) = write_report(&report) {
+        ccprintln!(reporter, "Couldn't save the regression report: {e}");
+    }
+
+    emit_update(reporter, &report);
+
+    ccprintln!(
+        reporter,
+        "Regression suite \"{}\" {}: {:.1}% win rate, {:+} goal difference, {} crashes, {} regressions",
+        suite.name,
+        if report.stopped { "stopped" } else { "finished" },
+        report.overall.win_rate * 100.,
+        report.overall.goal_difference,
+        report.overall.crashes,
+        report.regressions.len()
+    );
+}
+
+/// Runs a regression suite from a background thread
+pub struct RegressionRunner {
+    name: String,
+    running: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+/// Start running a regression suite without any input needed
+///
+/// # Arguments
+///
+/// * `reporter` - Where to send console output and events, like the GUI
+/// * `name` - The name of the suite
+/// * `use_pipe` - Whether or not to capture the output of the match handler
+pub fn start_suite(reporter: Box<dyn Reporter>, name: &str, use_pipe: bool) -> Result<(), RegressionError> {
+    let mut runner = REGRESSION_RUNNER
+        .lock()
+        .map_err(|_| RegressionError::Poisoned("REGRESSION_RUNNER"))?;
+
+    let suite = read_suite(name)?;
+    let slot = AutomationSlot::reserve(Automation::RegressionSuite(suite.name.clone()))?;
+    let running = Arc::new(AtomicBool::new(true));
+    let thread_running = running.clone();
+    let name = suite.name.clone();
+    let thread = thread::spawn(move || {
+        run(&*reporter, suite, &thread_running, use_pipe);
+        drop(slot);
+    });
+
+    *runner = Some(RegressionRunner { name, running, thread });
+
+    Ok(())
+}
+
+/// Stop the regression suite after the current game has finished, saving a report of the games that were played
+///
+/// Returns the name of the suite
+pub fn stop_suite() -> Result<String, RegressionError> {
+    let runner = REGRESSION_RUNNER
+        .lock()
+        .map_err(|_| RegressionError::Poisoned("REGRESSION_RUNNER"))?;
+
+    let runner = runner
+        .as_ref()
+        .filter(|runner| !runner.thread.is_finished())
+        .ok_or(RegressionError::NotRunning)?;
+    runner.running.store(false, Ordering::Relaxed);
+
+    Ok(runner.name.clone())
+}
+
+/// Get the name of the regression suite that's currently running, if there is one
+pub fn current_suite() -> Result<Option<String>, RegressionError> {
+    Ok(REGRESSION_RUNNER
+        .lock()
+        .map_err(|_| RegressionError::Poisoned("REGRESSION_RUNNER"))?
+        .as_ref()
+        .filter(|runner| !runner.thread.is_finished())
+        .map(|runner| runner.name.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reporter::MemoryReporter;
+    use std::collections::HashMap;
+
+    fn entrant(name: &str) -> Entrant {
+        Entrant {
+            name: name.to_owned(),
+            runnable_type: "rlbot".to_owned(),
+            skill: 1.,
+            path: Some(format!("bots/{name}.cfg")),
+            supports_early_start: false,
+            maximum_tick_rate_preference: None,
+            bot_parameters: HashMap::new(),
+        }
+    }
+
+    fn suite() -> RegressionSuite {
+        RegressionSuite {
+            name: "Suite".to_owned(),
+            bot: entrant("Mine"),
+            opponents: vec![entrant("Nexto"), entrant("Necto")],
+            games_per_opponent: 2,
+            match_settings: MiniMatchConfig::default(),
+            created_at: 0,
+        }
+    }
+
+    fn game(opponent: usize, outcome: Outcome, bot_goals: usize, opponent_goals: usize) -> GameResult {
+        GameResult {
+            opponent,
+            game: 0,
+            bot_team: Team::Blue,
+            outcome,
+            bot_goals,
+            opponent_goals,
+            record_id: None,
+            crash_output: Vec::new(),
+            error: None,
+        }
+    }
+
+    fn report(games: Vec<GameResult>, baseline: Option<&RegressionReport>) -> RegressionReport {
+        let suite = suite();
+        let mut report = RegressionReport::new(&suite);
+        report.games = games;
+        report.summarize(&suite, baseline);
+        report
+    }
+
+    #[test]
+    fn summary_counts_outcomes() {
+        let games = [
+            game(0, Outcome::Win, 3, 1),
+            game(0, Outcome::Draw, 2, 2),
+            game(1, Outcome::Loss, 0, 1),
+            game(1, Outcome::Unfinished, 5, 0),
+        ];
+        let summary = Summary::from_games(games.iter());
+
+        assert_eq!(summary.played, 4);
+        assert_eq!(
+            (summary.wins, summary.losses, summary.draws, summary.unfinished),
+            (1, 1, 1, 1)
+        );
+        assert_eq!(summary.crashes, 1);
+        // Unfinished games don't count towards the win rate or goal difference
+        assert!((summary.win_rate - 0.5).abs() < 1e-9);
+        assert_eq!(summary.goal_difference, 1);
+    }
+
+    #[test]
+    fn summaries_are_split_by_opponent() {
+        let report = report(vec![game(0, Outcome::Win, 1, 0), game(1, Outcome::Loss, 0, 2)], None);
+
+        assert_eq!(report.opponents[0].summary.wins, 1);
+        assert_eq!(report.opponents[1].summary.losses, 1);
+        assert!(report.baseline.is_none());
+        assert!(report.regressions.is_empty());
+    }
+
+    #[test]
+    fn worse_results_are_flagged() {
+        let baseline = report(vec![game(0, Outcome::Win, 3, 0), game(1, Outcome::Win, 2, 0)], None);
+        let current = report(
+            vec![game(0, Outcome::Loss, 0, 1), game(1, Outcome::Win, 2, 0)],
+            Some(&baseline),
+        );
+
+        assert_eq!(current.baseline.as_deref(), Some(baseline.id.as_str()));
+
+        let flagged = current
+            .regressions
+            .iter()
+            .map(|flag| (flag.opponent.as_deref(), flag.metric))
+            .collect::<Vec<_>>();
+        assert!(flagged.contains(&(None, Metric::WinRate)));
+        assert!(flagged.contains(&(Some("Nexto"), Metric::WinRate)));
+        assert!(flagged.contains(&(Some("Nexto"), Metric::GoalDifferencePerGame)));
+        assert!(!flagged.iter().any(|(opponent, _)| *opponent == Some("Necto")));
+
+        assert!(current.to_markdown().contains("- Nexto: WinRate went from 1.000 to 0.000"));
+    }
+
+    #[test]
+    fn new_crashes_are_flagged() {
+        let baseline = report(vec![game(0, Outcome::Win, 1, 0)], None);
+        let current = report(vec![game(0, Outcome::Unfinished, 0, 0)], Some(&baseline));
+
+        assert!(current
+            .regressions
+            .iter()
+            .any(|flag| flag.opponent.is_none() && flag.metric == Metric::CrashRate));
+    }
+
+    #[test]
+    fn crashes_are_picked_out_of_the_output() {
+        let output = [
+            "Nexto: error rate is 0.1, no error here",
+            "Traceback (most recent call last):",
+            "  File \"bot.py\", line 12, in get_output",
+            "    return self.brain.act(packet)",
+            "ZeroDivisionError: division by zero",
+            "Match started",
+            "thread 'main' panicked at src/main.rs:5:9:",
+            "called `Option::unwrap()` on a `None` value",
+            "Match ended",
+        ];
+
+        assert_eq!(
+            crash_lines(output.map(str::to_owned).to_vec()),
+            vec![
+                "Traceback (most recent call last):",
+                "  File \"bot.py\", line 12, in get_output",
+                "    return self.brain.act(packet)",
+                "ZeroDivisionError: division by zero",
+                "thread 'main' panicked at src/main.rs:5:9:",
+                "called `Option::unwrap()` on a `None` value",
+            ]
+        );
+    }
+
+    #[test]
+    fn updates_are_sent_to_the_reporter() {
+        let reporter = MemoryReporter::new();
+        let report = report(vec![game(0, Outcome::Win, 1, 0)], None);
+
+        emit_update(&reporter, &report);
+
+        let events = reporter.log().events;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0, REGRESSION_UPDATED_SIGNAL);
+        assert_eq!(events[0].1["overall"]["wins"], 1);
+    }
+}
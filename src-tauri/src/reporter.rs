@@ -0,0 +1,211 @@
+use crate::{bot_management::downloader::ProgressBarUpdate, commands::UPDATE_DOWNLOAD_PROGRESS_SIGNAL, emit_text};
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    io::{stdout, Write},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+use tauri::{Error as TauriError, Window};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ReporterError {
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+    #[error(transparent)]
+    Tauri(#[from] TauriError),
+}
+
+/// Somewhere to send console output, progress updates and events to, like the GUI or a terminal
+pub trait Reporter: Send + Sync {
+    /// Print a line of text to the console
+    ///
+    /// If `replace_last` is true, the line replaces the previous one instead of coming after it
+    fn print(&self, text: &str, replace_last: bool);
+
+    /// Update the progress of a long running task, like downloading the botpack
+    ///
+    /// # Arguments
+    ///
+    /// * `percent` - How far along the task is, from 0 to 100
+    /// * `status` - What the task is currently doing
+    fn progress(&self, percent: f64, status: &str);
+
+    /// Send an event with an already serialized payload
+    fn emit_value(&self, event: &str, payload: Value) -> Result<(), ReporterError>;
+
+    /// Get an owned copy of this reporter, so that it can be moved to another thread
+    fn boxed(&self) -> Box<dyn Reporter>;
+}
+
+impl dyn Reporter + '_ {
+    /// Send an event with the given payload
+    pub fn emit<S: Serialize>(&self, event: &str, payload: S) -> Result<(), ReporterError> {
+        self.emit_value(event, serde_json::to_value(payload)?)
+    }
+}
+
+impl Reporter for Window {
+    fn print(&self, text: &str, replace_last: bool) {
+        emit_text(self, text, replace_last);
+    }
+
+    fn progress(&self, percent: f64, status: &str) {
+        if let Err(e) = Window::emit(
+            self,
+            UPDATE_DOWNLOAD_PROGRESS_SIGNAL,
+            ProgressBarUpdate::new(percent, status.to_owned()),
+        ) {
+            emit_text(self, format!("Error when updating progress bar: {e}"), false);
+        }
+    }
+
+    fn emit_value(&self, event: &str, payload: Value) -> Result<(), ReporterError> {
+        Ok(Window::emit(self, event, payload)?)
+    }
+
+    fn boxed(&self) -> Box<dyn Reporter> {
+        Box::new(self.clone())
+    }
+}
+
+/// Prints console output and progress to stdout, for when there's no GUI
+///
+/// Events are only meant for the GUI, so they're ignored
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StdoutReporter;
+
+impl Reporter for StdoutReporter {
+    fn print(&self, text: &str, _replace_last: bool) {
+        println!("{text}");
+    }
+
+    fn progress(&self, percent: f64, status: &str) {
+        // Progress updates come in quickly, so they overwrite each other instead of flooding the terminal
+        print!("\r[{percent:>5.1}%] {status:<50}");
+        if percent >= 100. {
+            println!();
+        }
+
+        stdout().flush().ok();
+    }
+
+    fn emit_value(&self, _event: &str, _payload: Value) -> Result<(), ReporterError> {
+        Ok(())
+    }
+
+    fn boxed(&self) -> Box<dyn Reporter> {
+        Box::new(*self)
+    }
+}
+
+/// Everything that was sent to a `MemoryReporter`, in the order that it was sent
+#[derive(Clone, Debug, Default)]
+pub struct ReporterLog {
+    pub lines: Vec<String>,
+    pub progress: Vec<ProgressBarUpdate>,
+    pub events: Vec<(String, Value)>,
+}
+
+/// Keeps everything that's sent to it in memory, so it can be checked later
+///
+/// Clones (including ones from `boxed`) share the same log
+#[derive(Clone, Debug, Default)]
+pub struct MemoryReporter {
+    log: Arc<Mutex<ReporterLog>>,
+}
+
+impl MemoryReporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<ReporterLog> {
+        // The log is only ever pushed to, so it's still usable if another thread panicked while holding the lock
+        self.log.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Get a copy of everything that has been sent so far
+    pub fn log(&self) -> ReporterLog {
+        self.lock().clone()
+    }
+
+    /// Get a copy of the console output so far
+    pub fn lines(&self) -> Vec<String> {
+        self.lock().lines.clone()
+    }
+
+    /// Clear everything that has been sent so far
+    pub fn clear(&self) {
+        *self.lock() = ReporterLog::default();
+    }
+}
+
+impl Reporter for MemoryReporter {
+    fn print(&self, text: &str, replace_last: bool) {
+        let mut log = self.lock();
+
+        if replace_last {
+            log.lines.pop();
+        }
+
+        log.lines.push(text.to_owned());
+    }
+
+    fn progress(&self, percent: f64, status: &str) {
+        self.lock().progress.push(ProgressBarUpdate::new(percent, status.to_owned()));
+    }
+
+    fn emit_value(&self, event: &str, payload: Value) -> Result<(), ReporterError> {
+        self.lock().events.push((event.to_owned(), payload));
+        Ok(())
+    }
+
+    fn boxed(&self) -> Box<dyn Reporter> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn memory_reporter_keeps_lines_in_order() {
+        let reporter = MemoryReporter::new();
+        reporter.print("Downloading...", false);
+        reporter.print("Downloading... 50%", true);
+        reporter.print("Done", false);
+
+        assert_eq!(reporter.lines(), ["Downloading... 50%", "Done"]);
+    }
+
+    #[test]
+    fn memory_reporter_keeps_progress_and_events() {
+        let reporter = MemoryReporter::new();
+        reporter.progress(25., "Extracting");
+        (&reporter as &dyn Reporter)
+            .emit("match-started", json!({ "id": 1 }))
+            .unwrap();
+
+        let log = reporter.log();
+        assert_eq!(log.progress.len(), 1);
+        assert_eq!(log.progress[0].status, "Extracting");
+        assert_eq!(log.events, [("match-started".to_owned(), json!({ "id": 1 }))]);
+    }
+
+    #[test]
+    fn boxed_memory_reporters_share_a_log() {
+        let reporter = MemoryReporter::new();
+        let boxed = reporter.boxed();
+
+        std::thread::spawn(move || boxed.print("From another thread", false))
+            .join()
+            .unwrap();
+        assert_eq!(reporter.lines(), ["From another thread"]);
+
+        reporter.clear();
+        assert!(reporter.log().lines.is_empty());
+    }
+}
@@ -1,4 +1,7 @@
 pub(crate) mod agents;
 pub(crate) mod gateway_util;
+pub(crate) mod packet_stream;
 pub(crate) mod parsing;
 pub(crate) mod setup_manager;
+pub(crate) mod socket;
+pub(crate) mod state_setting;
@@ -1,2 +1,3 @@
 pub mod base_script;
+pub mod executable_agent;
 pub mod runnable;
@@ -0,0 +1,140 @@
+use crate::{
+    bot_management::{
+        cfg_document::CfgDocument,
+        cfg_helper::{load_cfg, Error},
+    },
+    get_content_folder,
+    rlbot::parsing::bot_config_bundle::{
+        BotConfigBundle, RuntimeKind, BOT_CONFIG_MODULE_HEADER, BOT_CONFIG_PARAMS_HEADER, EXECUTABLE_PATH_KEY,
+        LOADOUT_GENERATOR_FILE_KEY, LOGO_FILE_KEY, LOOKS_CONFIG_KEY, PYTHON_FILE_KEY, REQUIREMENTS_FILE_KEY,
+    },
+    settings::TeamBotBundle,
+};
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+};
+use tokio::fs as async_fs;
+
+/// The folder in the content folder that the generated agent and configs go in
+const LAUNCHERS_FOLDER: &str = "launchers";
+const AGENT_FILE_NAME: &str = "executable_agent.py";
+
+/// Keys in `[Locations]` that point to files, which have to be made absolute when the config is copied
+const LOCATION_PATH_KEYS: [&str; 4] = [
+    LOOKS_CONFIG_KEY,
+    LOGO_FILE_KEY,
+    LOADOUT_GENERATOR_FILE_KEY,
+    REQUIREMENTS_FILE_KEY,
+];
+
+/// The `python_file` that RLBot's Python framework runs for executable bots
+///
+/// It's the agent from RLBot's Rust template, which starts `[Bot Parameters] path` as a helper process.
+/// The index of the bot is passed to the executable as `--player-index`
+const EXECUTABLE_AGENT: &str = r#"
+import os
+
+from rlbot.agents.base_agent import BOT_CONFIG_AGENT_HEADER
+from rlbot.agents.base_independent_agent import BaseIndependentAgent
+from rlbot.botmanager.helper_process_request import HelperProcessRequest
+from rlbot.parsing.custom_config import ConfigHeader, ConfigObject
+
+
+class ExecutableAgent(BaseIndependentAgent):
+    def __init__(self, name, team, index):
+        super().__init__(name, team, index)
+        self.executable_path = None
+
+    def load_config(self, config_header: ConfigHeader):
+        self.executable_path = config_header.getpath("path")
+
+    def get_helper_process_request(self):
+        return HelperProcessRequest(
+            python_file_path=None,
+            key=f"{self.executable_path}-{self.index}",
+            executable=self.executable_path,
+            exe_args=["--player-index", str(self.index)],
+            current_working_directory=os.path.dirname(self.executable_path),
+        )
+
+    def run_independently(self, terminate_request_event):
+        # The executable does all of the work in its helper process
+        terminate_request_event.wait()
+
+    @staticmethod
+    def create_agent_configurations(config: ConfigObject):
+        params = config.get_header(BOT_CONFIG_AGENT_HEADER)
+        params.add_value("path", str, default=None, description="The path to the bot's executable")
+"#;
+
+/// Point executable bots at a copy of their config that runs them through a generated `python_file`,
+/// since the match handler can only start bots through one
+///
+/// Bots that are run through Python, and bots whose config can't be read, are left as they are
+///
+/// # Arguments
+///
+/// * `bot_list` - The bots in the match
+pub async fn with_launchers(mut bot_list: Vec<TeamBotBundle>) -> Result<Vec<TeamBotBundle>, Error> {
+    for bot in bot_list.iter_mut().filter(|bot| bot.runnable_type == "rlbot") {
+        let Some(path) = bot.path.as_deref() else {
+            continue;
+        };
+
+        let Ok(bundle) = BotConfigBundle::minimal_from_path(path).await else {
+            continue;
+        };
+
+        if let (RuntimeKind::Executable, Some(executable)) = (bundle.runtime, &bundle.executable_path) {
+            let launcher_path = write_launcher_config(Path::new(path), executable).await?;
+            bot.path = Some(launcher_path.display().to_string());
+        }
+    }
+
+    Ok(bot_list)
+}
+
+/// Copy a bot's config to the launchers folder, with the generated agent as its `python_file`
+///
+/// The copy is named after a hash of the original path, so the same bot always reuses the same copy
+///
+/// Returns the path to the copy
+///
+/// # Arguments
+///
+/// * `config_path` - The path to the bot's config
+/// * `executable` - The absolute path to the bot's executable
+async fn write_launcher_config(config_path: &Path, executable: &str) -> Result<PathBuf, Error> {
+    let folder = get_content_folder().join(LAUNCHERS_FOLDER);
+    async_fs::create_dir_all(&folder).await?;
+
+    let agent_path = folder.join(AGENT_FILE_NAME);
+    async_fs::write(&agent_path, EXECUTABLE_AGENT).await?;
+
+    let conf = load_cfg(config_path).await?;
+    let mut document = CfgDocument::load(config_path).await?;
+    let config_directory = config_path.parent().unwrap_or_else(|| Path::new("."));
+
+    // The copy is in a different folder, so relative paths wouldn't point to the bot's files anymore
+    for key in LOCATION_PATH_KEYS {
+        let value = conf.get(BOT_CONFIG_MODULE_HEADER, key).unwrap_or_default();
+        if !value.trim().is_empty() {
+            let absolute = config_directory.join(value.trim());
+            document.set(BOT_CONFIG_MODULE_HEADER, key, absolute.display().to_string());
+        }
+    }
+
+    document.set(BOT_CONFIG_MODULE_HEADER, PYTHON_FILE_KEY, agent_path.display().to_string());
+    document.set(BOT_CONFIG_PARAMS_HEADER, EXECUTABLE_PATH_KEY, executable);
+
+    let mut hasher = DefaultHasher::new();
+    config_path.hash(&mut hasher);
+    let stem = config_path.file_stem().unwrap_or_default().to_string_lossy();
+    let launcher_path = folder.join(format!("{stem}-{:016x}.cfg", hasher.finish()));
+
+    document.save(&launcher_path).await?;
+
+    Ok(launcher_path)
+}
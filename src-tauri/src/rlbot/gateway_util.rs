@@ -1,6 +1,5 @@
-use crate::ccprintln;
+use crate::{ccprintln, reporter::Reporter};
 use sysinfo::{ProcessExt, ProcessRefreshKind, RefreshKind, System, SystemExt};
-use tauri::Window;
 
 pub const IDEAL_RLBOT_PORT: u16 = 23233;
 const EXECUTABLE_NAME: &str = if cfg!(windows) {
@@ -11,7 +10,7 @@ const EXECUTABLE_NAME: &str = if cfg!(windows) {
     "RLBot"
 };
 
-pub fn find_existing_process(window: &Window) -> Option<u16> {
+pub fn find_existing_process(reporter: &dyn Reporter) -> Option<u16> {
     let system = System::new_with_specifics(RefreshKind::new().with_processes(ProcessRefreshKind::new()));
 
     if let Some(process_info) = system.processes_by_name(EXECUTABLE_NAME).next() {
@@ -19,30 +18,37 @@ pub fn find_existing_process(window: &Window) -> Option<u16> {
             let port = arg
                 .parse::<u16>()
                 .map_err(|e| {
-                    ccprintln(window, e.to_string());
+                    ccprintln(reporter, e.to_string());
                 })
                 .ok()?;
-            ccprintln!(window, "Found existing RLBot process listening on port {port}");
+            ccprintln!(reporter, "Found existing RLBot process listening on port {port}");
             return Some(port);
         }
     }
 
-    ccprintln(window, "No existing RLBot process found...");
+    ccprintln(reporter, "No existing RLBot process found...");
     None
 }
 
-pub fn kill_existing_processes(window: &Window) {
+/// Get the port of the RLBot process that's already running, without printing anything to the console
+pub fn existing_process_port() -> Option<u16> {
+    let system = System::new_with_specifics(RefreshKind::new().with_processes(ProcessRefreshKind::new()));
+    let process_info = system.processes_by_name(EXECUTABLE_NAME).next()?;
+    process_info.cmd().get(1)?.parse().ok()
+}
+
+pub fn kill_existing_processes(reporter: &dyn Reporter) {
     let system = System::new_with_specifics(RefreshKind::new().with_processes(ProcessRefreshKind::new()));
 
     let mut found = false;
     // there might be multiple processes, so just be able to kill them all encase
     for process_info in system.processes_by_name(EXECUTABLE_NAME) {
-        ccprintln(window, "Killing existing RLBot process");
+        ccprintln(reporter, "Killing existing RLBot process");
         process_info.kill();
         found = true;
     }
 
     if !found {
-        ccprintln(window, "No existing RLBot process found");
+        ccprintln(reporter, "No existing RLBot process found");
     }
 }
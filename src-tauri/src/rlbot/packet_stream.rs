@@ -0,0 +1,160 @@
+use crate::{
+    ccprintln, impl_serialize_from_display,
+    match_handler::{issue_match_handler_command, replying_handler_pid, CreateHandler, MatchHandlerError},
+    reporter::Reporter,
+    settings::GameTickPacket,
+    GAME_TICK_PACKET_FALLBACK, GAME_TICK_PACKET_FEED, GAME_TICK_PACKET_STREAM, PYTHON_PATH,
+};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use tauri::async_runtime::block_on as tauri_block_on;
+use thiserror::Error;
+
+pub const GAME_TICK_PACKET_SIGNAL: &str = "game-tick-packet";
+/// RLBot doesn't produce packets any faster than this, so there's no point in sending them faster
+const MAX_RATE: f32 = 120.;
+
+#[derive(Debug, Error)]
+pub enum PacketStreamError {
+    #[error("Packets can't be sent {0} times per second")]
+    InvalidRate(f32),
+    #[error("Mutex {0} was poisoned")]
+    Poisoned(&'static str),
+}
+
+impl_serialize_from_display!(PacketStreamError);
+
+/// Get the latest game tick packet, directly from RLBot if possible and otherwise from the match handler
+///
+/// The match handler is only asked once for as long as RLBot can't be reached and the same match handler is running,
+/// unless it actually had a packet, since this is called many times a second
+///
+/// Returns `None` if neither are running
+pub async fn latest_game_tick_packet(reporter: &dyn Reporter) -> Result<Option<GameTickPacket>, MatchHandlerError> {
+    let packet = GAME_TICK_PACKET_FEED.lock().ok().and_then(|mut feed| feed.latest());
+    if packet.is_some() {
+        if let Ok(mut fallback) = GAME_TICK_PACKET_FALLBACK.lock() {
+            *fallback = None;
+        }
+        return Ok(packet);
+    }
+
+    let Some(pid) = replying_handler_pid() else {
+        return Ok(None);
+    };
+
+    {
+        let fallback = GAME_TICK_PACKET_FALLBACK
+            .lock()
+            .map_err(|_| MatchHandlerError::Poisoned("GAME_TICK_PACKET_FALLBACK"))?;
+        if *fallback == Some(pid) {
+            return Ok(None);
+        }
+    }
+
+    let packet = issue_match_handler_command(
+        reporter,
+        &["fetch_gtp".to_owned()],
+        CreateHandler::No,
+        &*PYTHON_PATH.read().await,
+    )
+    .await;
+
+    if !matches!(packet, Ok(Some(_))) {
+        if let Ok(mut fallback) = GAME_TICK_PACKET_FALLBACK.lock() {
+            *fallback = Some(pid);
+        }
+    }
+
+    packet
+}
+
+/// Sends game tick packets to the GUI from a background thread until it's dropped
+pub struct PacketStream {
+    running: Arc<AtomicBool>,
+}
+
+impl PacketStream {
+    fn start(reporter: Box<dyn Reporter>, rate: f32) -> Self {
+        let running = Arc::new(AtomicBool::new(true));
+        let thread_running = running.clone();
+        let interval = Duration::from_secs_f32(1. / rate);
+
+        thread::spawn(move || {
+            // The same packet shouldn't be sent twice, like when the game is paused
+            let mut last_sent = None;
+            // Errors are only printed when they change, so that the console isn't flooded with the same one every tick
+            let mut last_error = None;
+
+            while thread_running.load(Ordering::Relaxed) {
+                let start_time = Instant::now();
+
+                match tauri_block_on(latest_game_tick_packet(&*reporter)) {
+                    Ok(Some(packet)) => {
+                        let key = (packet.game_info.frame_num, packet.game_info.seconds_elapsed.to_bits());
+
+                        if last_sent != Some(key) {
+                            last_sent = Some(key);
+
+                            if let Err(e) = reporter.emit(GAME_TICK_PACKET_SIGNAL, packet) {
+                                ccprintln!(&*reporter, "Failed to emit {GAME_TICK_PACKET_SIGNAL}: {e}");
+                            }
+                        }
+
+                        last_error = None;
+                    }
+                    Ok(None) => last_error = None,
+                    Err(e) => {
+                        let error = e.to_string();
+
+                        if last_error.as_ref() != Some(&error) {
+                            ccprintln!(&*reporter, "Couldn't get game tick packet: {error}");
+                            last_error = Some(error);
+                        }
+                    }
+                }
+
+                thread::sleep(interval.saturating_sub(start_time.elapsed()));
+            }
+        });
+
+        Self { running }
+    }
+}
+
+impl Drop for PacketStream {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Start sending game tick packets to the GUI `rate` times per second, replacing any existing subscription
+pub fn subscribe(reporter: Box<dyn Reporter>, rate: f32) -> Result<(), PacketStreamError> {
+    if !rate.is_finite() || rate <= 0. {
+        return Err(PacketStreamError::InvalidRate(rate));
+    }
+
+    let stream = PacketStream::start(reporter, rate.min(MAX_RATE));
+
+    *GAME_TICK_PACKET_STREAM
+        .lock()
+        .map_err(|_| PacketStreamError::Poisoned("GAME_TICK_PACKET_STREAM"))? = Some(stream);
+
+    Ok(())
+}
+
+/// Stop sending game tick packets to the GUI
+pub fn unsubscribe() -> Result<(), PacketStreamError> {
+    GAME_TICK_PACKET_STREAM
+        .lock()
+        .map_err(|_| PacketStreamError::Poisoned("GAME_TICK_PACKET_STREAM"))?
+        .take();
+
+    Ok(())
+}
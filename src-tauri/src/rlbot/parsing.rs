@@ -1,4 +1,7 @@
 pub mod agent_config_parser;
 pub mod bot_config_bundle;
+pub mod bot_config_linter;
+pub mod config_diagnostics;
 pub mod directory_scanner;
+pub mod item_catalog;
 pub mod match_settings_config_parser;
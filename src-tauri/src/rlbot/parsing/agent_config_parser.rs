@@ -1,17 +1,24 @@
-use crate::{
-    bot_management::cfg_helper::{load_cfg, save_cfg, Error},
-    ccprintln,
+use super::item_catalog::{ItemCatalog, ItemError, ItemSlot};
+use crate::bot_management::{
+    cfg_document::CfgDocument,
+    cfg_helper::{load_cfg, Error},
 };
-use configparser::ini::Ini;
 use serde::{Deserialize, Serialize};
-use tauri::Window;
+use std::path::Path;
 
 pub const BOT_CONFIG_LOADOUT_HEADER: &str = "Bot Loadout";
 pub const BOT_CONFIG_LOADOUT_ORANGE_HEADER: &str = "Bot Loadout Orange";
 pub const BOT_CONFIG_LOADOUT_PAINT_BLUE_HEADER: &str = "Bot Paint Blue";
 pub const BOT_CONFIG_LOADOUT_PAINT_ORANGE_HEADER: &str = "Bot Paint Orange";
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+/// The colors that can be picked for `team_color_id` and `custom_color_id`
+pub const TEAM_COLOR_COUNT: u32 = 70;
+pub const CUSTOM_COLOR_COUNT: u32 = 105;
+/// The paints that can be picked for the `*_paint_id` keys, where `0` is unpainted
+pub const PAINT_COUNT: u32 = 14;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(default)]
 pub struct BotTeamLooksConfig {
     pub team_color_id: String,
     pub custom_color_id: String,
@@ -94,46 +101,87 @@ impl BotTeamLooksConfig {
         })
     }
 
-    pub fn save_to_config(&self, config: &mut Ini, loadout_header: &str, paint_header: &str) {
-        config.set(loadout_header, "team_color_id", Some(self.team_color_id.clone()));
-        config.set(loadout_header, "custom_color_id", Some(self.custom_color_id.clone()));
-        config.set(loadout_header, "car_id", Some(self.car_id.clone()));
-        config.set(loadout_header, "decal_id", Some(self.decal_id.clone()));
-        config.set(loadout_header, "wheels_id", Some(self.wheels_id.clone()));
-        config.set(loadout_header, "boost_id", Some(self.boost_id.clone()));
-        config.set(loadout_header, "antenna_id", Some(self.antenna_id.clone()));
-        config.set(loadout_header, "hat_id", Some(self.hat_id.clone()));
-        config.set(loadout_header, "paint_finish_id", Some(self.paint_finish_id.clone()));
-        config.set(loadout_header, "custom_finish_id", Some(self.custom_finish_id.clone()));
-        config.set(loadout_header, "engine_audio_id", Some(self.engine_audio_id.clone()));
-        config.set(loadout_header, "trails_id", Some(self.trails_id.clone()));
-        config.set(loadout_header, "goal_explosion_id", Some(self.goal_explosion_id.clone()));
-        config.set(
-            loadout_header,
-            "primary_color_lookup",
-            Some(self.primary_color_lookup.clone()),
-        );
-        config.set(
-            loadout_header,
-            "secondary_color_lookup",
-            Some(self.secondary_color_lookup.clone()),
-        );
-        config.set(paint_header, "car_paint_id", Some(self.car_paint_id.clone()));
-        config.set(paint_header, "decal_paint_id", Some(self.decal_paint_id.clone()));
-        config.set(paint_header, "wheels_paint_id", Some(self.wheels_paint_id.clone()));
-        config.set(paint_header, "boost_paint_id", Some(self.boost_paint_id.clone()));
-        config.set(paint_header, "antenna_paint_id", Some(self.antenna_paint_id.clone()));
-        config.set(paint_header, "hat_paint_id", Some(self.hat_paint_id.clone()));
-        config.set(paint_header, "trails_paint_id", Some(self.trails_paint_id.clone()));
-        config.set(
-            paint_header,
-            "goal_explosion_paint_id",
-            Some(self.goal_explosion_paint_id.clone()),
-        );
+    /// The keys that hold items, along with the slot that each one is for
+    pub fn item_ids(&self) -> [(&'static str, ItemSlot, &String); 11] {
+        [
+            ("car_id", ItemSlot::Body, &self.car_id),
+            ("decal_id", ItemSlot::Skin, &self.decal_id),
+            ("wheels_id", ItemSlot::Wheels, &self.wheels_id),
+            ("boost_id", ItemSlot::Boost, &self.boost_id),
+            ("antenna_id", ItemSlot::Antenna, &self.antenna_id),
+            ("hat_id", ItemSlot::Hat, &self.hat_id),
+            ("paint_finish_id", ItemSlot::PaintFinish, &self.paint_finish_id),
+            ("custom_finish_id", ItemSlot::PaintFinish, &self.custom_finish_id),
+            ("engine_audio_id", ItemSlot::EngineAudio, &self.engine_audio_id),
+            ("trails_id", ItemSlot::SupersonicTrail, &self.trails_id),
+            ("goal_explosion_id", ItemSlot::GoalExplosion, &self.goal_explosion_id),
+        ]
+    }
+
+    fn item_ids_mut(&mut self) -> [(&'static str, ItemSlot, &mut String); 11] {
+        [
+            ("car_id", ItemSlot::Body, &mut self.car_id),
+            ("decal_id", ItemSlot::Skin, &mut self.decal_id),
+            ("wheels_id", ItemSlot::Wheels, &mut self.wheels_id),
+            ("boost_id", ItemSlot::Boost, &mut self.boost_id),
+            ("antenna_id", ItemSlot::Antenna, &mut self.antenna_id),
+            ("hat_id", ItemSlot::Hat, &mut self.hat_id),
+            ("paint_finish_id", ItemSlot::PaintFinish, &mut self.paint_finish_id),
+            ("custom_finish_id", ItemSlot::PaintFinish, &mut self.custom_finish_id),
+            ("engine_audio_id", ItemSlot::EngineAudio, &mut self.engine_audio_id),
+            ("trails_id", ItemSlot::SupersonicTrail, &mut self.trails_id),
+            ("goal_explosion_id", ItemSlot::GoalExplosion, &mut self.goal_explosion_id),
+        ]
+    }
+
+    /// Check that every item is from the right slot, and write the IDs the way that RLBot expects them
+    ///
+    /// Returns the IDs that aren't in the catalog, which are kept since they could be from a newer version of the game
+    pub fn normalize_items(&mut self, catalog: &ItemCatalog) -> Result<Vec<ItemError>, ItemError> {
+        let mut unknown = Vec::new();
+
+        for (key, slot, value) in self.item_ids_mut() {
+            match catalog.normalize_id(key, slot, value) {
+                Ok(id) => *value = id,
+                Err(ItemError::UnknownItem { key, id }) => {
+                    *value = id.to_string();
+                    unknown.push(ItemError::UnknownItem { key, id });
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(unknown)
+    }
+
+    pub fn save_to_config(&self, config: &mut CfgDocument, loadout_header: &str, paint_header: &str) {
+        config.set(loadout_header, "team_color_id", &self.team_color_id);
+        config.set(loadout_header, "custom_color_id", &self.custom_color_id);
+        config.set(loadout_header, "car_id", &self.car_id);
+        config.set(loadout_header, "decal_id", &self.decal_id);
+        config.set(loadout_header, "wheels_id", &self.wheels_id);
+        config.set(loadout_header, "boost_id", &self.boost_id);
+        config.set(loadout_header, "antenna_id", &self.antenna_id);
+        config.set(loadout_header, "hat_id", &self.hat_id);
+        config.set(loadout_header, "paint_finish_id", &self.paint_finish_id);
+        config.set(loadout_header, "custom_finish_id", &self.custom_finish_id);
+        config.set(loadout_header, "engine_audio_id", &self.engine_audio_id);
+        config.set(loadout_header, "trails_id", &self.trails_id);
+        config.set(loadout_header, "goal_explosion_id", &self.goal_explosion_id);
+        config.set(loadout_header, "primary_color_lookup", &self.primary_color_lookup);
+        config.set(loadout_header, "secondary_color_lookup", &self.secondary_color_lookup);
+        config.set(paint_header, "car_paint_id", &self.car_paint_id);
+        config.set(paint_header, "decal_paint_id", &self.decal_paint_id);
+        config.set(paint_header, "wheels_paint_id", &self.wheels_paint_id);
+        config.set(paint_header, "boost_paint_id", &self.boost_paint_id);
+        config.set(paint_header, "antenna_paint_id", &self.antenna_paint_id);
+        config.set(paint_header, "hat_paint_id", &self.hat_paint_id);
+        config.set(paint_header, "trails_paint_id", &self.trails_paint_id);
+        config.set(paint_header, "goal_explosion_paint_id", &self.goal_explosion_paint_id);
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct BotLooksConfig {
     pub blue: BotTeamLooksConfig,
     pub orange: BotTeamLooksConfig,
@@ -153,18 +201,41 @@ impl BotLooksConfig {
         })
     }
 
-    pub async fn save_to_path(&self, window: &Window, path: &str) {
-        let mut config = Ini::new();
+    /// Check that every item is from the right slot, and write the IDs the way that RLBot expects them
+    ///
+    /// Returns the IDs that aren't in the catalog, which are kept
+    pub fn normalize_items(&mut self, catalog: &ItemCatalog) -> Result<Vec<ItemError>, ItemError> {
+        let mut unknown = self.blue.normalize_items(catalog)?;
+        unknown.extend(self.orange.normalize_items(catalog)?);
+
+        Ok(unknown)
+    }
+
+    /// Swap the looks of the blue and orange teams
+    pub fn swap_teams(&mut self) {
+        std::mem::swap(&mut self.blue, &mut self.orange);
+    }
+
+    /// Write the looks of both teams to the sections that RLBot reads them from
+    pub fn save_to_config(&self, config: &mut CfgDocument) {
         self.blue
-            .save_to_config(&mut config, BOT_CONFIG_LOADOUT_HEADER, BOT_CONFIG_LOADOUT_PAINT_BLUE_HEADER);
+            .save_to_config(config, BOT_CONFIG_LOADOUT_HEADER, BOT_CONFIG_LOADOUT_PAINT_BLUE_HEADER);
         self.orange.save_to_config(
-            &mut config,
+            config,
             BOT_CONFIG_LOADOUT_ORANGE_HEADER,
             BOT_CONFIG_LOADOUT_PAINT_ORANGE_HEADER,
         );
+    }
 
-        if let Err(e) = save_cfg(&config, path).await {
-            ccprintln!(window, "Error saving bot config to {path}: {e}");
-        }
+    /// Save the looks to a file, keeping any comments and other sections that are already in it
+    pub async fn save_to_path(&self, path: &str) -> Result<(), Error> {
+        let mut config = if Path::new(path).exists() {
+            CfgDocument::load(path).await?
+        } else {
+            CfgDocument::default()
+        };
+
+        self.save_to_config(&mut config);
+        config.save(path).await
     }
 }
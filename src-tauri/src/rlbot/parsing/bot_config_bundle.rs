@@ -1,9 +1,13 @@
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+
 use crate::{
     bot_management::cfg_helper::{load_cfg, load_cfg_sync, Error},
     ccprintln, get_command_status,
+    reporter::Reporter,
     rlbot::agents::{base_script::SCRIPT_FILE_KEY, runnable::Runnable},
 };
 use base64::{prelude::BASE64_STANDARD, Engine};
@@ -12,31 +16,30 @@ use imghdr::Type;
 use serde::{Deserialize, Serialize};
 use std::{
     borrow::ToOwned,
+    collections::HashMap,
     ffi::OsStr,
     fs,
     io::Read,
-    path::Path,
+    path::{Path, PathBuf},
     process::{self, Stdio},
     str::from_utf8,
 };
-use tauri::Window;
 use thiserror::Error;
 
 pub const PYTHON_FILE_KEY: &str = "python_file";
 pub const REQUIREMENTS_FILE_KEY: &str = "requirements_file";
 pub const LOGO_FILE_KEY: &str = "logo_file";
 pub const NAME_KEY: &str = "name";
-// pub const SUPPORTS_EARLY_START_KEY: &str = "supports_early_start";
 pub const REQUIRES_TKINTER: &str = "requires_tkinter";
 pub const USE_VIRTUAL_ENVIRONMENT_KEY: &str = "use_virtual_environment";
 
 pub const BOT_CONFIG_MODULE_HEADER: &str = "Locations";
 pub const BOT_CONFIG_DETAILS_HEADER: &str = "Details";
 pub const SUPPORTS_STANDALONE: &str = "supports_standalone";
-// pub const LOADOUT_GENERATOR_FILE_KEY: &str = "loadout_generator";
+pub const LOADOUT_GENERATOR_FILE_KEY: &str = "loadout_generator";
 pub const LOOKS_CONFIG_KEY: &str = "looks_config";
-// pub const SUPPORTS_EARLY_START_KEY: &str = "supports_early_start";
-// pub const MAXIMUM_TICK_RATE_PREFERENCE_KEY: &str = "maximum_tick_rate_preference";
+pub const SUPPORTS_EARLY_START_KEY: &str = "supports_early_start";
+pub const MAXIMUM_TICK_RATE_PREFERENCE_KEY: &str = "maximum_tick_rate_preference";
 
 pub const BOT_CONFIG_PARAMS_HEADER: &str = "Bot Parameters";
 pub const EXECUTABLE_PATH_KEY: &str = "path";
@@ -140,6 +143,87 @@ pub enum RLBotCfgParseError {
     NoPythonFile(String),
     #[error("No script file found in config file {0}")]
     NoScriptFile(String),
+    #[error("The executable {1} from config file {0} doesn't exist")]
+    NoExecutable(String, String),
+    #[error("The executable {1} from config file {0} can't be run on this OS")]
+    NotExecutable(String, String),
+}
+
+/// What a bot needs to be run with
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum RuntimeKind {
+    /// The bot is run by RLBot's Python framework from its `python_file`
+    #[default]
+    Python,
+    /// The bot is a standalone program from `[Bot Parameters] path`, so it doesn't need Python packages
+    Executable,
+}
+
+/// Resolve the executable path from a bot's config file, relative to the folder that the config file is in
+///
+/// The bot templates point to `.exe` files, so on other OSes the same path without the extension is also tried
+///
+/// # Arguments
+///
+/// * `config_directory` - The folder that the config file is in
+/// * `value` - The value of `[Bot Parameters] path`
+pub fn resolve_executable(config_directory: &Path, value: &str) -> PathBuf {
+    let path = config_directory.join(value.trim());
+
+    if cfg!(not(windows)) && !path.exists() && path.extension() == Some(OsStr::new("exe")) {
+        let without_extension = path.with_extension("");
+        if without_extension.exists() {
+            return without_extension;
+        }
+    }
+
+    path
+}
+
+/// Get the files that a config file points to, since a bot can start or stop working when one of them changes
+///
+/// The files might not exist, because a bot that's missing its Python file starts working once it's added
+///
+/// # Arguments
+///
+/// * `config_path` - The path to the config file
+pub fn referenced_files(config_path: &Path) -> Vec<PathBuf> {
+    let (Ok(conf), Some(config_directory)) = (load_cfg_sync(config_path), config_path.parent()) else {
+        return Vec::new();
+    };
+    // Collecting the components gets rid of any `./` at the start of the values
+    let in_config_directory = |value: &str| config_directory.join(value.trim()).components().collect::<PathBuf>();
+
+    let mut files = [PYTHON_FILE_KEY, SCRIPT_FILE_KEY, LOOKS_CONFIG_KEY]
+        .into_iter()
+        .filter_map(|key| conf.get(BOT_CONFIG_MODULE_HEADER, key))
+        .map(|value| in_config_directory(&value))
+        .collect::<Vec<_>>();
+
+    if let Some(value) = conf.get(BOT_CONFIG_PARAMS_HEADER, EXECUTABLE_PATH_KEY) {
+        files.push(resolve_executable(config_directory, &value).components().collect());
+    }
+
+    files
+}
+
+/// Check if a file can be run as a program on the current OS
+#[cfg(unix)]
+pub fn is_executable(path: &Path) -> bool {
+    fs::metadata(path).map_or(false, |metadata| {
+        metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+    })
+}
+
+/// Check if a file can be run as a program on the current OS
+#[cfg(not(unix))]
+pub fn is_executable(path: &Path) -> bool {
+    const EXTENSIONS: [&str; 4] = ["exe", "bat", "cmd", "com"];
+
+    path.is_file()
+        && path.extension().and_then(OsStr::to_str).map_or(false, |extension| {
+            EXTENSIONS.iter().any(|ext| ext.eq_ignore_ascii_case(extension))
+        })
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
@@ -163,6 +247,15 @@ pub struct BotConfigBundle {
     requires_tkinter: bool,
     pub missing_python_packages: Option<Vec<String>>,
     pub python_path: String,
+    pub runtime: RuntimeKind,
+    /// The absolute path to the program that runs the bot, if it's an executable bot
+    pub executable_path: Option<String>,
+    /// If the bot can be started before Rocket League has finished loading the match
+    pub supports_early_start: bool,
+    /// The most ticks per second that the bot wants to get, if it has a limit
+    pub maximum_tick_rate_preference: Option<u32>,
+    /// Everything in the `[Bot Parameters]` section, which is passed to the bot when it starts
+    pub bot_parameters: HashMap<String, String>,
 }
 
 impl BotConfigBundle {
@@ -210,14 +303,34 @@ impl BotConfigBundle {
         let config_file_name = config_path.file_name().unwrap().to_string_lossy().to_string();
         let config_directory = config_path.parent().unwrap().to_string_lossy().to_string();
 
-        let python_path = conf
+        let python_file = conf
             .get(BOT_CONFIG_MODULE_HEADER, PYTHON_FILE_KEY)
             .map(|path| format!("{config_directory}/{path}"))
-            .ok_or_else(|| RLBotCfgParseError::NoPythonFile(config_path_str.clone()))?;
+            .filter(|path| Path::new(path).exists());
 
-        if !Path::new(&python_path).exists() {
-            return Err(RLBotCfgParseError::NoPythonFile(config_path_str));
-        }
+        // Bots with a working python file are always run through it, even if they also have an executable
+        let (runtime, python_path, executable_path) = if let Some(python_path) = python_file {
+            (RuntimeKind::Python, python_path, None)
+        } else {
+            // Scripts can have bot parameters too, but they're never bots
+            let executable = conf
+                .get(BOT_CONFIG_PARAMS_HEADER, EXECUTABLE_PATH_KEY)
+                .filter(|_| conf.get(BOT_CONFIG_MODULE_HEADER, SCRIPT_FILE_KEY).is_none())
+                .ok_or_else(|| RLBotCfgParseError::NoPythonFile(config_path_str.clone()))?;
+            let executable = resolve_executable(Path::new(&config_directory), &executable);
+            let executable_str = executable.display().to_string();
+
+            if !executable.is_file() {
+                return Err(RLBotCfgParseError::NoExecutable(config_path_str, executable_str));
+            }
+
+            if !is_executable(&executable) {
+                return Err(RLBotCfgParseError::NotExecutable(config_path_str, executable_str));
+            }
+
+            (RuntimeKind::Executable, String::new(), Some(executable_str))
+        };
+        let is_python = runtime == RuntimeKind::Python;
 
         let name = conf
             .get(BOT_CONFIG_MODULE_HEADER, NAME_KEY)
@@ -231,17 +344,41 @@ impl BotConfigBundle {
             .getboolcoerce(BOT_CONFIG_MODULE_HEADER, SUPPORTS_STANDALONE)
             .unwrap_or_default()
             .unwrap_or_default();
-        let use_virtual_environment = conf
-            .getboolcoerce(BOT_CONFIG_MODULE_HEADER, USE_VIRTUAL_ENVIRONMENT_KEY)
-            .unwrap_or_default()
-            .unwrap_or_default();
+        // Executable bots don't use Python, so none of the Python settings apply to them
+        let use_virtual_environment = is_python
+            && conf
+                .getboolcoerce(BOT_CONFIG_MODULE_HEADER, USE_VIRTUAL_ENVIRONMENT_KEY)
+                .unwrap_or_default()
+                .unwrap_or_default();
         let requirements_file = conf
             .get(BOT_CONFIG_MODULE_HEADER, REQUIREMENTS_FILE_KEY)
+            .filter(|_| is_python)
             .map(|path| format!("{config_directory}/{path}"));
-        let requires_tkinter = conf
-            .getboolcoerce(BOT_CONFIG_MODULE_HEADER, REQUIRES_TKINTER)
+        let requires_tkinter = is_python
+            && conf
+                .getboolcoerce(BOT_CONFIG_MODULE_HEADER, REQUIRES_TKINTER)
+                .unwrap_or_default()
+                .unwrap_or_default();
+
+        let supports_early_start = conf
+            .getboolcoerce(BOT_CONFIG_MODULE_HEADER, SUPPORTS_EARLY_START_KEY)
             .unwrap_or_default()
             .unwrap_or_default();
+        let maximum_tick_rate_preference = conf
+            .get(BOT_CONFIG_MODULE_HEADER, MAXIMUM_TICK_RATE_PREFERENCE_KEY)
+            .and_then(|rate| rate.trim().parse().ok())
+            .filter(|&rate| rate > 0);
+        // `Ini` lowercases the names of sections, so the raw map has to be looked up the same way
+        let bot_parameters = conf
+            .get_map_ref()
+            .get(&BOT_CONFIG_PARAMS_HEADER.to_lowercase())
+            .map(|params| {
+                params
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone().unwrap_or_default()))
+                    .collect()
+            })
+            .unwrap_or_default();
 
         if !Path::new(&looks_path).exists() {
             return Err(RLBotCfgParseError::NoLooksConfig(config_path_str));
@@ -280,43 +417,13 @@ impl BotConfigBundle {
             requires_tkinter,
             missing_python_packages,
             python_path,
+            runtime,
+            executable_path,
+            supports_early_start,
+            maximum_tick_rate_preference,
+            bot_parameters,
         })
     }
-
-    pub fn name_from_path(config_path: &Path) -> Result<(String, String), RLBotCfgParseError> {
-        let config_path_str = config_path.display().to_string();
-        let conf = load_cfg_sync(config_path)?;
-
-        let Some(name) = conf.get(BOT_CONFIG_MODULE_HEADER, NAME_KEY) else {
-            return Err(RLBotCfgParseError::NoName(config_path_str));
-        };
-
-        let path = config_path.to_string_lossy().to_string();
-
-        let config_directory = config_path.parent().unwrap();
-
-        let looks_path = conf
-            .get(BOT_CONFIG_MODULE_HEADER, LOOKS_CONFIG_KEY)
-            .map(|path| format!("{}/{path}", config_directory.display()));
-
-        let valid_looks = looks_path.as_ref().map_or(false, |path| Path::new(path).exists());
-
-        if !valid_looks {
-            return Err(RLBotCfgParseError::NoLooksConfig(config_path_str));
-        }
-
-        let python_path = conf
-            .get(BOT_CONFIG_MODULE_HEADER, PYTHON_FILE_KEY)
-            .map(|path| format!("{}/{path}", config_directory.display()));
-
-        let valid_path = python_path.as_ref().map_or(false, |path| Path::new(path).exists());
-
-        if !valid_path {
-            return Err(RLBotCfgParseError::NoPythonFile(config_path_str));
-        }
-
-        Ok((name, path))
-    }
 }
 
 impl Runnable for BotConfigBundle {
@@ -332,8 +439,8 @@ impl Runnable for BotConfigBundle {
         self.supports_standalone && self.use_virtual_environment
     }
 
-    fn get_missing_packages<S: AsRef<OsStr>>(&self, window: &Window, python: S) -> Vec<String> {
-        if self.use_virtual_environment() {
+    fn get_missing_packages<S: AsRef<OsStr>>(&self, reporter: &dyn Reporter, python: S) -> Vec<String> {
+        if self.runtime == RuntimeKind::Executable || self.use_virtual_environment() {
             return Vec::new();
         }
 
@@ -369,7 +476,7 @@ impl Runnable for BotConfigBundle {
                 serde_json::from_str(output).unwrap_or_default()
             }
             Err(e) => {
-                ccprintln(window, format!("Failed to calculate missing packages: {e}"));
+                ccprintln(reporter, format!("Failed to calculate missing packages: {e}"));
                 Vec::new()
             }
         }
@@ -396,10 +503,12 @@ impl Runnable for BotConfigBundle {
     }
 
     fn may_require_python_packages(&self) -> bool {
-        self.info
-            .as_ref()
-            .map(|info| info.language.to_lowercase().contains("python"))
-            .unwrap_or_default()
+        self.runtime == RuntimeKind::Python
+            && self
+                .info
+                .as_ref()
+                .map(|info| info.language.to_lowercase().contains("python"))
+                .unwrap_or_default()
     }
 }
 
@@ -426,8 +535,14 @@ pub struct ScriptConfigBundle {
 impl ScriptConfigBundle {
     pub async fn minimal_from_path<T: AsRef<Path>>(config_path: T) -> Result<Self, RLBotCfgParseError> {
         let config_path = config_path.as_ref();
-        let conf = load_cfg(config_path).await?;
+        Self::minimal_from_conf(config_path, &load_cfg(config_path).await?)
+    }
+
+    pub fn minimal_from_path_sync(config_path: &Path) -> Result<Self, RLBotCfgParseError> {
+        Self::minimal_from_conf(config_path, &load_cfg_sync(config_path)?)
+    }
 
+    fn minimal_from_conf(config_path: &Path, conf: &Ini) -> Result<Self, RLBotCfgParseError> {
         let config_path_str = config_path.display().to_string();
         // the follow unwrap calls will probably never fail because the config file was loaded successfully, already
         let config_file_name = config_path.file_name().unwrap().to_string_lossy().to_string();
@@ -467,7 +582,7 @@ impl ScriptConfigBundle {
         let absolute_logo_path = format!("{config_directory}/{relative_logo_path}");
         let logo = None;
 
-        let info = Some(DevInfo::from_config(&conf));
+        let info = Some(DevInfo::from_config(conf));
 
         let missing_python_packages = None;
         let logo_path = Some(absolute_logo_path);
@@ -505,7 +620,7 @@ impl Runnable for ScriptConfigBundle {
         self.use_virtual_environment
     }
 
-    fn get_missing_packages<S: AsRef<OsStr>>(&self, window: &Window, python: S) -> Vec<String> {
+    fn get_missing_packages<S: AsRef<OsStr>>(&self, reporter: &dyn Reporter, python: S) -> Vec<String> {
         if self.use_virtual_environment() {
             return Vec::new();
         }
@@ -542,7 +657,7 @@ impl Runnable for ScriptConfigBundle {
                 serde_json::from_str(output).unwrap_or_default()
             }
             Err(e) => {
-                ccprintln(window, format!("Failed to calculate missing packages: {e}"));
+                ccprintln(reporter, format!("Failed to calculate missing packages: {e}"));
                 Vec::new()
             }
         }
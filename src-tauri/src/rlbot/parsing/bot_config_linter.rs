@@ -0,0 +1,701 @@
+use super::{
+    agent_config_parser::{
+        BotLooksConfig, BotTeamLooksConfig, BOT_CONFIG_LOADOUT_HEADER, BOT_CONFIG_LOADOUT_ORANGE_HEADER,
+        BOT_CONFIG_LOADOUT_PAINT_BLUE_HEADER, BOT_CONFIG_LOADOUT_PAINT_ORANGE_HEADER, CUSTOM_COLOR_COUNT, PAINT_COUNT,
+        TEAM_COLOR_COUNT,
+    },
+    bot_config_bundle::{
+        BOT_CONFIG_DETAILS_HEADER, BOT_CONFIG_MODULE_HEADER, LOADOUT_GENERATOR_FILE_KEY, LOGO_FILE_KEY, LOOKS_CONFIG_KEY,
+        MAXIMUM_TICK_RATE_PREFERENCE_KEY, NAME_KEY, PYTHON_FILE_KEY, REQUIREMENTS_FILE_KEY, REQUIRES_TKINTER,
+        SUPPORTS_EARLY_START_KEY, SUPPORTS_STANDALONE, USE_VIRTUAL_ENVIRONMENT_KEY,
+    },
+    config_diagnostics::{self, DiagnosticKind, Severity},
+    item_catalog::{ItemError, ITEM_CATALOG},
+};
+use crate::{bot_management::cfg_helper::load_cfg, rlbot::agents::base_script::SCRIPT_FILE_KEY};
+use configparser::ini::Ini;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tokio::fs as async_fs;
+
+/// Every key that RLBot reads from the `[Locations]` section
+const LOCATIONS_KEYS: [&str; 12] = [
+    NAME_KEY,
+    PYTHON_FILE_KEY,
+    SCRIPT_FILE_KEY,
+    LOOKS_CONFIG_KEY,
+    LOGO_FILE_KEY,
+    REQUIREMENTS_FILE_KEY,
+    SUPPORTS_STANDALONE,
+    USE_VIRTUAL_ENVIRONMENT_KEY,
+    REQUIRES_TKINTER,
+    SUPPORTS_EARLY_START_KEY,
+    MAXIMUM_TICK_RATE_PREFERENCE_KEY,
+    LOADOUT_GENERATOR_FILE_KEY,
+];
+
+/// The botpack shows logos at 400x400, so anything far from that is probably a mistake
+const LOGO_SIZE_RANGE: (u32, u32) = (64, 1024);
+
+/// A requirement from a requirements file, like `rlbot==1.*` or `numpy >= 1.20; python_version >= "3.7"`
+static REQUIREMENT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(concat!(
+        // The name of the package, with any extras
+        r"^[A-Za-z0-9]([A-Za-z0-9._-]*[A-Za-z0-9])?(\[[A-Za-z0-9._,\s-]*\])?\s*",
+        // Either a link to the package or any number of version specifiers, where versions can't start with an operator
+        r"(@\s*\S+|",
+        r"(===|==|!=|~=|>=|<=|>|<)\s*[^,;\s<>=!~][^,;\s]*",
+        r"(\s*,\s*(===|==|!=|~=|>=|<=|>|<)\s*[^,;\s<>=!~][^,;\s]*)*)?",
+        // Environment markers
+        r"\s*(;.+)?$",
+    ))
+    .unwrap()
+});
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LintKind {
+    /// One of the problems that stops a bot from showing up in the GUI
+    Config(DiagnosticKind),
+    UnknownKey,
+    InvalidItemId,
+    UnknownItem,
+    WrongItemSlot,
+    InvalidColor,
+    BadLogo,
+    BadRequirements,
+    BadDetails,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LintIssue {
+    pub kind: LintKind,
+    /// The section and key that caused the problem, like `Bot Loadout.car_id`
+    pub key: Option<String>,
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+/// Everything that should be fixed in a bot's config before it goes into the botpack
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct LintReport {
+    pub path: String,
+    pub errors: Vec<LintIssue>,
+    pub warnings: Vec<LintIssue>,
+}
+
+impl LintReport {
+    fn push(
+        &mut self,
+        severity: Severity,
+        kind: LintKind,
+        key: Option<String>,
+        message: String,
+        suggestion: Option<String>,
+    ) {
+        let issue = LintIssue {
+            kind,
+            key,
+            message,
+            suggestion,
+        };
+
+        match severity {
+            Severity::Error => self.errors.push(issue),
+            Severity::Warning => self.warnings.push(issue),
+        }
+    }
+}
+
+/// How many single character edits it takes to turn one string into another
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+fn lint_unknown_keys(report: &mut LintReport, conf: &Ini) {
+    let Some(keys) = conf
+        .get_map_ref()
+        .iter()
+        .find(|(section, _)| section.eq_ignore_ascii_case(BOT_CONFIG_MODULE_HEADER))
+        .map(|(_, keys)| keys)
+    else {
+        return;
+    };
+
+    for key in keys.keys().filter(|key| !LOCATIONS_KEYS.contains(&key.as_str())) {
+        let closest = LOCATIONS_KEYS
+            .iter()
+            .map(|known| (edit_distance(key, known), known))
+            .min()
+            .filter(|(distance, _)| *distance <= 2);
+
+        report.push(
+            Severity::Warning,
+            LintKind::UnknownKey,
+            Some(format!("{BOT_CONFIG_MODULE_HEADER}.{key}")),
+            format!("RLBot doesn't use the key `{key}`, so it will be ignored"),
+            closest.map(|(_, known)| format!("Did you mean `{known}`?")),
+        );
+    }
+}
+
+fn lint_team_looks(report: &mut LintReport, looks: &BotTeamLooksConfig, loadout_header: &str, paint_header: &str) {
+    for (key, slot, value) in looks.item_ids() {
+        let e = match ITEM_CATALOG.normalize_id(key, slot, value) {
+            // RLBot only reads IDs, even though the catalog also understands names
+            Ok(id) if !value.trim().is_empty() && value.trim().parse::<u32>().is_err() => {
+                report.push(
+                    Severity::Error,
+                    LintKind::InvalidItemId,
+                    Some(format!("{loadout_header}.{key}")),
+                    format!("`{value}` is the name of an item, but RLBot needs its ID"),
+                    Some(format!(
+                        "Replace `{value}` with {id}, or save the looks with the appearance editor"
+                    )),
+                );
+                continue;
+            }
+            Ok(_) => continue,
+            Err(e) => e,
+        };
+
+        let kind = match e {
+            ItemError::InvalidId { .. } => LintKind::InvalidItemId,
+            ItemError::UnknownItem { .. } => LintKind::UnknownItem,
+            ItemError::WrongSlot { .. } => LintKind::WrongItemSlot,
+        };
+
+        report.push(
+            Severity::Error,
+            kind,
+            Some(format!("{loadout_header}.{key}")),
+            e.to_string(),
+            Some(format!("Pick a {slot:?} with the appearance editor")),
+        );
+    }
+
+    let colors = [
+        ("team_color_id", &looks.team_color_id, TEAM_COLOR_COUNT),
+        ("custom_color_id", &looks.custom_color_id, CUSTOM_COLOR_COUNT),
+    ];
+
+    for (key, value, count) in colors {
+        if !value.trim().is_empty() && value.trim().parse::<u32>().map_or(true, |color| color >= count) {
+            report.push(
+                Severity::Error,
+                LintKind::InvalidColor,
+                Some(format!("{loadout_header}.{key}")),
+                format!("`{value}` isn't a color, which go from 0 to {}", count - 1),
+                None,
+            );
+        }
+    }
+
+    let paints = [
+        ("car_paint_id", &looks.car_paint_id),
+        ("decal_paint_id", &looks.decal_paint_id),
+        ("wheels_paint_id", &looks.wheels_paint_id),
+        ("boost_paint_id", &looks.boost_paint_id),
+        ("antenna_paint_id", &looks.antenna_paint_id),
+        ("hat_paint_id", &looks.hat_paint_id),
+        ("trails_paint_id", &looks.trails_paint_id),
+        ("goal_explosion_paint_id", &looks.goal_explosion_paint_id),
+    ];
+
+    for (key, value) in paints {
+        if !value.trim().is_empty() && value.trim().parse::<u32>().map_or(true, |paint| paint >= PAINT_COUNT) {
+            report.push(
+                Severity::Error,
+                LintKind::InvalidColor,
+                Some(format!("{paint_header}.{key}")),
+                format!("`{value}` isn't a paint, which go from 0 to {}", PAINT_COUNT - 1),
+                None,
+            );
+        }
+    }
+}
+
+/// Read the width and height of a PNG or JPEG image
+fn image_size(bytes: &[u8]) -> Option<(u32, u32)> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        let width = u32::from_be_bytes(bytes.get(16..20)?.try_into().ok()?);
+        let height = u32::from_be_bytes(bytes.get(20..24)?.try_into().ok()?);
+        return Some((width, height));
+    }
+
+    if bytes.starts_with(&[0xFF, 0xD8]) {
+        let mut index = 2;
+        while index + 9 < bytes.len() {
+            if bytes[index] != 0xFF {
+                return None;
+            }
+
+            let marker = bytes[index + 1];
+            let length = usize::from(u16::from_be_bytes([bytes[index + 2], bytes[index + 3]]));
+
+            // Start of frame markers hold the size, except for the ones that mean something else
+            if (0xC0..=0xCF).contains(&marker) && ![0xC4, 0xC8, 0xCC].contains(&marker) {
+                let height = u16::from_be_bytes([bytes[index + 5], bytes[index + 6]]);
+                let width = u16::from_be_bytes([bytes[index + 7], bytes[index + 8]]);
+                return Some((u32::from(width), u32::from(height)));
+            }
+
+            index += 2 + length;
+        }
+    }
+
+    None
+}
+
+async fn lint_logo(report: &mut LintReport, conf: &Ini, config_directory: &Path) {
+    let relative_logo_path = conf
+        .get(BOT_CONFIG_MODULE_HEADER, LOGO_FILE_KEY)
+        .unwrap_or_else(|| String::from("logo.png"));
+    let logo_path = config_directory.join(relative_logo_path.trim());
+    let key = Some(format!("{BOT_CONFIG_MODULE_HEADER}.{LOGO_FILE_KEY}"));
+
+    // A missing logo is already a diagnostic if it was set, and logos are optional otherwise
+    let Ok(bytes) = async_fs::read(&logo_path).await else {
+        return;
+    };
+
+    if imghdr::from_bytes(&bytes).is_none() {
+        report.push(
+            Severity::Error,
+            LintKind::BadLogo,
+            key,
+            format!("The logo {} isn't an image", logo_path.display()),
+            Some("Save the logo as a PNG".to_owned()),
+        );
+        return;
+    }
+
+    let Some((width, height)) = image_size(&bytes) else {
+        report.push(
+            Severity::Warning,
+            LintKind::BadLogo,
+            key,
+            format!("The size of the logo {} couldn't be read", logo_path.display()),
+            Some("Save the logo as a PNG".to_owned()),
+        );
+        return;
+    };
+
+    let (min, max) = LOGO_SIZE_RANGE;
+    if width < min || height < min || width > max || height > max {
+        report.push(
+            Severity::Warning,
+            LintKind::BadLogo,
+            key.clone(),
+            format!("The logo is {width}x{height}, which is outside of {min}x{min} to {max}x{max}"),
+            Some("Resize the logo to 400x400".to_owned()),
+        );
+    }
+
+    if width != height {
+        report.push(
+            Severity::Warning,
+            LintKind::BadLogo,
+            key,
+            format!("The logo is {width}x{height}, so it will be stretched into a square"),
+            Some("Crop the logo to a square".to_owned()),
+        );
+    }
+}
+
+async fn lint_requirements(report: &mut LintReport, conf: &Ini, config_directory: &Path) {
+    let Some(requirements_file) = conf.get(BOT_CONFIG_MODULE_HEADER, REQUIREMENTS_FILE_KEY) else {
+        return;
+    };
+
+    // A missing requirements file is already a diagnostic
+    let Ok(requirements) = async_fs::read_to_string(config_directory.join(requirements_file.trim())).await else {
+        return;
+    };
+
+    let key = format!("{BOT_CONFIG_MODULE_HEADER}.{REQUIREMENTS_FILE_KEY}");
+
+    for (number, line) in requirements.lines().enumerate() {
+        let requirement = line.split(" #").next().unwrap_or_default().trim();
+
+        // Options like `-r other.txt` or `--index-url` and links to packages are passed through to pip
+        if requirement.is_empty()
+            || requirement.starts_with('#')
+            || requirement.starts_with('-')
+            || requirement.contains("://")
+        {
+            continue;
+        }
+
+        if !REQUIREMENT.is_match(requirement) {
+            report.push(
+                Severity::Error,
+                LintKind::BadRequirements,
+                Some(key.clone()),
+                format!(
+                    "Line {} of {requirements_file}, `{requirement}`, isn't a valid requirement",
+                    number + 1
+                ),
+                Some("Requirements look like `package`, `package==1.0` or `package>=1.0,<2`".to_owned()),
+            );
+        }
+    }
+}
+
+fn lint_details(report: &mut LintReport, conf: &Ini) {
+    let detail = |key: &str| {
+        conf.get(BOT_CONFIG_DETAILS_HEADER, key)
+            .map(|value| value.trim().to_owned())
+            .filter(|value| !value.is_empty())
+    };
+    let key = |key: &str| Some(format!("{BOT_CONFIG_DETAILS_HEADER}.{key}"));
+
+    for required in ["developer", "description", "language"] {
+        if detail(required).is_none() {
+            report.push(
+                Severity::Warning,
+                LintKind::BadDetails,
+                key(required),
+                format!("`{required}` is empty, so it won't be shown in the bot's info"),
+                Some(format!("Add `{required}` to the [{BOT_CONFIG_DETAILS_HEADER}] section")),
+            );
+        }
+    }
+
+    if let Some(github) = detail("github") {
+        if !github.starts_with("https://github.com/") || github.contains(char::is_whitespace) {
+            report.push(
+                Severity::Warning,
+                LintKind::BadDetails,
+                key("github"),
+                format!("`{github}` isn't a link to GitHub"),
+                Some("Link to the repository, like https://github.com/RLBot/RLBotPythonExample".to_owned()),
+            );
+        }
+    }
+
+    if let Some(tags) = detail("tags") {
+        // Tags are split on ", " when the config is read, so anything else ends up in one big tag
+        let split = tags.split(", ").collect::<Vec<_>>();
+
+        if split
+            .iter()
+            .any(|tag| tag.contains(',') || tag.trim().is_empty() || *tag != tag.trim())
+        {
+            report.push(
+                Severity::Warning,
+                LintKind::BadDetails,
+                key("tags"),
+                format!("`{tags}` isn't a list of tags separated by \", \""),
+                Some("Write the tags like `1v1, teamplay, goalie`".to_owned()),
+            );
+        }
+
+        let mut seen = Vec::new();
+        for tag in split {
+            if seen.contains(&tag) {
+                report.push(
+                    Severity::Warning,
+                    LintKind::BadDetails,
+                    key("tags"),
+                    format!("The tag `{tag}` is in the list more than once"),
+                    None,
+                );
+            }
+            seen.push(tag);
+        }
+    }
+}
+
+/// Check a bot's config for everything that should be fixed before it goes into the botpack
+///
+/// # Arguments
+///
+/// * `path` - The path to the bot's config file
+pub async fn lint_bot_config(path: &Path) -> LintReport {
+    let mut report = LintReport {
+        path: path.display().to_string(),
+        ..Default::default()
+    };
+
+    for diagnostic in config_diagnostics::diagnose(path) {
+        report.push(
+            diagnostic.severity,
+            LintKind::Config(diagnostic.kind),
+            diagnostic.key,
+            diagnostic.message,
+            Some(diagnostic.suggestion),
+        );
+    }
+
+    let Ok(conf) = load_cfg(path).await else {
+        // The diagnostics already say why the file couldn't be read
+        return report;
+    };
+
+    let config_directory = path.parent().unwrap_or_else(|| Path::new(""));
+
+    lint_unknown_keys(&mut report, &conf);
+    lint_logo(&mut report, &conf, config_directory).await;
+    lint_requirements(&mut report, &conf, config_directory).await;
+    lint_details(&mut report, &conf);
+
+    if let Some(looks_config) = conf.get(BOT_CONFIG_MODULE_HEADER, LOOKS_CONFIG_KEY) {
+        let looks_path = config_directory.join(looks_config.trim());
+
+        if let Ok(looks) = BotLooksConfig::from_path(&looks_path.to_string_lossy()).await {
+            lint_team_looks(
+                &mut report,
+                &looks.blue,
+                BOT_CONFIG_LOADOUT_HEADER,
+                BOT_CONFIG_LOADOUT_PAINT_BLUE_HEADER,
+            );
+            lint_team_looks(
+                &mut report,
+                &looks.orange,
+                BOT_CONFIG_LOADOUT_ORANGE_HEADER,
+                BOT_CONFIG_LOADOUT_PAINT_ORANGE_HEADER,
+            );
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tauri::async_runtime::block_on;
+    use tempfile::TempDir;
+
+    const VALID_CONFIG: &str = "[Locations]
+name = Test Bot
+python_file = bot.py
+looks_config = appearance.cfg
+requirements_file = requirements.txt
+maximum_tick_rate_preference = 120
+
+[Details]
+developer = Me
+description = A bot for testing
+language = python
+github = https://github.com/RLBot/RLBotPythonExample
+tags = 1v1, teamplay
+";
+
+    const VALID_LOOKS: &str = "[Bot Loadout]
+team_color_id = 1
+custom_color_id = 0
+car_id = 23
+decal_id = 302
+
+[Bot Loadout Orange]
+team_color_id = 1
+custom_color_id = 0
+car_id = 23
+decal_id = 0
+
+[Bot Paint Blue]
+car_paint_id = 13
+";
+
+    /// The start of a PNG, which is all that's read to get its size
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        bytes.extend(width.to_be_bytes());
+        bytes.extend(height.to_be_bytes());
+        bytes.extend([8, 6, 0, 0, 0]);
+        bytes
+    }
+
+    /// Write a bot that passes every lint to a new folder, with `changes` made to its files
+    fn lint_bot(changes: &[(&str, &[u8])]) -> LintReport {
+        let folder = TempDir::new().unwrap();
+        let logo = png(400, 400);
+        let files: [(&str, &[u8]); 5] = [
+            ("bot.cfg", VALID_CONFIG.as_bytes()),
+            ("bot.py", b"from rlbot.agents.base_agent import BaseAgent\n"),
+            ("appearance.cfg", VALID_LOOKS.as_bytes()),
+            ("requirements.txt", b"rlbot==1.*\nnumpy >= 1.20; python_version >= \"3.7\"\n"),
+            ("logo.png", &logo),
+        ];
+
+        for (name, contents) in files.iter().chain(changes) {
+            fs::write(folder.path().join(name), contents).unwrap();
+        }
+
+        block_on(lint_bot_config(&folder.path().join("bot.cfg")))
+    }
+
+    fn kinds(issues: &[LintIssue]) -> Vec<LintKind> {
+        issues.iter().map(|issue| issue.kind).collect()
+    }
+
+    fn ini(text: &str) -> Ini {
+        let mut conf = Ini::new();
+        conf.read(text.to_owned()).unwrap();
+        conf
+    }
+
+    #[test]
+    fn valid_bots_have_no_issues() {
+        let report = lint_bot(&[]);
+
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+    }
+
+    #[test]
+    fn config_diagnostics_are_included() {
+        let config = VALID_CONFIG.replace("python_file = bot.py", "python_file = missing.py");
+        let report = lint_bot(&[("bot.cfg", config.as_bytes())]);
+
+        assert_eq!(kinds(&report.errors), [LintKind::Config(DiagnosticKind::MissingPythonFile)]);
+    }
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("python_file", "python_file"), 0);
+        assert_eq!(edit_distance("pyhton_file", "python_file"), 2);
+        assert_eq!(edit_distance("logo", "logo_file"), 5);
+        assert_eq!(edit_distance("", "name"), 4);
+    }
+
+    #[test]
+    fn unknown_keys_suggest_the_closest_key() {
+        let mut report = LintReport::default();
+        lint_unknown_keys(
+            &mut report,
+            &ini("[Locations]\nname = Bot\npyton_file = bot.py\nauthor = Me\n"),
+        );
+
+        assert_eq!(kinds(&report.warnings), [LintKind::UnknownKey, LintKind::UnknownKey]);
+
+        let suggestion = |key: &str| {
+            report
+                .warnings
+                .iter()
+                .find(|issue| issue.key.as_deref() == Some(key))
+                .unwrap()
+                .suggestion
+                .clone()
+        };
+        assert_eq!(
+            suggestion("Locations.pyton_file").as_deref(),
+            Some("Did you mean `python_file`?")
+        );
+        assert_eq!(suggestion("Locations.author"), None);
+    }
+
+    #[test]
+    fn requirements_are_checked() {
+        for valid in [
+            "rlbot",
+            "rlbot==1.*",
+            "numpy >= 1.20, < 2",
+            "torch[cuda]~=2.0",
+            "rlutilities @ https://example.com/rlutilities.whl",
+            "numpy; python_version >= \"3.7\"",
+        ] {
+            assert!(REQUIREMENT.is_match(valid), "{valid} should be valid");
+        }
+
+        for invalid in ["rlbot=1.0", "==1.0", "numpy >=", "-rlbot"] {
+            assert!(!REQUIREMENT.is_match(invalid), "{invalid} should be invalid");
+        }
+
+        let report = lint_bot(&[("requirements.txt", b"# Comment\n-r other.txt\nrlbot=1.0\n")]);
+        assert_eq!(kinds(&report.errors), [LintKind::BadRequirements]);
+        assert!(report.errors[0].message.starts_with("Line 3"));
+    }
+
+    #[test]
+    fn image_sizes() {
+        assert_eq!(image_size(&png(400, 300)), Some((400, 300)));
+
+        // A JPEG with an APP0 segment before the start of frame
+        let jpeg = [
+            0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, 0xFF, 0xC0, 0x00, 0x11, 0x08, 0x01, 0x2C, 0x00, 0xC8, 0x03,
+        ];
+        assert_eq!(image_size(&jpeg), Some((200, 300)));
+
+        assert_eq!(image_size(b"GIF89a"), None);
+        assert_eq!(image_size(&png(1, 1)[..20]), None);
+    }
+
+    #[test]
+    fn logos_are_checked() {
+        let report = lint_bot(&[("logo.png", &png(2000, 1000))]);
+        assert_eq!(kinds(&report.warnings), [LintKind::BadLogo, LintKind::BadLogo]);
+
+        let report = lint_bot(&[("logo.png", b"not an image")]);
+        assert_eq!(kinds(&report.errors), [LintKind::BadLogo]);
+    }
+
+    #[test]
+    fn items_and_paints_are_checked() {
+        let looks = BotTeamLooksConfig {
+            car_id: "Octane".to_owned(),
+            decal_id: "23".to_owned(),
+            wheels_id: "wheels".to_owned(),
+            boost_id: "999999".to_owned(),
+            team_color_id: "70".to_owned(),
+            car_paint_id: "14".to_owned(),
+            ..Default::default()
+        };
+
+        let mut report = LintReport::default();
+        lint_team_looks(&mut report, &looks, "Bot Loadout", "Bot Paint Blue");
+
+        let issues = report
+            .errors
+            .iter()
+            .map(|issue| (issue.kind, issue.key.as_deref().unwrap_or_default()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            issues,
+            [
+                (LintKind::InvalidItemId, "Bot Loadout.car_id"),
+                (LintKind::WrongItemSlot, "Bot Loadout.decal_id"),
+                (LintKind::InvalidItemId, "Bot Loadout.wheels_id"),
+                (LintKind::UnknownItem, "Bot Loadout.boost_id"),
+                (LintKind::InvalidColor, "Bot Loadout.team_color_id"),
+                (LintKind::InvalidColor, "Bot Paint Blue.car_paint_id"),
+            ]
+        );
+        assert!(report.errors[0].suggestion.as_deref().unwrap().contains("with 23"));
+    }
+
+    #[test]
+    fn details_are_checked() {
+        let mut report = LintReport::default();
+        lint_details(
+            &mut report,
+            &ini(
+                "[Details]\ndescription = A bot\nlanguage = rust\ngithub = github.com/me/bot\ntags = 1v1,goalie, 1v1, 1v1\n",
+            ),
+        );
+
+        let keys = report
+            .warnings
+            .iter()
+            .map(|issue| issue.key.as_deref().unwrap_or_default())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, ["Details.developer", "Details.github", "Details.tags", "Details.tags"]);
+        assert!(report.warnings.iter().all(|issue| issue.kind == LintKind::BadDetails));
+    }
+}
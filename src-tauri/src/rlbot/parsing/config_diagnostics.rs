@@ -0,0 +1,383 @@
+use super::bot_config_bundle::{
+    is_executable, resolve_executable, BOT_CONFIG_MODULE_HEADER, BOT_CONFIG_PARAMS_HEADER, EXECUTABLE_PATH_KEY,
+    LOGO_FILE_KEY, LOOKS_CONFIG_KEY, MAXIMUM_TICK_RATE_PREFERENCE_KEY, NAME_KEY, PYTHON_FILE_KEY, REQUIREMENTS_FILE_KEY,
+    REQUIRES_TKINTER, SUPPORTS_EARLY_START_KEY, SUPPORTS_STANDALONE, USE_VIRTUAL_ENVIRONMENT_KEY,
+};
+use crate::{bot_management::cfg_helper::load_cfg_sync, rlbot::agents::base_script::SCRIPT_FILE_KEY};
+use configparser::ini::Ini;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// The keys in the `Locations` section that have to be a boolean if they're set
+const BOOLEAN_KEYS: [&str; 4] = [
+    SUPPORTS_STANDALONE,
+    USE_VIRTUAL_ENVIRONMENT_KEY,
+    REQUIRES_TKINTER,
+    SUPPORTS_EARLY_START_KEY,
+];
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    /// The bot or script won't show up
+    Error,
+    /// The bot or script shows up, but something about it won't work right
+    Warning,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    UnparseableIni,
+    MissingName,
+    MissingRunnableFile,
+    MissingPythonFile,
+    MissingScriptFile,
+    MissingExecutable,
+    NotExecutable,
+    MissingLooksConfig,
+    MissingLogo,
+    BadRequirementsPath,
+    InvalidBoolean,
+    InvalidTickRate,
+}
+
+/// Something wrong with a config file in the bot folders, and how to fix it
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ConfigDiagnostic {
+    pub path: String,
+    pub kind: DiagnosticKind,
+    pub severity: Severity,
+    /// The section and key that caused the problem, like `Locations.looks_config`
+    pub key: Option<String>,
+    pub message: String,
+    pub suggestion: String,
+}
+
+impl ConfigDiagnostic {
+    fn new(path: &Path, kind: DiagnosticKind, key: Option<String>, message: String, suggestion: String) -> Self {
+        let severity = match kind {
+            DiagnosticKind::MissingLogo
+            | DiagnosticKind::BadRequirementsPath
+            | DiagnosticKind::InvalidBoolean
+            | DiagnosticKind::InvalidTickRate => Severity::Warning,
+            _ => Severity::Error,
+        };
+
+        Self {
+            path: path.display().to_string(),
+            kind,
+            severity,
+            key,
+            message,
+            suggestion,
+        }
+    }
+}
+
+fn locations_key(key: &str) -> Option<String> {
+    Some(format!("{BOT_CONFIG_MODULE_HEADER}.{key}"))
+}
+
+/// Check that a file referenced by a key in the `Locations` section exists, relative to the config file
+fn check_file(conf: &Ini, config_directory: &Path, key: &str) -> Option<Result<(), (String, PathBuf)>> {
+    let value = conf.get(BOT_CONFIG_MODULE_HEADER, key)?;
+    let file = config_directory.join(value.trim());
+
+    Some(if file.is_file() { Ok(()) } else { Err((value, file)) })
+}
+
+/// Find everything that's wrong with a bot or script config file
+///
+/// Files without a `Locations` section, like looks configs, aren't bots or scripts and have no diagnostics
+///
+/// # Arguments
+///
+/// * `path` - The path to the config file
+pub fn diagnose(path: &Path) -> Vec<ConfigDiagnostic> {
+    let conf = match load_cfg_sync(path) {
+        Ok(conf) => conf,
+        Err(e) => {
+            return vec![ConfigDiagnostic::new(
+                path,
+                DiagnosticKind::UnparseableIni,
+                None,
+                e.to_string(),
+                "Make sure that the file is valid INI, with every key inside of a [Section] and no duplicate keys"
+                    .to_owned(),
+            )]
+        }
+    };
+
+    let is_runnable_config = conf
+        .sections()
+        .iter()
+        .any(|section| section.eq_ignore_ascii_case(BOT_CONFIG_MODULE_HEADER));
+    if !is_runnable_config {
+        return Vec::new();
+    }
+
+    let config_directory = path.parent().unwrap_or_else(|| Path::new(""));
+    let mut diagnostics = Vec::new();
+
+    if conf
+        .get(BOT_CONFIG_MODULE_HEADER, NAME_KEY)
+        .map_or(true, |name| name.trim().is_empty())
+    {
+        diagnostics.push(ConfigDiagnostic::new(
+            path,
+            DiagnosticKind::MissingName,
+            locations_key(NAME_KEY),
+            "No name was set".to_owned(),
+            format!("Add `{NAME_KEY} = <your bot's name>` to the [{BOT_CONFIG_MODULE_HEADER}] section"),
+        ));
+    }
+
+    let python_file = check_file(&conf, config_directory, PYTHON_FILE_KEY);
+    let script_file = check_file(&conf, config_directory, SCRIPT_FILE_KEY);
+
+    // Scripts can have bot parameters too, but they're never executable bots
+    let executable = conf
+        .get(BOT_CONFIG_PARAMS_HEADER, EXECUTABLE_PATH_KEY)
+        .filter(|_| script_file.is_none())
+        .map(|value| (resolve_executable(config_directory, &value), value));
+
+    if python_file.is_none() && script_file.is_none() && executable.is_none() {
+        diagnostics.push(ConfigDiagnostic::new(
+            path,
+            DiagnosticKind::MissingRunnableFile,
+            None,
+            format!("None of `{PYTHON_FILE_KEY}`, `{SCRIPT_FILE_KEY}` or an executable `{EXECUTABLE_PATH_KEY}` were set"),
+            format!(
+                "Add `{PYTHON_FILE_KEY}`, `{EXECUTABLE_PATH_KEY}` in [{BOT_CONFIG_PARAMS_HEADER}], or `{SCRIPT_FILE_KEY}`"
+            ),
+        ));
+    }
+
+    // A broken python file only stops the bot from showing up if there's no executable to fall back to
+    if let (Some(Err((value, file))), None) = (&python_file, &executable) {
+        diagnostics.push(ConfigDiagnostic::new(
+            path,
+            DiagnosticKind::MissingPythonFile,
+            locations_key(PYTHON_FILE_KEY),
+            format!("The python file {} doesn't exist", file.display()),
+            format!("Check that `{value}` is relative to the folder that the config file is in"),
+        ));
+    }
+
+    // The executable is only used if there's no working python file
+    if let (Some((file, value)), None | Some(Err(_))) = (&executable, &python_file) {
+        let key = Some(format!("{BOT_CONFIG_PARAMS_HEADER}.{EXECUTABLE_PATH_KEY}"));
+
+        if !file.is_file() {
+            diagnostics.push(ConfigDiagnostic::new(
+                path,
+                DiagnosticKind::MissingExecutable,
+                key,
+                format!("The executable {} doesn't exist", file.display()),
+                format!("Build the bot, or check that `{value}` is relative to the folder that the config file is in"),
+            ));
+        } else if !is_executable(file) {
+            diagnostics.push(ConfigDiagnostic::new(
+                path,
+                DiagnosticKind::NotExecutable,
+                key,
+                format!("The executable {} can't be run on this OS", file.display()),
+                if cfg!(windows) {
+                    "Point to an .exe, .bat or .cmd file that was built for Windows".to_owned()
+                } else {
+                    format!("Build the bot for this OS, then run `chmod +x {}`", file.display())
+                },
+            ));
+        }
+    }
+
+    if let Some(Err((value, file))) = &script_file {
+        diagnostics.push(ConfigDiagnostic::new(
+            path,
+            DiagnosticKind::MissingScriptFile,
+            locations_key(SCRIPT_FILE_KEY),
+            format!("The script file {} doesn't exist", file.display()),
+            format!("Check that `{value}` is relative to the folder that the config file is in"),
+        ));
+    }
+
+    // Only bots need a looks config
+    if python_file.is_some() || executable.is_some() {
+        match check_file(&conf, config_directory, LOOKS_CONFIG_KEY) {
+            None => diagnostics.push(ConfigDiagnostic::new(
+                path,
+                DiagnosticKind::MissingLooksConfig,
+                locations_key(LOOKS_CONFIG_KEY),
+                "No looks config was set".to_owned(),
+                format!("Add `{LOOKS_CONFIG_KEY} = appearance.cfg` and create it with the loadout editor"),
+            )),
+            Some(Err((value, file))) => diagnostics.push(ConfigDiagnostic::new(
+                path,
+                DiagnosticKind::MissingLooksConfig,
+                locations_key(LOOKS_CONFIG_KEY),
+                format!("The looks config {} doesn't exist", file.display()),
+                format!("Check that `{value}` is relative to the folder that the config file is in"),
+            )),
+            Some(Ok(())) => {}
+        }
+    }
+
+    if let Some(Err((value, file))) = check_file(&conf, config_directory, LOGO_FILE_KEY) {
+        diagnostics.push(ConfigDiagnostic::new(
+            path,
+            DiagnosticKind::MissingLogo,
+            locations_key(LOGO_FILE_KEY),
+            format!("The logo {} doesn't exist", file.display()),
+            format!("Fix the path `{value}`, or remove `{LOGO_FILE_KEY}` to use logo.png next to the config file"),
+        ));
+    }
+
+    if let Some(Err((value, file))) = check_file(&conf, config_directory, REQUIREMENTS_FILE_KEY) {
+        diagnostics.push(ConfigDiagnostic::new(
+            path,
+            DiagnosticKind::BadRequirementsPath,
+            locations_key(REQUIREMENTS_FILE_KEY),
+            format!(
+                "The requirements file {} doesn't exist, so missing packages can't be found",
+                file.display()
+            ),
+            format!("Fix the path `{value}`, or remove `{REQUIREMENTS_FILE_KEY}` if there are no requirements"),
+        ));
+    }
+
+    for key in BOOLEAN_KEYS {
+        if let Err(e) = conf.getboolcoerce(BOT_CONFIG_MODULE_HEADER, key) {
+            diagnostics.push(ConfigDiagnostic::new(
+                path,
+                DiagnosticKind::InvalidBoolean,
+                locations_key(key),
+                e,
+                format!("Set `{key}` to either True or False"),
+            ));
+        }
+    }
+
+    if let Some(rate) = conf.get(BOT_CONFIG_MODULE_HEADER, MAXIMUM_TICK_RATE_PREFERENCE_KEY) {
+        if rate.trim().parse::<u32>().map_or(true, |rate| rate == 0) {
+            diagnostics.push(ConfigDiagnostic::new(
+                path,
+                DiagnosticKind::InvalidTickRate,
+                locations_key(MAXIMUM_TICK_RATE_PREFERENCE_KEY),
+                format!("`{rate}` isn't a tick rate, so the bot's preference is ignored"),
+                format!("Set `{MAXIMUM_TICK_RATE_PREFERENCE_KEY}` to a number of ticks per second, like 120"),
+            ));
+        }
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const BOT_CONFIG: &str = "[Locations]
+name = Test Bot
+python_file = bot.py
+looks_config = appearance.cfg
+";
+
+    /// Write the files to a new folder, then diagnose the first one
+    fn diagnose_files(files: &[(&str, &str)]) -> Vec<ConfigDiagnostic> {
+        let folder = TempDir::new().unwrap();
+        for (name, contents) in files {
+            fs::write(folder.path().join(name), contents).unwrap();
+        }
+
+        diagnose(&folder.path().join(files[0].0))
+    }
+
+    fn kinds(diagnostics: &[ConfigDiagnostic]) -> Vec<(DiagnosticKind, Severity)> {
+        diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.kind, diagnostic.severity))
+            .collect()
+    }
+
+    #[test]
+    fn valid_bots_have_no_diagnostics() {
+        let diagnostics = diagnose_files(&[("bot.cfg", BOT_CONFIG), ("bot.py", ""), ("appearance.cfg", "")]);
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+
+    #[test]
+    fn files_that_arent_bots_have_no_diagnostics() {
+        let diagnostics = diagnose_files(&[("appearance.cfg", "[Bot Loadout]\ncar_id = 23\n")]);
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+
+    #[test]
+    fn unparseable_ini() {
+        let diagnostics = diagnose_files(&[("bot.cfg", "[Locations\nname = Test Bot\n")]);
+        assert_eq!(kinds(&diagnostics), [(DiagnosticKind::UnparseableIni, Severity::Error)]);
+        assert!(diagnostics[0].key.is_none());
+    }
+
+    #[test]
+    fn missing_runnable() {
+        let diagnostics = diagnose_files(&[("bot.cfg", "[Locations]\nname = Test Bot\n")]);
+        assert_eq!(kinds(&diagnostics), [(DiagnosticKind::MissingRunnableFile, Severity::Error)]);
+    }
+
+    #[test]
+    fn missing_python_file() {
+        let diagnostics = diagnose_files(&[("bot.cfg", BOT_CONFIG), ("appearance.cfg", "")]);
+        assert_eq!(kinds(&diagnostics), [(DiagnosticKind::MissingPythonFile, Severity::Error)]);
+        assert_eq!(diagnostics[0].key.as_deref(), Some("Locations.python_file"));
+    }
+
+    #[test]
+    fn missing_looks_config() {
+        let diagnostics = diagnose_files(&[("bot.cfg", BOT_CONFIG), ("bot.py", "")]);
+        assert_eq!(kinds(&diagnostics), [(DiagnosticKind::MissingLooksConfig, Severity::Error)]);
+        assert_eq!(diagnostics[0].key.as_deref(), Some("Locations.looks_config"));
+
+        // Scripts don't have a looks config, so they don't need one
+        let diagnostics = diagnose_files(&[
+            ("script.cfg", "[Locations]\nname = Test Script\nscript_file = script.py\n"),
+            ("script.py", ""),
+        ]);
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+
+    #[test]
+    fn bad_tick_rate() {
+        for rate in ["fast", "0", "-60"] {
+            let config = format!("{BOT_CONFIG}maximum_tick_rate_preference = {rate}\n");
+            let diagnostics = diagnose_files(&[("bot.cfg", &config), ("bot.py", ""), ("appearance.cfg", "")]);
+
+            assert_eq!(
+                kinds(&diagnostics),
+                [(DiagnosticKind::InvalidTickRate, Severity::Warning)],
+                "{rate}"
+            );
+            assert_eq!(diagnostics[0].key.as_deref(), Some("Locations.maximum_tick_rate_preference"));
+        }
+    }
+
+    #[test]
+    fn warnings_dont_hide_errors() {
+        let config = "[Locations]
+python_file = bot.py
+looks_config = appearance.cfg
+logo_file = missing.png
+supports_early_start = maybe
+";
+        let diagnostics = diagnose_files(&[("bot.cfg", config), ("bot.py", ""), ("appearance.cfg", "")]);
+
+        assert_eq!(
+            kinds(&diagnostics),
+            [
+                (DiagnosticKind::MissingName, Severity::Error),
+                (DiagnosticKind::MissingLogo, Severity::Warning),
+                (DiagnosticKind::InvalidBoolean, Severity::Warning),
+            ]
+        );
+    }
+}
@@ -0,0 +1,304 @@
+use crate::impl_serialize_from_display;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, str::FromStr};
+use thiserror::Error;
+
+/// The items that the appearance editor shows, shipped with the GUI
+static ITEMS_CSV: &str = include_str!("../../../../assets/csv/items.csv");
+
+/// How many items `search` returns at most
+const SEARCH_LIMIT: usize = 50;
+
+/// Every item that can be put on a bot, loaded from `items.csv` the first time that it's used
+pub static ITEM_CATALOG: Lazy<ItemCatalog> = Lazy::new(|| ItemCatalog::parse(ITEMS_CSV));
+
+/// The slots in a looks config that hold items, named after their categories in `items.csv`
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ItemSlot {
+    Body,
+    /// Decals
+    Skin,
+    Wheels,
+    Boost,
+    Antenna,
+    /// Toppers
+    Hat,
+    PaintFinish,
+    EngineAudio,
+    SupersonicTrail,
+    GoalExplosion,
+}
+
+impl FromStr for ItemSlot {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "Body" => Self::Body,
+            "Skin" => Self::Skin,
+            "Wheels" => Self::Wheels,
+            "Boost" => Self::Boost,
+            "Antenna" => Self::Antenna,
+            "Hat" => Self::Hat,
+            "PaintFinish" => Self::PaintFinish,
+            "EngineAudio" => Self::EngineAudio,
+            "SupersonicTrail" => Self::SupersonicTrail,
+            "GoalExplosion" => Self::GoalExplosion,
+            _ => return Err(()),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Item {
+    pub id: u32,
+    pub slot: ItemSlot,
+    /// The name of the item in the game's files, like `Product_TA ProductsDB.Products.Body_Octane`
+    pub product: String,
+    pub name: String,
+}
+
+#[derive(Debug, Error)]
+pub enum ItemError {
+    #[error("{key} is {value}, which isn't an item ID")]
+    InvalidId { key: String, value: String },
+    #[error("{key} is {id}, but there's no item with that ID")]
+    UnknownItem { key: String, id: u32 },
+    #[error("{key} is {id}, which is a {slot:?} instead of a {expected:?}")]
+    WrongSlot {
+        key: String,
+        id: u32,
+        slot: ItemSlot,
+        expected: ItemSlot,
+    },
+}
+
+impl_serialize_from_display!(ItemError);
+
+/// The items from `items.csv`, indexed by ID, slot and name
+#[derive(Debug, Default)]
+pub struct ItemCatalog {
+    items: Vec<Item>,
+    by_id: HashMap<u32, usize>,
+    by_slot: HashMap<ItemSlot, Vec<usize>>,
+    /// Lowercase names, since the same name can be used by items in different slots
+    by_name: HashMap<(ItemSlot, String), usize>,
+}
+
+impl ItemCatalog {
+    /// Parse the lines of `items.csv`, which are `id,category,product,name`
+    ///
+    /// Items from categories that can't be put on a bot, like banners, are skipped
+    fn parse(csv: &str) -> Self {
+        let mut catalog = Self::default();
+
+        for line in csv.lines() {
+            // Names can have commas in them, so they're everything after the third comma
+            let mut columns = line.splitn(4, ',');
+            let (Some(id), Some(category), Some(product), Some(name)) =
+                (columns.next(), columns.next(), columns.next(), columns.next())
+            else {
+                continue;
+            };
+
+            let (Ok(id), Ok(slot)) = (id.trim().parse(), category.trim().parse()) else {
+                continue;
+            };
+
+            let index = catalog.items.len();
+            catalog.by_id.insert(id, index);
+            catalog.by_slot.entry(slot).or_default().push(index);
+            catalog.by_name.entry((slot, name.trim().to_lowercase())).or_insert(index);
+            catalog.items.push(Item {
+                id,
+                slot,
+                product: product.trim().to_owned(),
+                name: name.trim().to_owned(),
+            });
+        }
+
+        catalog
+    }
+
+    pub fn get(&self, id: u32) -> Option<&Item> {
+        self.by_id.get(&id).map(|&index| &self.items[index])
+    }
+
+    /// Find an item in a slot by its name, ignoring case
+    pub fn find_by_name(&self, slot: ItemSlot, name: &str) -> Option<&Item> {
+        self.by_name
+            .get(&(slot, name.trim().to_lowercase()))
+            .map(|&index| &self.items[index])
+    }
+
+    /// Every item in a slot, in the same order as `items.csv`
+    pub fn in_slot(&self, slot: ItemSlot) -> impl Iterator<Item = &Item> {
+        self.by_slot.get(&slot).into_iter().flatten().map(|&index| &self.items[index])
+    }
+
+    /// Search the items in a slot by name or ID
+    ///
+    /// Exact matches come first, then names that start with the query, then names that contain it
+    ///
+    /// # Arguments
+    ///
+    /// * `slot` - The slot to search in
+    /// * `query` - Part of an item's name, ignoring case, or its ID
+    pub fn search(&self, slot: ItemSlot, query: &str) -> Vec<&Item> {
+        let query = query.trim().to_lowercase();
+        let id = query.parse::<u32>().ok();
+
+        let mut matches = self
+            .in_slot(slot)
+            .filter_map(|item| {
+                let name = item.name.to_lowercase();
+                let rank = if Some(item.id) == id || name == query {
+                    0
+                } else if name.starts_with(&query) {
+                    1
+                } else if name.contains(&query) {
+                    2
+                } else {
+                    return None;
+                };
+
+                Some((rank, item))
+            })
+            .collect::<Vec<_>>();
+
+        matches.sort_by(|(a_rank, a), (b_rank, b)| a_rank.cmp(b_rank).then_with(|| a.name.cmp(&b.name)));
+        matches.into_iter().take(SEARCH_LIMIT).map(|(_, item)| item).collect()
+    }
+
+    /// Check that the value of an item key in a looks config is an item from the right slot
+    ///
+    /// Returns the ID written the way that RLBot expects it, where `0` means that the default item is used
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key in the looks config, used in the errors
+    /// * `expected` - The slot that `key` holds items for
+    /// * `value` - The value of `key`
+    pub fn normalize_id(&self, key: &str, expected: ItemSlot, value: &str) -> Result<String, ItemError> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Ok("0".to_owned());
+        }
+
+        // Hand written configs sometimes use the name of the item instead of its ID
+        let Ok(id) = value.parse::<u32>() else {
+            return self
+                .find_by_name(expected, value)
+                .map(|item| item.id.to_string())
+                .ok_or_else(|| ItemError::InvalidId {
+                    key: key.to_owned(),
+                    value: value.to_owned(),
+                });
+        };
+
+        if id == 0 {
+            return Ok(id.to_string());
+        }
+
+        match self.get(id) {
+            None => Err(ItemError::UnknownItem { key: key.to_owned(), id }),
+            Some(item) if item.slot != expected => Err(ItemError::WrongSlot {
+                key: key.to_owned(),
+                id,
+                slot: item.slot,
+                expected,
+            }),
+            Some(_) => Ok(id.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OCTANE: u32 = 23;
+    const OCTANE_FLAMES: u32 = 302;
+
+    #[test]
+    fn known_items_are_in_the_catalog() {
+        let octane = ITEM_CATALOG.get(OCTANE).unwrap();
+        assert_eq!((octane.slot, octane.name.as_str()), (ItemSlot::Body, "Octane"));
+
+        let flames = ITEM_CATALOG.get(OCTANE_FLAMES).unwrap();
+        assert_eq!((flames.slot, flames.name.as_str()), (ItemSlot::Skin, "Octane: Flames"));
+    }
+
+    #[test]
+    fn items_are_found_by_name_ignoring_case() {
+        assert_eq!(
+            ITEM_CATALOG.find_by_name(ItemSlot::Body, " octane ").map(|item| item.id),
+            Some(OCTANE)
+        );
+        assert_eq!(
+            ITEM_CATALOG
+                .find_by_name(ItemSlot::Skin, "OCTANE: FLAMES")
+                .map(|item| item.id),
+            Some(OCTANE_FLAMES)
+        );
+        assert_eq!(ITEM_CATALOG.find_by_name(ItemSlot::Skin, "Octane"), None);
+    }
+
+    #[test]
+    fn slots_only_have_their_own_items() {
+        for slot in [ItemSlot::Body, ItemSlot::Skin] {
+            assert!(ITEM_CATALOG.in_slot(slot).all(|item| item.slot == slot));
+        }
+
+        assert!(ITEM_CATALOG.in_slot(ItemSlot::Body).any(|item| item.id == OCTANE));
+        assert!(ITEM_CATALOG.in_slot(ItemSlot::Skin).any(|item| item.id == OCTANE_FLAMES));
+    }
+
+    #[test]
+    fn search_ranks_and_limits_results() {
+        assert_eq!(ITEM_CATALOG.search(ItemSlot::Skin, "").len(), SEARCH_LIMIT);
+
+        let octanes = ITEM_CATALOG.search(ItemSlot::Body, "octane");
+        assert_eq!(octanes[0].id, OCTANE);
+        assert!(octanes.iter().any(|item| item.name == "Octane ZSR"));
+
+        assert_eq!(ITEM_CATALOG.search(ItemSlot::Body, "23")[0].id, OCTANE);
+    }
+
+    #[test]
+    fn ids_are_normalized() {
+        let normalize = |slot, value| ITEM_CATALOG.normalize_id("car_id", slot, value);
+
+        assert_eq!(normalize(ItemSlot::Body, "").unwrap(), "0");
+        assert_eq!(normalize(ItemSlot::Body, "0").unwrap(), "0");
+        assert_eq!(normalize(ItemSlot::Body, " 23 ").unwrap(), "23");
+        assert_eq!(normalize(ItemSlot::Body, "Octane").unwrap(), "23");
+        assert_eq!(normalize(ItemSlot::Skin, "Octane: Flames").unwrap(), "302");
+
+        assert!(matches!(
+            normalize(ItemSlot::Body, "Not a car"),
+            Err(ItemError::InvalidId { .. })
+        ));
+        assert!(matches!(
+            normalize(ItemSlot::Body, "999999"),
+            Err(ItemError::UnknownItem { id: 999_999, .. })
+        ));
+        assert!(matches!(
+            normalize(ItemSlot::Body, "302"),
+            Err(ItemError::WrongSlot {
+                slot: ItemSlot::Skin,
+                expected: ItemSlot::Body,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn lines_that_arent_items_are_skipped() {
+        let catalog = ItemCatalog::parse("1,Banner,Banner_One,One\nx,Body,Body_X,X\n5,Body,Body_Five,Five, the car\r\n");
+
+        assert_eq!(catalog.items.len(), 1);
+        assert_eq!(catalog.get(5).map(|item| item.name.as_str()), Some("Five, the car"));
+    }
+}
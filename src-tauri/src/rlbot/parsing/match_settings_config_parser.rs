@@ -114,7 +114,7 @@ pub enum MapType {
     Custom(String),
 }
 
-#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, EnumIter)]
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, EnumIter, PartialEq, Eq)]
 pub enum GameMode {
     #[default]
     Soccer,
@@ -0,0 +1,381 @@
+pub(crate) mod flat;
+pub(crate) mod messages;
+
+use crate::{
+    impl_serialize_from_display,
+    rlbot::{gateway_util, state_setting::DesiredGameState},
+    settings::{GameTickPacket, MiniMatchConfig, TeamBotBundle},
+    STATE_SETTING_CONNECTION,
+};
+use flat::FlatError;
+use messages::MessageType;
+use std::{
+    io::{self, Read, Write},
+    net::{Ipv4Addr, SocketAddr, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+use thiserror::Error;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+/// How long to wait before looking for RLBot again after failing to connect to it
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Error)]
+pub enum CoreError {
+    #[error("Couldn't connect to RLBot on port {port}: {source}")]
+    Connect { port: u16, source: io::Error },
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("Message is too large to send to RLBot ({0} bytes)")]
+    TooLarge(usize),
+    #[error("Couldn't read message from RLBot: {0}")]
+    Flat(#[from] FlatError),
+    #[error("{0} can't be used without the match handler")]
+    Unsupported(String),
+    #[error("Mutex {0} was poisoned")]
+    Poisoned(&'static str),
+}
+
+impl_serialize_from_display!(CoreError);
+
+/// A connection to the RLBot core, which speaks flatbuffers over TCP
+///
+/// Every message is prefixed by its type and size, both as big-endian `u16`s.
+/// This only talks to a core that's already running: launching RLBot and Rocket League is still up to the match handler,
+/// so a match can only be started without Python once something else has started the core
+pub struct CoreConnection {
+    stream: TcpStream,
+    port: u16,
+}
+
+impl CoreConnection {
+    /// Connect to the RLBot core that's listening on `port`
+    pub fn connect(port: u16) -> Result<Self, CoreError> {
+        let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+        let stream =
+            TcpStream::connect_timeout(&address, CONNECT_TIMEOUT).map_err(|source| CoreError::Connect { port, source })?;
+        stream.set_nodelay(true)?;
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+
+        Ok(Self { stream, port })
+    }
+
+    /// Connect to the RLBot core that's already running, if there is one
+    ///
+    /// This can't start the core itself, so something else still has to have started it,
+    /// which is usually the match handler and therefore still needs Python
+    pub fn connect_existing() -> Option<Result<Self, CoreError>> {
+        gateway_util::existing_process_port().map(Self::connect)
+    }
+
+    fn send(&mut self, message_type: MessageType, payload: &[u8]) -> Result<(), CoreError> {
+        let size = u16::try_from(payload.len()).map_err(|_| CoreError::TooLarge(payload.len()))?;
+
+        let mut frame = Vec::with_capacity(payload.len() + 4);
+        frame.extend((message_type as u16).to_be_bytes());
+        frame.extend(size.to_be_bytes());
+        frame.extend(payload);
+
+        self.stream.write_all(&frame)?;
+        Ok(())
+    }
+
+    /// Read the next message from RLBot, returning its type and payload
+    fn recv(&mut self) -> Result<(u16, Vec<u8>), CoreError> {
+        let mut header = [0; 4];
+        self.stream.read_exact(&mut header)?;
+
+        let message_type = u16::from_be_bytes([header[0], header[1]]);
+        let mut payload = vec![0; usize::from(u16::from_be_bytes([header[2], header[3]]))];
+        self.stream.read_exact(&mut payload)?;
+
+        Ok((message_type, payload))
+    }
+
+    /// Check that RLBot hasn't closed the connection, without waiting for anything to arrive
+    fn is_open(&self) -> bool {
+        if self.stream.set_nonblocking(true).is_err() {
+            return false;
+        }
+
+        let open = match self.stream.peek(&mut [0]) {
+            Ok(read) => read > 0,
+            Err(e) => e.kind() == io::ErrorKind::WouldBlock,
+        };
+
+        self.stream.set_nonblocking(false).is_ok() && open
+    }
+
+    /// Start a match with only humans and Psyonix bots
+    pub fn start_match(&mut self, bots: &[TeamBotBundle], settings: &MiniMatchConfig) -> Result<(), CoreError> {
+        self.send(MessageType::MatchSettings, &messages::match_settings(bots, settings)?)
+    }
+
+    pub fn set_state(&mut self, state: &DesiredGameState) -> Result<(), CoreError> {
+        self.send(MessageType::DesiredGameState, &messages::desired_game_state(state))
+    }
+
+    /// Tell RLBot that we're ready, after which it starts sending us game tick packets
+    pub fn subscribe(&mut self) -> Result<(), CoreError> {
+        self.send(MessageType::ReadyMessage, &messages::ready_message(false, false, false))
+    }
+
+    /// Wait for the next game tick packet, skipping any other messages
+    pub fn next_game_tick_packet(&mut self) -> Result<GameTickPacket, CoreError> {
+        loop {
+            let (message_type, payload) = self.recv()?;
+
+            if message_type == MessageType::GameTickPacket as u16 {
+                return Ok(messages::game_tick_packet(&payload)?);
+            }
+        }
+    }
+}
+
+/// Set the state through the RLBot core that's already running, reusing the connection from last time if it's still open
+///
+/// Returns `None` if RLBot isn't running
+///
+/// # Arguments
+///
+/// * `state` - The state to set
+pub fn set_state_existing(state: &DesiredGameState) -> Option<Result<(), CoreError>> {
+    let port = gateway_util::existing_process_port()?;
+
+    Some(
+        STATE_SETTING_CONNECTION
+            .lock()
+            .map_err(|_| CoreError::Poisoned("STATE_SETTING_CONNECTION"))
+            .and_then(|mut connection| set_state_on_port(&mut connection, port, state)),
+    )
+}
+
+fn set_state_on_port(connection: &mut Option<CoreConnection>, port: u16, state: &DesiredGameState) -> Result<(), CoreError> {
+    // RLBot gets a new port when it restarts, and closes its connections when it exits
+    if let Some(existing) = connection
+        .as_mut()
+        .filter(|existing| existing.port == port && existing.is_open())
+    {
+        if existing.set_state(state).is_ok() {
+            return Ok(());
+        }
+    }
+
+    *connection = None;
+    let mut new_connection = CoreConnection::connect(port)?;
+    new_connection.set_state(state)?;
+    *connection = Some(new_connection);
+
+    Ok(())
+}
+
+/// Returns `true` if the match can be started without the match handler, as long as RLBot is already running
+pub fn can_start_natively(bots: &[TeamBotBundle], settings: &MiniMatchConfig) -> bool {
+    messages::match_settings(bots, settings).is_ok()
+}
+
+/// Reads game tick packets from RLBot on a background thread, keeping only the latest one
+struct FeedThread {
+    latest: Arc<Mutex<Option<GameTickPacket>>>,
+    alive: Arc<AtomicBool>,
+}
+
+impl FeedThread {
+    fn start(port: u16) -> Result<Self, CoreError> {
+        let mut connection = CoreConnection::connect(port)?;
+        connection.subscribe()?;
+        // Packets stop while the game is paused, which shouldn't end the feed
+        connection.stream.set_read_timeout(None)?;
+
+        let latest = Arc::new(Mutex::new(None));
+        let alive = Arc::new(AtomicBool::new(true));
+
+        let thread_latest = latest.clone();
+        let thread_alive = alive.clone();
+        thread::spawn(move || {
+            while let Ok(packet) = connection.next_game_tick_packet() {
+                let Ok(mut latest) = thread_latest.lock() else {
+                    break;
+                };
+
+                *latest = Some(packet);
+            }
+
+            thread_alive.store(false, Ordering::Relaxed);
+        });
+
+        Ok(Self { latest, alive })
+    }
+}
+
+/// A subscription to RLBot's game tick packets that connects to RLBot on demand
+#[derive(Default)]
+pub struct PacketFeed {
+    thread: Option<FeedThread>,
+    last_attempt: Option<Instant>,
+}
+
+impl PacketFeed {
+    pub const fn new() -> Self {
+        Self {
+            thread: None,
+            last_attempt: None,
+        }
+    }
+
+    /// Get the latest game tick packet, connecting to RLBot first if needed
+    ///
+    /// Returns `None` if RLBot isn't running or hasn't sent a packet yet
+    pub fn latest(&mut self) -> Option<GameTickPacket> {
+        if !self
+            .thread
+            .as_ref()
+            .map_or(false, |thread| thread.alive.load(Ordering::Relaxed))
+        {
+            self.thread = None;
+
+            if self.last_attempt.map_or(false, |last| last.elapsed() < RECONNECT_INTERVAL) {
+                return None;
+            }
+
+            self.last_attempt = Some(Instant::now());
+            self.thread = FeedThread::start(gateway_util::existing_process_port()?).ok();
+        }
+
+        self.thread.as_ref()?.latest.lock().ok()?.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rlbot::state_setting::DesiredGameInfoState, settings::Team};
+    use flat::{Table, TableRef, Value};
+    use std::net::TcpListener;
+
+    /// A stand-in for the RLBot core, which accepts connections on a random port
+    fn fake_core() -> (TcpListener, u16) {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        (listener, port)
+    }
+
+    fn read_message(stream: &mut TcpStream) -> (u16, Vec<u8>) {
+        let mut header = [0; 4];
+        stream.read_exact(&mut header).unwrap();
+
+        let mut payload = vec![0; usize::from(u16::from_be_bytes([header[2], header[3]]))];
+        stream.read_exact(&mut payload).unwrap();
+
+        (u16::from_be_bytes([header[0], header[1]]), payload)
+    }
+
+    fn write_message(stream: &mut TcpStream, message_type: u16, payload: &[u8]) {
+        let size = u16::try_from(payload.len()).unwrap();
+        stream.write_all(&message_type.to_be_bytes()).unwrap();
+        stream.write_all(&size.to_be_bytes()).unwrap();
+        stream.write_all(payload).unwrap();
+    }
+
+    #[test]
+    fn subscribe_sends_ready_message() {
+        let (listener, port) = fake_core();
+        let mut connection = CoreConnection::connect(port).unwrap();
+        let (mut core, _) = listener.accept().unwrap();
+
+        connection.subscribe().unwrap();
+
+        let (message_type, payload) = read_message(&mut core);
+        assert_eq!(message_type, 11);
+        assert!(!TableRef::root(&payload).unwrap().bool(0).unwrap());
+    }
+
+    #[test]
+    fn start_match_sends_match_settings() {
+        let (listener, port) = fake_core();
+        let mut connection = CoreConnection::connect(port).unwrap();
+        let (mut core, _) = listener.accept().unwrap();
+
+        let bots = [TeamBotBundle {
+            name: "Psyonix Allstar".to_owned(),
+            team: Team::Orange,
+            skill: 1.,
+            runnable_type: "psyonix".to_owned(),
+            path: None,
+            supports_early_start: false,
+            maximum_tick_rate_preference: None,
+            bot_parameters: Default::default(),
+        }];
+        connection.start_match(&bots, &MiniMatchConfig::default()).unwrap();
+
+        let (message_type, payload) = read_message(&mut core);
+        assert_eq!(message_type, 3);
+        let players = TableRef::root(&payload).unwrap().tables(0).unwrap();
+        assert_eq!(players[0].string(2).unwrap(), Some("Psyonix Allstar"));
+    }
+
+    #[test]
+    fn game_tick_packets_skip_other_messages() {
+        let (listener, port) = fake_core();
+        let mut connection = CoreConnection::connect(port).unwrap();
+        let (mut core, _) = listener.accept().unwrap();
+
+        let game_info = Table::new().with(9, Value::I32(64));
+        let packet = Table::new().with(3, Value::Table(game_info)).finish();
+        write_message(&mut core, 11, &messages::ready_message(false, false, false));
+        write_message(&mut core, 1, &packet);
+
+        let packet = connection.next_game_tick_packet().unwrap();
+        assert_eq!(packet.game_info.frame_num, 64);
+    }
+
+    #[test]
+    fn set_state_reuses_the_connection() {
+        let (listener, port) = fake_core();
+        let mut connection = None;
+        let state = DesiredGameState {
+            game_info: Some(DesiredGameInfoState {
+                paused: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        set_state_on_port(&mut connection, port, &state).unwrap();
+        let (mut core, _) = listener.accept().unwrap();
+        set_state_on_port(&mut connection, port, &state).unwrap();
+
+        for _ in 0..2 {
+            let (message_type, payload) = read_message(&mut core);
+            assert_eq!(message_type, 7);
+            let game_info = TableRef::root(&payload).unwrap().table(3).unwrap().unwrap();
+            assert!(game_info.bool(2).unwrap());
+        }
+
+        // Nobody else connected, so both states went over the first connection
+        listener.set_nonblocking(true).unwrap();
+        assert!(listener.accept().is_err());
+    }
+
+    #[test]
+    fn set_state_reconnects_after_rlbot_closes_the_connection() {
+        let (listener, port) = fake_core();
+        let mut connection = None;
+        let state = DesiredGameState::default();
+
+        set_state_on_port(&mut connection, port, &state).unwrap();
+        drop(listener.accept().unwrap());
+        // Give the connection a moment to notice that it was closed
+        thread::sleep(Duration::from_millis(50));
+
+        set_state_on_port(&mut connection, port, &state).unwrap();
+        let (mut core, _) = listener.accept().unwrap();
+        assert_eq!(read_message(&mut core).0, 7);
+    }
+}
@@ -0,0 +1,388 @@
+//! Just enough of the `FlatBuffers` binary format to build and read the messages that RLBot uses
+//!
+//! Tables are written front-to-back: the vtable, then the table itself, then everything it points to.
+//! That keeps every offset pointing forward, as the format requires, without needing a back-to-front builder.
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum FlatError {
+    #[error("Tried to read past the end of a {0} byte flatbuffer")]
+    OutOfBounds(usize),
+    #[error("Flatbuffer contains a string that isn't valid UTF-8: {0}")]
+    InvalidUtf8(#[from] std::str::Utf8Error),
+}
+
+/// A value that can be stored in a table field or in a vector
+#[derive(Debug, Clone)]
+pub enum Value {
+    Bool(bool),
+    U8(u8),
+    I32(i32),
+    F32(f32),
+    /// A struct made up of only `f32`s, like `Vector3`, which is stored inline in its parent table
+    F32Struct(Vec<f32>),
+    String(String),
+    Table(Table),
+    /// A vector of strings or tables
+    Vector(Vec<Value>),
+}
+
+impl Value {
+    const fn inline_align(&self) -> usize {
+        match self {
+            Self::Bool(_) | Self::U8(_) => 1,
+            _ => 4,
+        }
+    }
+
+    /// Write the part of the value that lives inside of its parent
+    ///
+    /// Returns `true` if a placeholder offset was written, meaning the actual value still has to be written later
+    fn write_inline(&self, buf: &mut Vec<u8>) -> bool {
+        match self {
+            Self::Bool(value) => buf.push(u8::from(*value)),
+            Self::U8(value) => buf.push(*value),
+            Self::I32(value) => buf.extend(value.to_le_bytes()),
+            Self::F32(value) => buf.extend(value.to_le_bytes()),
+            Self::F32Struct(values) => values.iter().for_each(|value| buf.extend(value.to_le_bytes())),
+            Self::String(_) | Self::Table(_) | Self::Vector(_) => {
+                buf.extend([0; 4]);
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Write a value that's referred to by an offset, returning where it was written
+    fn write_referenced(&self, buf: &mut Vec<u8>) -> usize {
+        match self {
+            Self::String(value) => {
+                align(buf, 4);
+                let pos = buf.len();
+                buf.extend(len_u32(value.len()).to_le_bytes());
+                buf.extend(value.as_bytes());
+                buf.push(0);
+                pos
+            }
+            Self::Table(table) => table.write(buf),
+            Self::Vector(items) => {
+                align(buf, 4);
+                let pos = buf.len();
+                buf.extend(len_u32(items.len()).to_le_bytes());
+                buf.resize(buf.len() + items.len() * 4, 0);
+
+                for (i, item) in items.iter().enumerate() {
+                    let item_pos = item.write_referenced(buf);
+                    patch_offset(buf, pos + 4 + i * 4, item_pos);
+                }
+
+                pos
+            }
+            // Scalars and structs are normally stored inline, but they can still be written on their own
+            _ => {
+                align(buf, self.inline_align());
+                let pos = buf.len();
+                self.write_inline(buf);
+                pos
+            }
+        }
+    }
+}
+
+/// A table under construction
+///
+/// Fields are identified by their slot, which is their index in the schema
+/// (a union takes up two slots: one for its type and one for its value)
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    fields: Vec<(u16, Value)>,
+}
+
+impl Table {
+    pub const fn new() -> Self {
+        Self { fields: Vec::new() }
+    }
+
+    #[must_use]
+    pub fn with(mut self, slot: u16, value: Value) -> Self {
+        self.fields.push((slot, value));
+        self
+    }
+
+    #[must_use]
+    pub fn with_opt(self, slot: u16, value: Option<Value>) -> Self {
+        match value {
+            Some(value) => self.with(slot, value),
+            None => self,
+        }
+    }
+
+    fn write(&self, buf: &mut Vec<u8>) -> usize {
+        let num_slots = self.fields.iter().map(|(slot, _)| usize::from(*slot) + 1).max().unwrap_or(0);
+
+        align(buf, 2);
+        let vtable_pos = buf.len();
+        let vtable_len = 4 + num_slots * 2;
+        buf.resize(vtable_pos + vtable_len, 0);
+
+        align(buf, 4);
+        let table_pos = buf.len();
+        buf.extend(len_i32(table_pos - vtable_pos).to_le_bytes());
+
+        let mut referenced = Vec::new();
+        for (slot, value) in &self.fields {
+            align(buf, value.inline_align());
+            let field_pos = buf.len();
+            write_u16(buf, vtable_pos + 4 + usize::from(*slot) * 2, field_pos - table_pos);
+
+            if value.write_inline(buf) {
+                referenced.push((field_pos, value));
+            }
+        }
+
+        let table_len = buf.len() - table_pos;
+        write_u16(buf, vtable_pos, vtable_len);
+        write_u16(buf, vtable_pos + 2, table_len);
+
+        for (field_pos, value) in referenced {
+            let value_pos = value.write_referenced(buf);
+            patch_offset(buf, field_pos, value_pos);
+        }
+
+        table_pos
+    }
+
+    /// Serialize this table as the root of a new flatbuffer
+    pub fn finish(&self) -> Vec<u8> {
+        let mut buf = vec![0; 4];
+        let root_pos = self.write(&mut buf);
+        patch_offset(&mut buf, 0, root_pos);
+        buf
+    }
+}
+
+fn align(buf: &mut Vec<u8>, alignment: usize) {
+    let padding = (alignment - buf.len() % alignment) % alignment;
+    buf.resize(buf.len() + padding, 0);
+}
+
+fn len_u32(len: usize) -> u32 {
+    u32::try_from(len).unwrap_or(u32::MAX)
+}
+
+fn len_i32(len: usize) -> i32 {
+    i32::try_from(len).unwrap_or(i32::MAX)
+}
+
+fn write_u16(buf: &mut [u8], pos: usize, value: usize) {
+    let value = u16::try_from(value).unwrap_or(u16::MAX);
+    buf[pos..pos + 2].copy_from_slice(&value.to_le_bytes());
+}
+
+fn patch_offset(buf: &mut [u8], pos: usize, target: usize) {
+    buf[pos..pos + 4].copy_from_slice(&len_u32(target - pos).to_le_bytes());
+}
+
+fn read_bytes<const N: usize>(buf: &[u8], pos: usize) -> Result<[u8; N], FlatError> {
+    buf.get(pos..pos + N)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(FlatError::OutOfBounds(buf.len()))
+}
+
+fn read_u16(buf: &[u8], pos: usize) -> Result<u16, FlatError> {
+    read_bytes(buf, pos).map(u16::from_le_bytes)
+}
+
+fn read_u32(buf: &[u8], pos: usize) -> Result<usize, FlatError> {
+    read_bytes(buf, pos).map(|bytes| u32::from_le_bytes(bytes) as usize)
+}
+
+fn read_f32(buf: &[u8], pos: usize) -> Result<f32, FlatError> {
+    read_bytes(buf, pos).map(f32::from_le_bytes)
+}
+
+/// Follow the offset stored at `pos`
+fn deref(buf: &[u8], pos: usize) -> Result<usize, FlatError> {
+    Ok(pos + read_u32(buf, pos)?)
+}
+
+/// A table inside of a received flatbuffer
+#[derive(Debug, Clone, Copy)]
+pub struct TableRef<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> TableRef<'a> {
+    pub fn root(buf: &'a [u8]) -> Result<Self, FlatError> {
+        Ok(Self {
+            buf,
+            pos: deref(buf, 0)?,
+        })
+    }
+
+    /// Find where the field in `slot` is stored, if it's present
+    fn field_pos(&self, slot: u16) -> Result<Option<usize>, FlatError> {
+        let soffset = i32::from_le_bytes(read_bytes(self.buf, self.pos)?);
+        let vtable_pos = i64::try_from(self.pos)
+            .ok()
+            .and_then(|pos| usize::try_from(pos - i64::from(soffset)).ok())
+            .ok_or(FlatError::OutOfBounds(self.buf.len()))?;
+
+        let entry = 4 + usize::from(slot) * 2;
+        if entry >= usize::from(read_u16(self.buf, vtable_pos)?) {
+            return Ok(None);
+        }
+
+        Ok(match read_u16(self.buf, vtable_pos + entry)? {
+            0 => None,
+            offset => Some(self.pos + usize::from(offset)),
+        })
+    }
+
+    pub fn bool(&self, slot: u16) -> Result<bool, FlatError> {
+        self.field_pos(slot)?
+            .map_or(Ok(false), |pos| read_bytes::<1>(self.buf, pos).map(|[byte]| byte != 0))
+    }
+
+    pub fn i32(&self, slot: u16) -> Result<i32, FlatError> {
+        self.field_pos(slot)?
+            .map_or(Ok(0), |pos| read_bytes(self.buf, pos).map(i32::from_le_bytes))
+    }
+
+    pub fn f32(&self, slot: u16) -> Result<f32, FlatError> {
+        self.field_pos(slot)?.map_or(Ok(0.), |pos| read_f32(self.buf, pos))
+    }
+
+    /// Read a struct made up of `N` `f32`s, like `Vector3`
+    pub fn f32_struct<const N: usize>(&self, slot: u16) -> Result<Option<[f32; N]>, FlatError> {
+        let Some(pos) = self.field_pos(slot)? else {
+            return Ok(None);
+        };
+
+        let mut values = [0.; N];
+        for (i, value) in values.iter_mut().enumerate() {
+            *value = read_f32(self.buf, pos + i * 4)?;
+        }
+
+        Ok(Some(values))
+    }
+
+    pub fn table(&self, slot: u16) -> Result<Option<Self>, FlatError> {
+        self.field_pos(slot)?
+            .map(|pos| {
+                Ok(Self {
+                    buf: self.buf,
+                    pos: deref(self.buf, pos)?,
+                })
+            })
+            .transpose()
+    }
+
+    pub fn string(&self, slot: u16) -> Result<Option<&'a str>, FlatError> {
+        let Some(pos) = self.field_pos(slot)? else {
+            return Ok(None);
+        };
+
+        let start = deref(self.buf, pos)?;
+        let len = read_u32(self.buf, start)?;
+        let bytes = self
+            .buf
+            .get(start + 4..start + 4 + len)
+            .ok_or(FlatError::OutOfBounds(self.buf.len()))?;
+
+        Ok(Some(std::str::from_utf8(bytes)?))
+    }
+
+    /// Read a vector of tables, which is empty if the field isn't present
+    pub fn tables(&self, slot: u16) -> Result<Vec<Self>, FlatError> {
+        let Some(pos) = self.field_pos(slot)? else {
+            return Ok(Vec::new());
+        };
+
+        let start = deref(self.buf, pos)?;
+        let len = read_u32(self.buf, start)?;
+
+        (0..len)
+            .map(|i| {
+                Ok(Self {
+                    buf: self.buf,
+                    pos: deref(self.buf, start + 4 + i * 4)?,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scalars_round_trip() {
+        let buf = Table::new()
+            .with(0, Value::Bool(true))
+            .with(1, Value::U8(7))
+            .with(2, Value::I32(-12))
+            .with(3, Value::F32(1.5))
+            .finish();
+        let table = TableRef::root(&buf).unwrap();
+
+        assert!(table.bool(0).unwrap());
+        assert_eq!(table.i32(2).unwrap(), -12);
+        assert!((table.f32(3).unwrap() - 1.5).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn missing_fields_are_defaults() {
+        let buf = Table::new().with(1, Value::Bool(true)).finish();
+        let table = TableRef::root(&buf).unwrap();
+
+        assert!(!table.bool(0).unwrap());
+        assert_eq!(table.i32(5).unwrap(), 0);
+        assert_eq!(table.f32_struct::<3>(2).unwrap(), None);
+        assert!(table.table(3).unwrap().is_none());
+        assert!(table.string(4).unwrap().is_none());
+        assert!(table.tables(6).unwrap().is_empty());
+    }
+
+    #[test]
+    fn nested_values_round_trip() {
+        let buf = Table::new()
+            .with(0, Value::F32Struct(vec![1., 2., 3.]))
+            .with(1, Value::String("Psyonix Allstar".to_owned()))
+            .with(2, Value::Table(Table::new().with(0, Value::I32(42))))
+            .with(
+                3,
+                Value::Vector(vec![
+                    Value::Table(Table::new().with(0, Value::String("first".to_owned()))),
+                    Value::Table(Table::new().with(0, Value::String("second".to_owned()))),
+                ]),
+            )
+            .finish();
+        let table = TableRef::root(&buf).unwrap();
+
+        assert_eq!(table.f32_struct::<3>(0).unwrap(), Some([1., 2., 3.]));
+        assert_eq!(table.string(1).unwrap(), Some("Psyonix Allstar"));
+        assert_eq!(table.table(2).unwrap().unwrap().i32(0).unwrap(), 42);
+
+        let names = table
+            .tables(3)
+            .unwrap()
+            .iter()
+            .map(|item| item.string(0).unwrap().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["first", "second"]);
+    }
+
+    #[test]
+    fn truncated_buffer_is_an_error() {
+        let buf = Table::new().with(0, Value::String("cut off".to_owned())).finish();
+        let truncated = &buf[..buf.len() - 6];
+
+        let result = TableRef::root(truncated).and_then(|table| table.string(0).map(|_| ()));
+        assert!(matches!(result, Err(FlatError::OutOfBounds(_))));
+    }
+}
@@ -0,0 +1,492 @@
+//! Builders and readers for the messages in RLBot's flatbuffer schema (`rlbot.fbs`)
+//!
+//! The slot numbers used here are the field indices from that schema
+
+use super::{
+    flat::{FlatError, Table, TableRef, Value},
+    CoreError,
+};
+use crate::{
+    rlbot::{
+        parsing::match_settings_config_parser::MapType,
+        state_setting::{DesiredGameState, DesiredPhysics},
+    },
+    settings::{
+        Ball, BoostPad, Car, GameInfo, GameTickPacket, MatchPhase, MiniMatchConfig, Physics, Rotation, ScoreInfo, Team,
+        TeamBotBundle, TeamInfo, Vec3D,
+    },
+};
+use rand::Rng;
+use std::collections::BTreeMap;
+use strum::IntoEnumIterator;
+
+/// The type of each message, which is sent before it over the socket
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u16)]
+pub enum MessageType {
+    GameTickPacket = 1,
+    MatchSettings = 3,
+    DesiredGameState = 7,
+    ReadyMessage = 11,
+}
+
+/// The values of the `PlayerClass` union
+const PLAYER_CLASS_HUMAN: u8 = 2;
+const PLAYER_CLASS_PSYONIX: u8 = 3;
+
+/// Tells RLBot that we're connected and which optional messages we want to receive
+pub fn ready_message(wants_ball_predictions: bool, wants_quick_chat: bool, wants_game_messages: bool) -> Vec<u8> {
+    Table::new()
+        .with(0, Value::Bool(wants_ball_predictions))
+        .with(1, Value::Bool(wants_quick_chat))
+        .with(2, Value::Bool(wants_game_messages))
+        .finish()
+}
+
+fn player_configuration(bot: &TeamBotBundle) -> Result<Table, CoreError> {
+    let (player_class, variety) = match bot.runnable_type.as_str() {
+        "human" => (PLAYER_CLASS_HUMAN, Table::new()),
+        "psyonix" => (PLAYER_CLASS_PSYONIX, Table::new().with(0, Value::F32(bot.skill))),
+        other => return Err(CoreError::Unsupported(format!("Bots of type '{other}'"))),
+    };
+
+    let team = match bot.team {
+        Team::Blue => 0,
+        Team::Orange => 1,
+    };
+
+    Ok(Table::new()
+        .with(0, Value::U8(player_class))
+        .with(1, Value::Table(variety))
+        .with(2, Value::String(bot.name.clone()))
+        .with(3, Value::I32(team))
+        .with(5, Value::I32(rand::thread_rng().gen_range(0..i32::MAX))))
+}
+
+/// Build the `MatchSettings` message that starts a match
+///
+/// Only humans and Psyonix bots are supported, because any other bot needs to be launched by the match handler
+pub fn match_settings(bots: &[TeamBotBundle], settings: &MiniMatchConfig) -> Result<Vec<u8>, CoreError> {
+    if !settings.scripts.is_empty() {
+        return Err(CoreError::Unsupported("Scripts".to_owned()));
+    }
+
+    // The order of `MapType` mirrors the `GameMap` enum in the schema
+    let game_map = match &settings.map {
+        MapType::Custom(path) => return Err(CoreError::Unsupported(format!("The custom map {path}"))),
+        map => MapType::iter()
+            .position(|other| &other == map)
+            .and_then(|index| u8::try_from(index).ok())
+            .ok_or_else(|| CoreError::Unsupported(format!("The map {map:?}")))?,
+    };
+
+    let players = bots
+        .iter()
+        .map(|bot| player_configuration(bot).map(Value::Table))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let m = &settings.mutators;
+    let mutators = Table::new()
+        .with(0, Value::U8(m.match_length as u8))
+        .with(1, Value::U8(m.max_score as u8))
+        .with(2, Value::U8(m.overtime as u8))
+        .with(3, Value::U8(m.series_length as u8))
+        .with(4, Value::U8(m.game_speed as u8))
+        .with(5, Value::U8(m.ball_max_speed as u8))
+        .with(6, Value::U8(m.ball_type as u8))
+        .with(7, Value::U8(m.ball_weight as u8))
+        .with(8, Value::U8(m.ball_size as u8))
+        .with(9, Value::U8(m.ball_bounciness as u8))
+        .with(10, Value::U8(m.boost_amount as u8))
+        .with(11, Value::U8(m.rumble as u8))
+        .with(12, Value::U8(m.boost_strength as u8))
+        .with(13, Value::U8(m.gravity as u8))
+        .with(14, Value::U8(m.demolish as u8))
+        .with(15, Value::U8(m.respawn_time as u8));
+
+    Ok(Table::new()
+        .with(0, Value::Vector(players))
+        .with(1, Value::U8(settings.game_mode as u8))
+        .with(2, Value::U8(game_map))
+        .with(3, Value::Bool(settings.skip_replays))
+        .with(4, Value::Bool(settings.instant_start))
+        .with(5, Value::Table(mutators))
+        .with(6, Value::U8(settings.match_behavior as u8))
+        .with(7, Value::Bool(settings.enable_lockstep))
+        .with(8, Value::Bool(settings.enable_rendering))
+        .with(9, Value::Bool(settings.enable_state_setting))
+        .with(10, Value::Bool(settings.auto_save_replay))
+        .finish())
+}
+
+fn vector3_partial(vec: Vec3D) -> Value {
+    Value::Table(
+        Table::new()
+            .with(0, Value::F32(vec.x))
+            .with(1, Value::F32(vec.y))
+            .with(2, Value::F32(vec.z)),
+    )
+}
+
+fn desired_physics(physics: &DesiredPhysics) -> Value {
+    let rotation = physics.rotation.map(|rotation| {
+        Value::Table(
+            Table::new()
+                .with(0, Value::F32(rotation.pitch))
+                .with(1, Value::F32(rotation.yaw))
+                .with(2, Value::F32(rotation.roll)),
+        )
+    });
+
+    Value::Table(
+        Table::new()
+            .with_opt(0, physics.location.map(vector3_partial))
+            .with_opt(1, rotation)
+            .with_opt(2, physics.velocity.map(vector3_partial))
+            .with_opt(3, physics.angular_velocity.map(vector3_partial)),
+    )
+}
+
+/// Turn a map of indices to states into a vector, filling the gaps with empty tables so those objects are left alone
+fn indexed_states<T>(states: &BTreeMap<usize, T>, to_table: impl Fn(&T) -> Table) -> Option<Value> {
+    let len = states.keys().next_back()? + 1;
+
+    Some(Value::Vector(
+        (0..len)
+            .map(|index| Value::Table(states.get(&index).map_or_else(Table::new, &to_table)))
+            .collect(),
+    ))
+}
+
+/// Build the `DesiredGameState` message
+pub fn desired_game_state(state: &DesiredGameState) -> Vec<u8> {
+    let ball = state
+        .ball
+        .map(|ball| Value::Table(Table::new().with_opt(0, ball.physics.as_ref().map(desired_physics))));
+
+    let cars = indexed_states(&state.cars, |car| {
+        Table::new()
+            .with_opt(0, car.physics.as_ref().map(desired_physics))
+            .with_opt(1, car.boost_amount.map(Value::F32))
+            .with_opt(2, car.jumped.map(Value::Bool))
+            .with_opt(3, car.double_jumped.map(Value::Bool))
+    });
+
+    let boosts = indexed_states(&state.boosts, |boost| {
+        Table::new().with_opt(0, boost.respawn_time.map(Value::F32))
+    });
+
+    let game_info = state.game_info.map(|game_info| {
+        Value::Table(
+            Table::new()
+                .with_opt(0, game_info.world_gravity_z.map(Value::F32))
+                .with_opt(1, game_info.game_speed.map(Value::F32))
+                .with_opt(2, game_info.paused.map(Value::Bool))
+                .with_opt(3, game_info.end_match.map(Value::Bool)),
+        )
+    });
+
+    let console_commands = (!state.console_commands.is_empty()).then(|| {
+        Value::Vector(
+            state
+                .console_commands
+                .iter()
+                .map(|command| Value::Table(Table::new().with(0, Value::String(command.clone()))))
+                .collect(),
+        )
+    });
+
+    Table::new()
+        .with_opt(0, ball)
+        .with_opt(1, cars)
+        .with_opt(2, boosts)
+        .with_opt(3, game_info)
+        .with_opt(4, console_commands)
+        .finish()
+}
+
+fn read_vec3(table: &TableRef, slot: u16) -> Result<Vec3D, FlatError> {
+    let [x, y, z] = table.f32_struct(slot)?.unwrap_or_default();
+    Ok(Vec3D { x, y, z })
+}
+
+fn read_physics(table: Option<TableRef>) -> Result<Physics, FlatError> {
+    let Some(table) = table else {
+        return Ok(Physics::default());
+    };
+
+    let [pitch, yaw, roll] = table.f32_struct(1)?.unwrap_or_default();
+
+    Ok(Physics {
+        location: read_vec3(&table, 0)?,
+        rotation: Rotation { pitch, yaw, roll },
+        velocity: read_vec3(&table, 2)?,
+        angular_velocity: read_vec3(&table, 3)?,
+    })
+}
+
+fn read_score_info(table: Option<TableRef>) -> Result<ScoreInfo, FlatError> {
+    let Some(table) = table else {
+        return Ok(ScoreInfo::default());
+    };
+
+    Ok(ScoreInfo {
+        score: table.i32(0)?,
+        goals: table.i32(1)?,
+        own_goals: table.i32(2)?,
+        assists: table.i32(3)?,
+        saves: table.i32(4)?,
+        shots: table.i32(5)?,
+        demolitions: table.i32(6)?,
+    })
+}
+
+fn read_car(player: TableRef) -> Result<Car, FlatError> {
+    Ok(Car {
+        team: u8::try_from(player.i32(9)?).unwrap_or_default(),
+        physics: read_physics(player.table(0)?)?,
+        name: player.string(8)?.unwrap_or_default().to_owned(),
+        boost: player.i32(10)?,
+        is_demolished: player.bool(2)?,
+        has_wheel_contact: player.bool(3)?,
+        is_supersonic: player.bool(4)?,
+        is_bot: player.bool(5)?,
+        score_info: read_score_info(player.table(1)?)?,
+    })
+}
+
+fn read_game_info(table: Option<TableRef>) -> Result<GameInfo, FlatError> {
+    let Some(table) = table else {
+        return Ok(GameInfo::default());
+    };
+
+    Ok(GameInfo {
+        seconds_elapsed: table.f32(0)?,
+        game_time_remaining: table.f32(1)?,
+        is_overtime: table.bool(2)?,
+        is_unlimited_time: table.bool(3)?,
+        phase: MatchPhase::new(table.bool(4)?, table.bool(5)?, table.bool(6)?),
+        world_gravity_z: table.f32(7)?,
+        game_speed: table.f32(8)?,
+        frame_num: table.i32(9)?,
+    })
+}
+
+/// Read a `GameTickPacket` message
+pub fn game_tick_packet(buf: &[u8]) -> Result<GameTickPacket, FlatError> {
+    let packet = TableRef::root(buf)?;
+
+    let game_cars = packet.tables(0)?.into_iter().map(read_car).collect::<Result<_, _>>()?;
+
+    let game_boosts = packet
+        .tables(1)?
+        .into_iter()
+        .map(|pad| {
+            Ok(BoostPad {
+                is_active: pad.bool(0)?,
+                timer: pad.f32(1)?,
+            })
+        })
+        .collect::<Result<_, FlatError>>()?;
+
+    let game_ball = Ball {
+        physics: read_physics(packet.table(2)?.map(|ball| ball.table(0)).transpose()?.flatten())?,
+    };
+
+    let teams = packet
+        .tables(5)?
+        .into_iter()
+        .map(|team| {
+            Ok(TeamInfo {
+                team_index: team.i32(0)?,
+                score: team.i32(1)?,
+            })
+        })
+        .collect::<Result<_, FlatError>>()?;
+
+    Ok(GameTickPacket {
+        game_ball,
+        game_cars,
+        game_boosts,
+        teams,
+        game_info: read_game_info(packet.table(3)?)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rlbot::state_setting::{DesiredBallState, DesiredCarState};
+
+    fn bot(name: &str, runnable_type: &str, team: Team) -> TeamBotBundle {
+        TeamBotBundle {
+            name: name.to_owned(),
+            team,
+            skill: 0.5,
+            runnable_type: runnable_type.to_owned(),
+            path: None,
+            supports_early_start: false,
+            maximum_tick_rate_preference: None,
+            bot_parameters: Default::default(),
+        }
+    }
+
+    #[test]
+    fn message_types_match_the_schema() {
+        assert_eq!(MessageType::GameTickPacket as u16, 1);
+        assert_eq!(MessageType::MatchSettings as u16, 3);
+        assert_eq!(MessageType::DesiredGameState as u16, 7);
+        assert_eq!(MessageType::ReadyMessage as u16, 11);
+    }
+
+    #[test]
+    fn ready_message_round_trips() {
+        let buf = ready_message(true, false, true);
+        let table = TableRef::root(&buf).unwrap();
+
+        assert!(table.bool(0).unwrap());
+        assert!(!table.bool(1).unwrap());
+        assert!(table.bool(2).unwrap());
+    }
+
+    #[test]
+    fn match_settings_round_trip() {
+        let bots = [bot("Me", "human", Team::Blue), bot("Psyonix", "psyonix", Team::Orange)];
+        let settings = MiniMatchConfig {
+            map: MapType::Mannfield,
+            skip_replays: true,
+            ..Default::default()
+        };
+
+        let buf = match_settings(&bots, &settings).unwrap();
+        let table = TableRef::root(&buf).unwrap();
+
+        let players = table.tables(0).unwrap();
+        assert_eq!(players.len(), 2);
+        assert_eq!(players[0].string(2).unwrap(), Some("Me"));
+        assert_eq!(players[0].i32(3).unwrap(), 0);
+        assert_eq!(players[1].string(2).unwrap(), Some("Psyonix"));
+        assert_eq!(players[1].i32(3).unwrap(), 1);
+
+        let skill = players[1].table(1).unwrap().unwrap().f32(0).unwrap();
+        assert!((skill - 0.5).abs() < f32::EPSILON);
+
+        assert!(table.bool(3).unwrap());
+        assert!(!table.bool(4).unwrap());
+        assert!(table.bool(9).unwrap());
+    }
+
+    #[test]
+    fn match_settings_rejects_what_needs_the_match_handler() {
+        let settings = MiniMatchConfig::default();
+        assert!(matches!(
+            match_settings(&[bot("Custom", "rlbot", Team::Blue)], &settings),
+            Err(CoreError::Unsupported(_))
+        ));
+
+        let custom_map = MiniMatchConfig {
+            map: MapType::Custom("map.upk".to_owned()),
+            ..Default::default()
+        };
+        assert!(matches!(match_settings(&[], &custom_map), Err(CoreError::Unsupported(_))));
+    }
+
+    #[test]
+    fn desired_game_state_round_trips() {
+        let mut state = DesiredGameState {
+            ball: Some(DesiredBallState {
+                physics: Some(DesiredPhysics {
+                    location: Some(Vec3D { x: 1., y: 2., z: 3. }),
+                    ..Default::default()
+                }),
+            }),
+            console_commands: vec!["Stat FPS".to_owned()],
+            ..Default::default()
+        };
+        state.cars.insert(
+            2,
+            DesiredCarState {
+                boost_amount: Some(50.),
+                ..Default::default()
+            },
+        );
+
+        let buf = desired_game_state(&state);
+        let table = TableRef::root(&buf).unwrap();
+
+        let location = table
+            .table(0)
+            .unwrap()
+            .unwrap()
+            .table(0)
+            .unwrap()
+            .unwrap()
+            .table(0)
+            .unwrap()
+            .unwrap();
+        assert!((location.f32(1).unwrap() - 2.).abs() < f32::EPSILON);
+
+        // Cars that aren't mentioned get an empty table, so they're left alone
+        let cars = table.tables(1).unwrap();
+        assert_eq!(cars.len(), 3);
+        assert!(cars[0].table(0).unwrap().is_none());
+        assert!((cars[2].f32(1).unwrap() - 50.).abs() < f32::EPSILON);
+
+        assert!(table.tables(2).unwrap().is_empty());
+        let commands = table.tables(4).unwrap();
+        assert_eq!(commands[0].string(0).unwrap(), Some("Stat FPS"));
+    }
+
+    /// Build a `GameTickPacket` the same way that RLBot lays it out
+    fn game_tick_packet_buf(frame_num: i32) -> Vec<u8> {
+        let physics = Table::new()
+            .with(0, Value::F32Struct(vec![100., 200., 17.]))
+            .with(1, Value::F32Struct(vec![0., 1.5, 0.]));
+
+        let car = Table::new()
+            .with(0, Value::Table(physics.clone()))
+            .with(1, Value::Table(Table::new().with(1, Value::I32(2))))
+            .with(3, Value::Bool(true))
+            .with(8, Value::String("Nexto".to_owned()))
+            .with(9, Value::I32(1))
+            .with(10, Value::I32(33));
+
+        let game_info = Table::new()
+            .with(0, Value::F32(12.5))
+            .with(4, Value::Bool(true))
+            .with(9, Value::I32(frame_num));
+
+        Table::new()
+            .with(0, Value::Vector(vec![Value::Table(car)]))
+            .with(1, Value::Vector(vec![Value::Table(Table::new().with(0, Value::Bool(true)))]))
+            .with(2, Value::Table(Table::new().with(0, Value::Table(physics))))
+            .with(3, Value::Table(game_info))
+            .with(
+                5,
+                Value::Vector(vec![Value::Table(Table::new().with(0, Value::I32(1)).with(1, Value::I32(3)))]),
+            )
+            .finish()
+    }
+
+    #[test]
+    fn game_tick_packet_is_read() {
+        let packet = game_tick_packet(&game_tick_packet_buf(900)).unwrap();
+
+        let car = &packet.game_cars[0];
+        assert_eq!(car.name, "Nexto");
+        assert_eq!(car.team, 1);
+        assert_eq!(car.boost, 33);
+        assert!(car.has_wheel_contact);
+        assert!(!car.is_demolished);
+        assert_eq!(car.score_info.goals, 2);
+        assert!((car.physics.location.y - 200.).abs() < f32::EPSILON);
+        assert!((car.physics.rotation.yaw - 1.5).abs() < f32::EPSILON);
+
+        assert!(packet.game_boosts[0].is_active);
+        assert!((packet.game_ball.physics.location.z - 17.).abs() < f32::EPSILON);
+        assert_eq!(packet.teams[0].team_index, 1);
+        assert_eq!(packet.teams[0].score, 3);
+        assert_eq!(packet.game_info.frame_num, 900);
+        assert_eq!(packet.game_info.phase, MatchPhase::Active);
+    }
+}
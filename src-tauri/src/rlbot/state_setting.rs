@@ -0,0 +1,441 @@
+use crate::{
+    impl_serialize_from_display,
+    settings::{GameTickPacket, Physics, Rotation, Vec3D},
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, f32::consts::FRAC_PI_2, fmt};
+use thiserror::Error;
+
+/// The furthest that anything can be from the center of the standard field, including the goals
+const STANDARD_MAX_LOCATION: Vec3D = Vec3D {
+    x: 4096.,
+    y: 6000.,
+    z: 2044.,
+};
+/// The ball's top speed without any mutators
+const STANDARD_BALL_MAX_SPEED: f32 = 6000.;
+/// Custom maps can be much bigger than the standard field and mutators can make the ball much faster,
+/// so locations and ball speeds are only checked against the standard limits times this.
+/// That still catches values that are clearly wrong, like ones in the wrong units
+const CUSTOM_LIMIT_MARGIN: f32 = 10.;
+const BALL_MAX_ANGULAR_SPEED: f32 = 6.;
+const CAR_MAX_SPEED: f32 = 2300.;
+const CAR_MAX_ANGULAR_SPEED: f32 = 5.5;
+const MAX_BOOST: f32 = 100.;
+const MAX_GAME_SPEED: f32 = 10.;
+/// Speeds read from a packet can be slightly over the limit, and should still be accepted when they're sent back
+const SPEED_TOLERANCE: f32 = 1.01;
+
+/// The parts of an object's physics to change, leaving anything that's `None` alone
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct DesiredPhysics {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<Vec3D>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rotation: Option<Rotation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub velocity: Option<Vec3D>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub angular_velocity: Option<Vec3D>,
+}
+
+impl From<Physics> for DesiredPhysics {
+    fn from(physics: Physics) -> Self {
+        Self {
+            location: Some(physics.location),
+            rotation: Some(physics.rotation),
+            velocity: Some(physics.velocity),
+            angular_velocity: Some(physics.angular_velocity),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct DesiredBallState {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub physics: Option<DesiredPhysics>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct DesiredCarState {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub physics: Option<DesiredPhysics>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub boost_amount: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jumped: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub double_jumped: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct DesiredBoostState {
+    /// How long until the boost pad becomes active again, in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub respawn_time: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct DesiredGameInfoState {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub world_gravity_z: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub game_speed: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paused: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_match: Option<bool>,
+}
+
+/// A change to make to the state of a running match
+///
+/// Cars and boost pads are keyed by their index, and anything that isn't mentioned is left alone
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct DesiredGameState {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ball: Option<DesiredBallState>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub cars: BTreeMap<usize, DesiredCarState>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub boosts: BTreeMap<usize, DesiredBoostState>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_info: Option<DesiredGameInfoState>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub console_commands: Vec<String>,
+}
+
+/// A single problem with a `DesiredGameState`
+#[derive(Debug, Clone, Error, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StateError {
+    #[error("There's no car with index {index}, there are only {count} cars")]
+    CarIndex { index: usize, count: usize },
+    #[error("There's no boost pad with index {index}, there are only {count} boost pads")]
+    BoostIndex { index: usize, count: usize },
+    #[error("{field} must be a finite number, but it was {value}")]
+    NotFinite { field: String, value: f32 },
+    #[error("{field} must be between {min} and {max}, but it was {value}")]
+    OutOfBounds { field: String, value: f32, min: f32, max: f32 },
+    #[error("Console command {index} is empty")]
+    EmptyCommand { index: usize },
+}
+
+/// Everything that's wrong with a `DesiredGameState`
+#[derive(Debug, Error)]
+pub struct InvalidStateError(pub Vec<StateError>);
+
+impl fmt::Display for InvalidStateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid state:")?;
+
+        for error in &self.0 {
+            write!(f, "\n{error}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl_serialize_from_display!(InvalidStateError);
+
+/// Collects every problem found while validating a `DesiredGameState`
+#[derive(Default)]
+struct Validator {
+    errors: Vec<StateError>,
+}
+
+impl Validator {
+    fn range(&mut self, field: impl Into<String>, value: f32, min: f32, max: f32) {
+        if !value.is_finite() {
+            self.errors.push(StateError::NotFinite {
+                field: field.into(),
+                value,
+            });
+        } else if value < min || value > max {
+            self.errors.push(StateError::OutOfBounds {
+                field: field.into(),
+                value,
+                min,
+                max,
+            });
+        }
+    }
+
+    fn finite(&mut self, field: impl Into<String>, value: f32) {
+        self.range(field, value, f32::MIN, f32::MAX);
+    }
+
+    /// Checks that each component of the vector is finite and that its length is at most `max_length`
+    fn vector(&mut self, field: &str, vec: Vec3D, max_length: f32) {
+        let components = [("x", vec.x), ("y", vec.y), ("z", vec.z)];
+        let errors_before = self.errors.len();
+
+        for (axis, value) in components {
+            self.finite(format!("{field}.{axis}"), value);
+        }
+
+        if self.errors.len() == errors_before {
+            let length = vec.z.mul_add(vec.z, vec.x.mul_add(vec.x, vec.y * vec.y)).sqrt();
+            self.range(format!("the length of {field}"), length, 0., max_length * SPEED_TOLERANCE);
+        }
+    }
+
+    fn physics(&mut self, field: &str, physics: &DesiredPhysics, max_speed: f32, max_angular_speed: f32) {
+        if let Some(location) = physics.location {
+            let axes = [
+                ("x", location.x, STANDARD_MAX_LOCATION.x),
+                ("y", location.y, STANDARD_MAX_LOCATION.y),
+                ("z", location.z, STANDARD_MAX_LOCATION.z),
+            ];
+
+            for (axis, value, max) in axes {
+                let max = max * CUSTOM_LIMIT_MARGIN;
+                self.range(format!("{field}.location.{axis}"), value, -max, max);
+            }
+        }
+
+        if let Some(rotation) = physics.rotation {
+            // Yaw and roll wrap around, but pitch doesn't
+            self.range(format!("{field}.rotation.pitch"), rotation.pitch, -FRAC_PI_2, FRAC_PI_2);
+            self.finite(format!("{field}.rotation.yaw"), rotation.yaw);
+            self.finite(format!("{field}.rotation.roll"), rotation.roll);
+        }
+
+        if let Some(velocity) = physics.velocity {
+            self.vector(&format!("{field}.velocity"), velocity, max_speed);
+        }
+
+        if let Some(angular_velocity) = physics.angular_velocity {
+            self.vector(&format!("{field}.angular_velocity"), angular_velocity, max_angular_speed);
+        }
+    }
+}
+
+impl DesiredGameState {
+    /// The state that puts the ball and every car back where they were in `packet`
+    ///
+    /// Demolished cars are left alone, and so are boost pads because the packet doesn't say how long they take to respawn
+    pub fn from_packet(packet: &GameTickPacket) -> Self {
+        let cars = packet
+            .game_cars
+            .iter()
+            .enumerate()
+            .filter(|(_, car)| !car.is_demolished)
+            .map(|(index, car)| {
+                let state = DesiredCarState {
+                    physics: Some(car.physics.into()),
+                    boost_amount: Some(car.boost as f32),
+                    ..Default::default()
+                };
+
+                (index, state)
+            })
+            .collect();
+
+        Self {
+            ball: Some(DesiredBallState {
+                physics: Some(packet.game_ball.physics.into()),
+            }),
+            cars,
+            ..Default::default()
+        }
+    }
+
+    /// Check that the state is valid, and that it refers to cars and boost pads that exist
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - The latest packet from the match, if there is one, to check car and boost pad indices against
+    pub fn validate(&self, packet: Option<&GameTickPacket>) -> Result<(), InvalidStateError> {
+        let mut validator = Validator::default();
+
+        if let Some(physics) = self.ball.as_ref().and_then(|ball| ball.physics.as_ref()) {
+            validator.physics(
+                "ball.physics",
+                physics,
+                STANDARD_BALL_MAX_SPEED * CUSTOM_LIMIT_MARGIN,
+                BALL_MAX_ANGULAR_SPEED,
+            );
+        }
+
+        for (index, car) in &self.cars {
+            if let Some(packet) = packet {
+                let count = packet.game_cars.len();
+                if *index >= count {
+                    validator.errors.push(StateError::CarIndex { index: *index, count });
+                }
+            }
+
+            if let Some(physics) = &car.physics {
+                validator.physics(
+                    &format!("cars[{index}].physics"),
+                    physics,
+                    CAR_MAX_SPEED,
+                    CAR_MAX_ANGULAR_SPEED,
+                );
+            }
+
+            if let Some(boost_amount) = car.boost_amount {
+                validator.range(format!("cars[{index}].boost_amount"), boost_amount, 0., MAX_BOOST);
+            }
+        }
+
+        for (index, boost) in &self.boosts {
+            // Packets from the match handler don't include boost pads
+            if let Some(packet) = packet.filter(|packet| !packet.game_boosts.is_empty()) {
+                let count = packet.game_boosts.len();
+                if *index >= count {
+                    validator.errors.push(StateError::BoostIndex { index: *index, count });
+                }
+            }
+
+            if let Some(respawn_time) = boost.respawn_time {
+                validator.range(format!("boosts[{index}].respawn_time"), respawn_time, 0., f32::MAX);
+            }
+        }
+
+        if let Some(game_info) = &self.game_info {
+            if let Some(world_gravity_z) = game_info.world_gravity_z {
+                validator.finite("game_info.world_gravity_z", world_gravity_z);
+            }
+
+            if let Some(game_speed) = game_info.game_speed {
+                validator.range("game_info.game_speed", game_speed, f32::MIN_POSITIVE, MAX_GAME_SPEED);
+            }
+        }
+
+        for (index, command) in self.console_commands.iter().enumerate() {
+            if command.trim().is_empty() {
+                validator.errors.push(StateError::EmptyCommand { index });
+            }
+        }
+
+        if validator.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(InvalidStateError(validator.errors))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::{Ball, BoostPad, Car, GameInfo};
+
+    fn packet(cars: usize, boosts: usize) -> GameTickPacket {
+        let car = Car {
+            team: 0,
+            physics: Physics::default(),
+            name: String::new(),
+            boost: 33,
+            is_demolished: false,
+            has_wheel_contact: true,
+            is_supersonic: false,
+            is_bot: true,
+            score_info: Default::default(),
+        };
+
+        GameTickPacket {
+            game_ball: Ball::default(),
+            game_cars: vec![car; cars],
+            game_boosts: vec![BoostPad::default(); boosts],
+            teams: Vec::new(),
+            game_info: GameInfo::default(),
+        }
+    }
+
+    fn errors(json: &str, packet: Option<&GameTickPacket>) -> Vec<StateError> {
+        let state: DesiredGameState = serde_json::from_str(json).unwrap();
+        state.validate(packet).err().map(|e| e.0).unwrap_or_default()
+    }
+
+    #[test]
+    fn packets_are_valid_states() {
+        let packet = packet(2, 34);
+        assert!(DesiredGameState::from_packet(&packet).validate(Some(&packet)).is_ok());
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        assert!(serde_json::from_str::<DesiredGameState>(r#"{"bal": {}}"#).is_err());
+        assert!(serde_json::from_str::<DesiredGameState>(r#"{"cars": {"0": {"boost": 50}}}"#).is_err());
+    }
+
+    #[test]
+    fn car_indices_are_checked_against_the_packet() {
+        let json = r#"{"cars": {"2": {"boost_amount": 50}}}"#;
+
+        assert!(matches!(
+            errors(json, Some(&packet(2, 0)))[..],
+            [StateError::CarIndex { index: 2, count: 2 }]
+        ));
+        assert!(errors(json, Some(&packet(3, 0))).is_empty());
+        assert!(errors(json, None).is_empty());
+    }
+
+    #[test]
+    fn boost_indices_are_checked_if_the_packet_has_boost_pads() {
+        let json = r#"{"boosts": {"34": {"respawn_time": 1}}}"#;
+
+        assert!(matches!(
+            errors(json, Some(&packet(0, 34)))[..],
+            [StateError::BoostIndex { index: 34, count: 34 }]
+        ));
+        assert!(errors(json, Some(&packet(0, 0))).is_empty());
+    }
+
+    #[test]
+    fn values_have_to_be_finite() {
+        let state = DesiredGameState {
+            game_info: Some(DesiredGameInfoState {
+                world_gravity_z: Some(f32::NAN),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let errors = state.validate(None).unwrap_err().0;
+        assert!(matches!(&errors[..], [StateError::NotFinite { field, .. }] if field == "game_info.world_gravity_z"));
+    }
+
+    #[test]
+    fn values_have_to_be_in_bounds() {
+        let json = r#"{"cars": {"0": {"boost_amount": 101, "physics": {"velocity": {"x": 5000, "y": 0, "z": 0}}}}}"#;
+        let fields = errors(json, None)
+            .into_iter()
+            .map(|error| match error {
+                StateError::OutOfBounds { field, .. } => field,
+                other => panic!("expected only out of bounds errors, got {other:?}"),
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(fields, ["the length of cars[0].physics.velocity", "cars[0].boost_amount"]);
+    }
+
+    #[test]
+    fn custom_maps_and_mutators_have_room() {
+        let json = r#"{"ball": {"physics": {"location": {"x": 9000, "y": -20000, "z": 5000}}}}"#;
+        assert!(errors(json, None).is_empty());
+
+        let json = r#"{"ball": {"physics": {"velocity": {"x": 0, "y": 15000, "z": 0}}}}"#;
+        assert!(errors(json, None).is_empty());
+
+        let json = r#"{"ball": {"physics": {"location": {"x": 409600, "y": 0, "z": 0}}}}"#;
+        assert!(matches!(errors(json, None)[..], [StateError::OutOfBounds { .. }]));
+    }
+
+    #[test]
+    fn console_commands_cant_be_empty() {
+        assert!(matches!(
+            errors(r#"{"console_commands": ["Stat FPS", " "]}"#, None)[..],
+            [StateError::EmptyCommand { index: 1 }]
+        ));
+    }
+}
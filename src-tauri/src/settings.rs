@@ -1,10 +1,14 @@
 use crate::{
-    bot_management::cfg_helper::save_cfg,
+    bot_management::{bot_index, cfg_helper::save_cfg},
     ccprintln,
     config_handles::{load_gui_config, load_gui_config_sync},
     custom_maps::convert_to_path,
     get_config_path,
-    rlbot::parsing::{bot_config_bundle::ScriptConfigBundle, match_settings_config_parser::*},
+    reporter::Reporter,
+    rlbot::parsing::{
+        bot_config_bundle::{BotConfigBundle, ScriptConfigBundle},
+        match_settings_config_parser::*,
+    },
 };
 use configparser::ini::Ini;
 use core::fmt;
@@ -15,8 +19,8 @@ use std::{
     fmt::{Debug, Display, Formatter},
     io,
     str::FromStr,
+    time::Duration,
 };
-use tauri::Window;
 use thiserror::Error;
 use tokio::fs as async_fs;
 
@@ -62,24 +66,27 @@ impl BotFolders {
         Self { files, folders }
     }
 
-    pub fn update_config(&mut self, window: &Window, bfs: Self) -> io::Result<()> {
+    pub fn update_config(&mut self, reporter: &dyn Reporter, bfs: Self) -> io::Result<()> {
         *self = bfs;
 
-        let mut conf = load_gui_config_sync(window);
+        let mut conf = load_gui_config_sync(reporter);
         conf.set("bot_folder_settings", "files", serde_json::to_string(&self.files).ok());
         conf.set("bot_folder_settings", "folders", serde_json::to_string(&self.folders).ok());
 
-        conf.write(get_config_path())
+        conf.write(get_config_path())?;
+        bot_index::folders_changed();
+
+        Ok(())
     }
 
-    pub fn add_folder(&mut self, window: &Window, path: String) -> io::Result<()> {
+    pub fn add_folder(&mut self, reporter: &dyn Reporter, path: String) -> io::Result<()> {
         self.folders.insert(path, BotFolder { visible: true });
-        self.update_config(window, self.clone())
+        self.update_config(reporter, self.clone())
     }
 
-    pub fn add_file(&mut self, window: &Window, path: String) -> io::Result<()> {
+    pub fn add_file(&mut self, reporter: &dyn Reporter, path: String) -> io::Result<()> {
         self.files.insert(path, BotFolder { visible: true });
-        self.update_config(window, self.clone())
+        self.update_config(reporter, self.clone())
     }
 }
 
@@ -108,8 +115,8 @@ pub struct MutatorConfig {
 }
 
 impl MutatorConfig {
-    pub async fn load(window: &Window) -> Self {
-        let conf = load_gui_config(window).await;
+    pub async fn load(reporter: &dyn Reporter) -> Self {
+        let conf = load_gui_config(reporter).await;
 
         let match_length = conf
             .get("mutator_settings", "match_length")
@@ -318,14 +325,37 @@ impl Default for MatchConfig {
     }
 }
 
+impl From<MatchConfig> for MiniMatchConfig {
+    fn from(config: MatchConfig) -> Self {
+        Self {
+            map: config.map,
+            game_mode: config.game_mode,
+            match_behavior: config.match_behavior,
+            skip_replays: config.skip_replays,
+            instant_start: config.instant_start,
+            enable_lockstep: config.enable_lockstep,
+            randomize_map: config.randomize_map,
+            enable_rendering: config.enable_rendering,
+            enable_state_setting: config.enable_state_setting,
+            auto_save_replay: config.auto_save_replay,
+            scripts: config
+                .scripts
+                .into_iter()
+                .map(|script| MiniScriptBundle { path: script.path })
+                .collect(),
+            mutators: config.mutators,
+        }
+    }
+}
+
 impl MatchConfig {
-    async fn trimmed_to_bundles(window: &Window, trimmed_bundles: Vec<String>) -> Vec<ScriptConfigBundle> {
+    async fn trimmed_to_bundles(reporter: &dyn Reporter, trimmed_bundles: Vec<String>) -> Vec<ScriptConfigBundle> {
         join_all(trimmed_bundles.into_iter().map(ScriptConfigBundle::minimal_from_path))
             .await
             .into_iter()
             .flat_map(|f| {
                 if let Err(e) = &f {
-                    ccprintln!(window, "Error loading bot config: {e}");
+                    ccprintln!(reporter, "Error loading bot config: {e}");
                 }
 
                 f
@@ -333,8 +363,29 @@ impl MatchConfig {
             .collect()
     }
 
-    pub async fn load(window: &Window) -> Self {
-        let conf = load_gui_config(window).await;
+    /// Load the full script configs for match settings that only have the paths of the scripts
+    pub async fn from_mini(reporter: &dyn Reporter, config: MiniMatchConfig) -> Self {
+        let scripts =
+            Self::trimmed_to_bundles(reporter, config.scripts.into_iter().map(|script| script.path).collect()).await;
+
+        Self {
+            map: config.map,
+            game_mode: config.game_mode,
+            match_behavior: config.match_behavior,
+            skip_replays: config.skip_replays,
+            instant_start: config.instant_start,
+            enable_lockstep: config.enable_lockstep,
+            randomize_map: config.randomize_map,
+            enable_rendering: config.enable_rendering,
+            enable_state_setting: config.enable_state_setting,
+            auto_save_replay: config.auto_save_replay,
+            scripts,
+            mutators: config.mutators,
+        }
+    }
+
+    pub async fn load(reporter: &dyn Reporter) -> Self {
+        let conf = load_gui_config(reporter).await;
 
         let map = conf
             .get("match_settings", "map")
@@ -384,7 +435,7 @@ impl MatchConfig {
             .flatten()
             .unwrap_or_default();
         let scripts = Self::trimmed_to_bundles(
-            window,
+            reporter,
             serde_json::from_str(&conf.get("match_settings", "scripts").unwrap_or_else(|| "[]".to_owned()))
                 .unwrap_or_default(),
         )
@@ -402,7 +453,7 @@ impl MatchConfig {
             enable_state_setting,
             auto_save_replay,
             scripts,
-            mutators: MutatorConfig::load(window).await,
+            mutators: MutatorConfig::load(reporter).await,
         }
     }
 
@@ -425,12 +476,12 @@ impl MatchConfig {
         self.mutators.save_config(conf);
     }
 
-    pub async fn save_config(&self, window: &Window) {
-        let mut conf = load_gui_config(window).await;
+    pub async fn save_config(&self, reporter: &dyn Reporter) {
+        let mut conf = load_gui_config(reporter).await;
         self.save_to_config(&mut conf);
 
         if let Err(e) = async_fs::write(get_config_path(), conf.writes()).await {
-            ccprintln!(window, "Error writing config file: {e}");
+            ccprintln!(reporter, "Error writing config file: {e}");
         }
     }
 }
@@ -530,8 +581,8 @@ pub struct LauncherConfig {
 }
 
 impl LauncherConfig {
-    pub async fn load(window: &Window) -> Self {
-        let config = load_gui_config(window).await;
+    pub async fn load(reporter: &dyn Reporter) -> Self {
+        let config = load_gui_config(reporter).await;
 
         Self {
             preferred_launcher: config
@@ -545,8 +596,8 @@ impl LauncherConfig {
         }
     }
 
-    pub async fn write_to_file(self, window: &Window) {
-        let mut config = load_gui_config(window).await;
+    pub async fn write_to_file(self, reporter: &dyn Reporter) {
+        let mut config = load_gui_config(reporter).await;
 
         config.set("launcher_settings", "preferred_launcher", Some(self.preferred_launcher));
         config.set(
@@ -557,12 +608,157 @@ impl LauncherConfig {
         config.set("launcher_settings", "rocket_league_exe_path", self.rocket_league_exe_path);
 
         if let Err(e) = async_fs::write(get_config_path(), config.writes()).await {
-            ccprintln!(window, "Error writing config file: {e}");
+            ccprintln!(reporter, "Error writing config file: {e}");
+        }
+    }
+}
+
+/// What the match handler supervisor should do when the match handler exits on it's own
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RestartPolicy {
+    /// Leave the match handler down
+    Never,
+    /// Restart the match handler if it exited with a non-zero exit code, or was killed
+    #[default]
+    OnFailure,
+    /// Restart the match handler no matter how it exited
+    Always,
+}
+
+impl RestartPolicy {
+    pub const fn should_restart(self, exit_code: Option<i32>) -> bool {
+        match self {
+            Self::Never => false,
+            Self::OnFailure => !matches!(exit_code, Some(0)),
+            Self::Always => true,
+        }
+    }
+}
+
+impl fmt::Display for RestartPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Never => "never",
+                Self::OnFailure => "on_failure",
+                Self::Always => "always",
+            }
+        )
+    }
+}
+
+impl FromStr for RestartPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "never" => Ok(Self::Never),
+            "on_failure" => Ok(Self::OnFailure),
+            "always" => Ok(Self::Always),
+            _ => Err(format!("Unknown restart policy: {s}")),
         }
     }
 }
 
-#[derive(Serialize_repr, Deserialize_repr, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct MatchHandlerConfig {
+    /// How many seconds to wait for the match handler to say that it's ready before giving up
+    pub startup_timeout: u64,
+    pub restart_policy: RestartPolicy,
+    /// How many times in a row the match handler can be restarted before the supervisor gives up
+    pub max_restarts: u32,
+    /// How many seconds to wait before the first restart, doubled after each failed restart
+    pub restart_backoff: u64,
+    /// The longest that the supervisor will wait between restarts, in seconds
+    pub max_restart_backoff: u64,
+}
+
+impl Default for MatchHandlerConfig {
+    fn default() -> Self {
+        Self {
+            startup_timeout: 30,
+            restart_policy: RestartPolicy::default(),
+            max_restarts: 3,
+            restart_backoff: 1,
+            max_restart_backoff: 30,
+        }
+    }
+}
+
+impl MatchHandlerConfig {
+    pub fn load_from_conf(conf: &Ini) -> Self {
+        let default = Self::default();
+
+        Self {
+            startup_timeout: conf
+                .getuint("match_handler", "startup_timeout")
+                .ok()
+                .flatten()
+                .unwrap_or(default.startup_timeout),
+            restart_policy: conf
+                .get("match_handler", "restart_policy")
+                .and_then(|policy| policy.parse().ok())
+                .unwrap_or(default.restart_policy),
+            max_restarts: conf
+                .getuint("match_handler", "max_restarts")
+                .ok()
+                .flatten()
+                .and_then(|max_restarts| u32::try_from(max_restarts).ok())
+                .unwrap_or(default.max_restarts),
+            restart_backoff: conf
+                .getuint("match_handler", "restart_backoff")
+                .ok()
+                .flatten()
+                .unwrap_or(default.restart_backoff),
+            max_restart_backoff: conf
+                .getuint("match_handler", "max_restart_backoff")
+                .ok()
+                .flatten()
+                .unwrap_or(default.max_restart_backoff),
+        }
+    }
+
+    pub async fn load(reporter: &dyn Reporter) -> Self {
+        Self::load_from_conf(&load_gui_config(reporter).await)
+    }
+
+    pub const fn startup_timeout(&self) -> Duration {
+        Duration::from_secs(self.startup_timeout)
+    }
+
+    /// How long to wait before restarting the match handler, given how many restarts in a row have already happened
+    pub fn restart_delay(&self, consecutive_restarts: u32) -> Duration {
+        let backoff = self
+            .restart_backoff
+            .saturating_mul(2u64.saturating_pow(consecutive_restarts))
+            .min(self.max_restart_backoff);
+
+        Duration::from_secs(backoff)
+    }
+
+    pub async fn write_to_file(self, reporter: &dyn Reporter) {
+        let mut config = load_gui_config(reporter).await;
+
+        config.set("match_handler", "startup_timeout", Some(self.startup_timeout.to_string()));
+        config.set("match_handler", "restart_policy", Some(self.restart_policy.to_string()));
+        config.set("match_handler", "max_restarts", Some(self.max_restarts.to_string()));
+        config.set("match_handler", "restart_backoff", Some(self.restart_backoff.to_string()));
+        config.set(
+            "match_handler",
+            "max_restart_backoff",
+            Some(self.max_restart_backoff.to_string()),
+        );
+
+        if let Err(e) = async_fs::write(get_config_path(), config.writes()).await {
+            ccprintln!(reporter, "Error writing config file: {e}");
+        }
+    }
+}
+
+#[derive(Serialize_repr, Deserialize_repr, Clone, Debug, PartialEq, Eq)]
 #[repr(u8)]
 pub enum Team {
     Blue,
@@ -576,23 +772,94 @@ pub struct TeamBotBundle {
     pub skill: f32,
     pub runnable_type: String,
     pub path: Option<String>,
+    /// If the bot can be started before Rocket League has finished loading the match
+    #[serde(default)]
+    pub supports_early_start: bool,
+    /// The most ticks per second that the bot wants to get, if it has a limit
+    pub maximum_tick_rate_preference: Option<u32>,
+    /// Everything in the `[Bot Parameters]` section of the bot's config
+    #[serde(default)]
+    pub bot_parameters: HashMap<String, String>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+impl TeamBotBundle {
+    /// Put a bot on a team, the same way that the GUI does when starting a match
+    pub fn from_bundle(bundle: &BotConfigBundle, team: Team) -> Self {
+        Self {
+            name: bundle.name.clone(),
+            team,
+            skill: bundle.skill.unwrap_or(1.),
+            runnable_type: bundle.runnable_type.clone(),
+            path: Some(bundle.path.clone()),
+            supports_early_start: bundle.supports_early_start,
+            maximum_tick_rate_preference: bundle.maximum_tick_rate_preference,
+            bot_parameters: bundle.bot_parameters.clone(),
+        }
+    }
+}
+
+/// Bots in the same match that want different maximum tick rates, when RLBot can only run a match at one rate
+#[derive(Serialize, Clone, Debug)]
+pub struct TickRateConflict {
+    /// The lowest preference, which is the only rate that every bot with a preference is happy with
+    pub lowest: u32,
+    /// The name and preference of every bot that has one
+    pub preferences: Vec<(String, u32)>,
+}
+
+impl TickRateConflict {
+    /// Check if the bots in a match want different maximum tick rates
+    ///
+    /// # Arguments
+    ///
+    /// * `bots` - The bots in the match
+    pub fn find(bots: &[TeamBotBundle]) -> Option<Self> {
+        let preferences = bots
+            .iter()
+            .filter_map(|bot| Some((bot.name.clone(), bot.maximum_tick_rate_preference?)))
+            .collect::<Vec<_>>();
+        let lowest = preferences.iter().map(|(_, rate)| *rate).min()?;
+
+        if preferences.iter().all(|(_, rate)| *rate == lowest) {
+            return None;
+        }
+
+        Some(Self { lowest, preferences })
+    }
+}
+
+impl Display for TickRateConflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let preferences = self
+            .preferences
+            .iter()
+            .map(|(name, rate)| format!("{name} ({rate})"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        write!(
+            f,
+            "The bots in this match want different maximum tick rates ({preferences}), but only {} suits all of them",
+            self.lowest
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
 pub struct Vec3D {
     pub x: f32,
     pub y: f32,
     pub z: f32,
 }
 
-#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
 pub struct Rotation {
     pub pitch: f32,
     pub yaw: f32,
     pub roll: f32,
 }
 
-#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
 pub struct Physics {
     pub location: Vec3D,
     pub velocity: Vec3D,
@@ -600,26 +867,109 @@ pub struct Physics {
     pub rotation: Rotation,
 }
 
-#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
 pub struct Ball {
     pub physics: Physics,
 }
 
-#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+pub struct ScoreInfo {
+    pub score: i32,
+    pub goals: i32,
+    pub own_goals: i32,
+    pub assists: i32,
+    pub saves: i32,
+    pub shots: i32,
+    pub demolitions: i32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Car {
     pub team: u8,
     pub physics: Physics,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub boost: i32,
+    #[serde(default)]
+    pub is_demolished: bool,
+    #[serde(default)]
+    pub has_wheel_contact: bool,
+    #[serde(default)]
+    pub is_supersonic: bool,
+    #[serde(default)]
+    pub is_bot: bool,
+    #[serde(default)]
+    pub score_info: ScoreInfo,
 }
 
-#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+pub struct BoostPad {
+    pub is_active: bool,
+    /// How long the pad has been inactive for, in seconds
+    pub timer: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+pub struct TeamInfo {
+    pub team_index: i32,
+    pub score: i32,
+}
+
+/// What's currently happening in the match
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchPhase {
+    /// Between rounds, such as during a goal replay or the countdown before a kickoff
+    #[default]
+    Inactive,
+    /// The countdown has finished, but nobody has touched the ball yet
+    Kickoff,
+    Active,
+    Ended,
+}
+
+impl MatchPhase {
+    pub const fn new(is_round_active: bool, is_kickoff_pause: bool, is_match_ended: bool) -> Self {
+        if is_match_ended {
+            Self::Ended
+        } else if is_kickoff_pause {
+            Self::Kickoff
+        } else if is_round_active {
+            Self::Active
+        } else {
+            Self::Inactive
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
 pub struct GameInfo {
     pub seconds_elapsed: f32,
+    #[serde(default)]
+    pub game_time_remaining: f32,
+    #[serde(default)]
+    pub is_overtime: bool,
+    #[serde(default)]
+    pub is_unlimited_time: bool,
+    #[serde(default)]
+    pub phase: MatchPhase,
+    #[serde(default)]
+    pub world_gravity_z: f32,
+    #[serde(default)]
+    pub game_speed: f32,
+    #[serde(default)]
+    pub frame_num: i32,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct GameTickPacket {
     pub game_ball: Ball,
     pub game_cars: Vec<Car>,
+    #[serde(default)]
+    pub game_boosts: Vec<BoostPad>,
+    #[serde(default)]
+    pub teams: Vec<TeamInfo>,
     pub game_info: GameInfo,
 }
 
@@ -651,8 +1001,8 @@ pub struct StoryConfig {
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ScoreResult {
-    team_index: Team,
-    score: usize,
+    pub team_index: Team,
+    pub score: usize,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -703,21 +1053,21 @@ impl StoryState {
         }
     }
 
-    pub fn save_sync(&self, window: &Window) {
-        let mut conf = load_gui_config_sync(window);
+    pub fn save_sync(&self, reporter: &dyn Reporter) {
+        let mut conf = load_gui_config_sync(reporter);
         conf.set("story_mode", "save_state", serde_json::to_string(self).ok());
 
         if let Err(e) = conf.write(get_config_path()) {
-            ccprintln!(window, "Error writing config: {e}");
+            ccprintln!(reporter, "Error writing config: {e}");
         }
     }
 
-    pub async fn save(&self, window: &Window) {
-        let mut conf = load_gui_config(window).await;
+    pub async fn save(&self, reporter: &dyn Reporter) {
+        let mut conf = load_gui_config(reporter).await;
         conf.set("story_mode", "save_state", serde_json::to_string(self).ok());
 
         if let Err(e) = save_cfg(&conf, get_config_path()).await {
-            ccprintln!(window, "Error writing config: {e}");
+            ccprintln!(reporter, "Error writing config: {e}");
         }
     }
 
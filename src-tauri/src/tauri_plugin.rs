@@ -1,6 +1,5 @@
-use crate::{commands::shut_down_match_handler, InternalConsoleError, CONSOLE_TEXT_OUT_QUEUE};
+use crate::{match_handler::supervisor, InternalConsoleError, CONSOLE_TEXT_OUT_QUEUE};
 use tauri::{
-    async_runtime::block_on as tauri_block_on,
     plugin::{Builder as PluginBuilder, TauriPlugin},
     RunEvent, Runtime,
 };
@@ -21,7 +20,7 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
     PluginBuilder::new("eventhandler")
         .on_event(|_, event| {
             if let RunEvent::Exit = event {
-                if let Err(e) = tauri_block_on(async { shut_down_match_handler().await }) {
+                if let Err(e) = supervisor::shut_down() {
                     if let Err(e) = log_text(e.to_string()) {
                         println!("{e}");
                     }
@@ -0,0 +1,495 @@
+pub(crate) mod pairing;
+
+use crate::{
+    automation::{Automation, AutomationError, AutomationSlot},
+    ccprintln,
+    commands::start_match_helper,
+    content::{read_json, write_json, ContentError, ContentFolder},
+    impl_serialize_from_display,
+    match_history::{self, MatchRecord},
+    recordings::unix_time,
+    reporter::Reporter,
+    rlbot::parsing::bot_config_bundle::BotConfigBundle,
+    settings::{MiniMatchConfig, Team, TeamBotBundle},
+    TOURNAMENT_RUNNER,
+};
+use pairing::Standing;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs, io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+use tauri::async_runtime::block_on as tauri_block_on;
+use thiserror::Error;
+
+pub const TOURNAMENTS: ContentFolder = ContentFolder {
+    folder: "tournaments",
+    extension: "json",
+    kind: "tournament",
+};
+pub const TOURNAMENT_UPDATED_SIGNAL: &str = "tournament-updated";
+
+/// How long to wait after a match ends before starting the next one, so that the end of the match can be seen
+const BETWEEN_MATCHES: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Error)]
+pub enum TournamentError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Content(#[from] ContentError),
+    #[error("A tournament needs at least 2 bots, but {0} were given")]
+    NotEnoughEntrants(usize),
+    #[error("The tournament \"{0}\" is already running")]
+    AlreadyRunning(String),
+    #[error("No tournament is running")]
+    NotRunning,
+    #[error("The tournament \"{0}\" is already over")]
+    Finished(String),
+    #[error(transparent)]
+    Automation(#[from] AutomationError),
+    #[error("Mutex {0} was poisoned")]
+    Poisoned(&'static str),
+}
+
+impl_serialize_from_display!(TournamentError);
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TournamentFormat {
+    /// Everyone plays everyone else once
+    RoundRobin,
+    /// Entrants are knocked out after their first loss
+    SingleElimination,
+    /// Entrants are knocked out after their second loss
+    DoubleElimination,
+    /// Entrants with similar records play each other for a fixed number of rounds
+    Swiss {
+        /// Defaults to enough rounds for a single undefeated winner
+        rounds: Option<u32>,
+    },
+}
+
+impl TournamentFormat {
+    /// How many losses knock an entrant out, if any
+    pub const fn max_losses(self) -> Option<u32> {
+        match self {
+            Self::SingleElimination => Some(1),
+            Self::DoubleElimination => Some(2),
+            Self::RoundRobin | Self::Swiss { .. } => None,
+        }
+    }
+
+    /// Enough rounds for there to be a single undefeated entrant
+    pub fn default_swiss_rounds(entrants: usize) -> u32 {
+        entrants.next_power_of_two().trailing_zeros().max(1)
+    }
+}
+
+/// A bot that's taking part in a tournament
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Entrant {
+    pub name: String,
+    pub runnable_type: String,
+    pub skill: f32,
+    pub path: Option<String>,
+    #[serde(default)]
+    pub supports_early_start: bool,
+    pub maximum_tick_rate_preference: Option<u32>,
+    #[serde(default)]
+    pub bot_parameters: HashMap<String, String>,
+}
+
+impl Entrant {
+    pub fn from_bundle(bundle: &BotConfigBundle) -> Self {
+        let TeamBotBundle {
+            name,
+            skill,
+            runnable_type,
+            path,
+            supports_early_start,
+            maximum_tick_rate_preference,
+            bot_parameters,
+            ..
+        } = TeamBotBundle::from_bundle(bundle, Team::Blue);
+
+        Self {
+            name,
+            runnable_type,
+            skill,
+            path,
+            supports_early_start,
+            maximum_tick_rate_preference,
+            bot_parameters,
+        }
+    }
+
+    pub fn on_team(&self, team: Team) -> TeamBotBundle {
+        TeamBotBundle {
+            name: self.name.clone(),
+            team,
+            skill: self.skill,
+            runnable_type: self.runnable_type.clone(),
+            path: self.path.clone(),
+            supports_early_start: self.supports_early_start,
+            maximum_tick_rate_preference: self.maximum_tick_rate_preference,
+            bot_parameters: self.bot_parameters.clone(),
+        }
+    }
+}
+
+/// How a tournament match went
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TournamentResult {
+    pub blue_goals: usize,
+    pub orange_goals: usize,
+    /// The index of the entrant that won, or `None` for a draw
+    pub winner: Option<usize>,
+    /// The id of the match in the match history
+    pub record_id: Option<String>,
+}
+
+impl TournamentResult {
+    fn from_record(record: &MatchRecord, blue: usize, orange: usize) -> Self {
+        let goals = |team: Team| {
+            record
+                .score
+                .iter()
+                .find(|score| score.team_index == team)
+                .map_or(0, |score| score.score)
+        };
+
+        Self {
+            blue_goals: goals(Team::Blue),
+            orange_goals: goals(Team::Orange),
+            winner: record.winner.as_ref().map(|team| match team {
+                Team::Blue => blue,
+                Team::Orange => orange,
+            }),
+            record_id: Some(record.id.clone()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TournamentMatch {
+    /// Starts at 0
+    pub round: u32,
+    /// The index of the entrant on the blue team
+    pub blue: usize,
+    /// The index of the entrant on the orange team, or `None` if the blue entrant has a bye
+    pub orange: Option<usize>,
+    pub result: Option<TournamentResult>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Tournament {
+    pub name: String,
+    pub format: TournamentFormat,
+    /// The bots in the tournament, in order of their seed
+    pub entrants: Vec<Entrant>,
+    /// The settings that every match is started with
+    pub match_settings: MiniMatchConfig,
+    /// Every match that has been scheduled so far, including the ones that are still to be played
+    pub matches: Vec<TournamentMatch>,
+    pub finished: bool,
+    /// When the tournament was created, in seconds since the Unix epoch
+    pub created_at: u64,
+}
+
+impl Tournament {
+    /// How many rounds have been scheduled
+    pub fn rounds_played(&self) -> u32 {
+        self.matches.last().map_or(0, |game| game.round + 1)
+    }
+
+    fn next_match_index(&self) -> Option<usize> {
+        self.matches.iter().position(|game| game.result.is_none())
+    }
+
+    /// Schedule the next round, with byes being given their result straight away
+    ///
+    /// Returns false if there are no more rounds, meaning that the tournament is over
+    fn schedule_next_round(&mut self) -> bool {
+        let pairs = pairing::next_round(self);
+        if pairs.is_empty() {
+            return false;
+        }
+
+        let round = self.rounds_played();
+        self.matches.extend(pairs.into_iter().map(|(blue, orange)| TournamentMatch {
+            round,
+            blue,
+            orange,
+            result: orange.is_none().then_some(TournamentResult {
+                blue_goals: 0,
+                orange_goals: 0,
+                winner: Some(blue),
+                record_id: None,
+            }),
+        }));
+
+        true
+    }
+}
+
+/// A tournament and its standings, as shown in the GUI
+#[derive(Serialize, Clone, Debug)]
+pub struct TournamentView {
+    #[serde(flatten)]
+    pub tournament: Tournament,
+    pub standings: Vec<Standing>,
+    pub running: bool,
+}
+
+impl TournamentView {
+    fn new(tournament: Tournament) -> Self {
+        let running = current_tournament().ok().flatten().as_deref() == Some(tournament.name.as_str());
+
+        Self {
+            standings: pairing::standings(&tournament),
+            tournament,
+            running,
+        }
+    }
+}
+
+fn read_tournament(name: &str) -> Result<Tournament, TournamentError> {
+    Ok(read_json(&TOURNAMENTS.existing_item_path(name)?)?)
+}
+
+fn write_tournament(tournament: &Tournament) -> Result<(), TournamentError> {
+    Ok(write_json(&TOURNAMENTS.item_path(&tournament.name)?, tournament)?)
+}
+
+/// Create a new tournament, with the first round already scheduled
+///
+/// # Arguments
+///
+/// * `name` - The name of the tournament, which can't already be in use
+/// * `format` - How entrants are paired up and knocked out
+/// * `bots` - The bots in the tournament, in order of their seed
+/// * `match_settings` - The settings that every match is started with
+pub fn create_tournament(
+    name: &str,
+    format: TournamentFormat,
+    bots: &[BotConfigBundle],
+    match_settings: MiniMatchConfig,
+) -> Result<TournamentView, TournamentError> {
+    TOURNAMENTS.new_item_path(name)?;
+
+    if bots.len() < 2 {
+        return Err(TournamentError::NotEnoughEntrants(bots.len()));
+    }
+
+    let mut tournament = Tournament {
+        name: name.trim().to_owned(),
+        format,
+        entrants: bots.iter().map(Entrant::from_bundle).collect(),
+        match_settings,
+        matches: Vec::new(),
+        finished: false,
+        created_at: unix_time(),
+    };
+    tournament.schedule_next_round();
+    write_tournament(&tournament)?;
+
+    Ok(TournamentView::new(tournament))
+}
+
+pub fn get_tournament(name: &str) -> Result<TournamentView, TournamentError> {
+    Ok(TournamentView::new(read_tournament(name)?))
+}
+
+/// Get all of the saved tournaments, newest first
+///
+/// # Arguments
+///
+/// * `reporter` - Where to report the tournaments that couldn't be read
+pub fn list_tournaments(reporter: &dyn Reporter) -> Result<Vec<TournamentView>, TournamentError> {
+    let mut tournaments = TOURNAMENTS.list(reporter, read_json::<Tournament>)?;
+    tournaments.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+    Ok(tournaments.into_iter().map(TournamentView::new).collect())
+}
+
+pub fn delete_tournament(name: &str) -> Result<(), TournamentError> {
+    if current_tournament()?.as_deref() == Some(name.trim()) {
+        return Err(TournamentError::AlreadyRunning(name.to_owned()));
+    }
+
+    fs::remove_file(TOURNAMENTS.existing_item_path(name)?)?;
+
+    Ok(())
+}
+
+/// Plays the matches of a tournament one after another from a background thread
+pub struct TournamentRunner {
+    name: String,
+    running: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+fn emit_update(reporter: &dyn Reporter, tournament: &Tournament, running: bool) {
+    let view = TournamentView {
+        standings: pairing::standings(tournament),
+        tournament: tournament.clone(),
+        running,
+    };
+
+    if let Err(e) = reporter.emit(TOURNAMENT_UPDATED_SIGNAL, view) {
+        ccprintln!(reporter, "Failed to emit {TOURNAMENT_UPDATED_SIGNAL}: {e}");
+    }
+}
+
+fn save(reporter: &dyn Reporter, tournament: &Tournament) {
+    if let Err(e) = write_tournament(tournament) {
+        ccprintln!(reporter, "Couldn't save tournament \"{}\": {e}", tournament.name);
+    }
+
+    emit_update(reporter, tournament, !tournament.finished);
+}
+
+/// Play the rest of the tournament, until it's over, stopped, or a match doesn't finish
+fn run(reporter: &dyn Reporter, mut tournament: Tournament, running: &AtomicBool, use_pipe: bool) {
+    while running.load(Ordering::Relaxed) {
+        let Some(index) = tournament.next_match_index() else {
+            if tournament.schedule_next_round() {
+                save(reporter, &tournament);
+                continue;
+            }
+
+            tournament.finished = true;
+            save(reporter, &tournament);
+
+            if let Some(winner) = pairing::standings(&tournament).first() {
+                ccprintln!(reporter, "{} won the tournament \"{}\"!", winner.name, tournament.name);
+            }
+
+            return;
+        };
+
+        let game = &tournament.matches[index];
+        let (blue, Some(orange)) = (game.blue, game.orange) else {
+            // Byes always get their result when they're scheduled
+            continue;
+        };
+
+        ccprintln!(
+            reporter,
+            "Tournament \"{}\", round {}: {} vs {}",
+            tournament.name,
+            game.round + 1,
+            tournament.entrants[blue].name,
+            tournament.entrants[orange].name
+        );
+
+        let bots = vec![
+            tournament.entrants[blue].on_team(Team::Blue),
+            tournament.entrants[orange].on_team(Team::Orange),
+        ];
+
+        if let Err(e) = tauri_block_on(start_match_helper(
+            reporter,
+            bots,
+            tournament.match_settings.clone(),
+            use_pipe,
+            false,
+        )) {
+            ccprintln!(reporter, "Couldn't start the tournament match, stopping the tournament: {e}");
+            break;
+        }
+
+        let record = match match_history::await_match_result() {
+            Ok(Some(record)) if record.completed => record,
+            Ok(_) => {
+                ccprintln(
+                    reporter,
+                    "The tournament match didn't finish, so the tournament has been stopped",
+                );
+                break;
+            }
+            Err(e) => {
+                ccprintln!(reporter, "Couldn't get the result of the tournament match: {e}");
+                break;
+            }
+        };
+
+        let result = TournamentResult::from_record(&record, blue, orange);
+        if result.winner.is_none() && tournament.format.max_losses().is_some() {
+            ccprintln(reporter, "The match was a draw, so it will be replayed");
+        } else {
+            tournament.matches[index].result = Some(result);
+            save(reporter, &tournament);
+        }
+
+        thread::sleep(BETWEEN_MATCHES);
+    }
+
+    ccprintln!(reporter, "Tournament \"{}\" stopped", tournament.name);
+    emit_update(reporter, &tournament, false);
+}
+
+/// Start or continue playing a tournament from where it left off
+///
+/// # Arguments
+///
+/// * `reporter` - Where to send console output and events, like the GUI
+/// * `name` - The name of the tournament
+/// * `use_pipe` - Whether or not to capture the output of the match handler
+pub fn start_tournament(reporter: Box<dyn Reporter>, name: &str, use_pipe: bool) -> Result<(), TournamentError> {
+    let mut runner = TOURNAMENT_RUNNER
+        .lock()
+        .map_err(|_| TournamentError::Poisoned("TOURNAMENT_RUNNER"))?;
+
+    let tournament = read_tournament(name)?;
+    if tournament.finished {
+        return Err(TournamentError::Finished(tournament.name));
+    }
+
+    let slot = AutomationSlot::reserve(Automation::Tournament(tournament.name.clone()))?;
+    let running = Arc::new(AtomicBool::new(true));
+    let thread_running = running.clone();
+    let name = tournament.name.clone();
+    let thread = thread::spawn(move || {
+        run(&*reporter, tournament, &thread_running, use_pipe);
+        drop(slot);
+    });
+
+    *runner = Some(TournamentRunner { name, running, thread });
+
+    Ok(())
+}
+
+/// Stop the tournament after the current match has finished
+///
+/// Returns the name of the tournament
+pub fn stop_tournament() -> Result<String, TournamentError> {
+    let runner = TOURNAMENT_RUNNER
+        .lock()
+        .map_err(|_| TournamentError::Poisoned("TOURNAMENT_RUNNER"))?;
+
+    let runner = runner
+        .as_ref()
+        .filter(|runner| !runner.thread.is_finished())
+        .ok_or(TournamentError::NotRunning)?;
+    runner.running.store(false, Ordering::Relaxed);
+
+    Ok(runner.name.clone())
+}
+
+/// Get the name of the tournament that's currently being played, if there is one
+pub fn current_tournament() -> Result<Option<String>, TournamentError> {
+    Ok(TOURNAMENT_RUNNER
+        .lock()
+        .map_err(|_| TournamentError::Poisoned("TOURNAMENT_RUNNER"))?
+        .as_ref()
+        .filter(|runner| !runner.thread.is_finished())
+        .map(|runner| runner.name.clone()))
+}
@@ -0,0 +1,354 @@
+use super::{Tournament, TournamentFormat};
+use serde::Serialize;
+use std::{cmp::Ordering, collections::HashSet};
+
+/// How an entrant is doing in a tournament
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct Standing {
+    /// The index of the entrant, which is also their seed
+    pub entrant: usize,
+    pub name: String,
+    pub played: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    pub byes: u32,
+    pub goals_for: usize,
+    pub goals_against: usize,
+    /// Wins are worth 1 point and draws are worth half a point, with byes counting as wins in Swiss
+    pub points: f32,
+    /// If the entrant has been knocked out of an elimination tournament
+    pub eliminated: bool,
+}
+
+impl Standing {
+    fn goal_difference(&self) -> i64 {
+        self.goals_for as i64 - self.goals_against as i64
+    }
+
+    /// Better standings come first
+    fn rank(&self, other: &Self) -> Ordering {
+        self.eliminated
+            .cmp(&other.eliminated)
+            .then_with(|| other.points.total_cmp(&self.points))
+            .then_with(|| other.goal_difference().cmp(&self.goal_difference()))
+            .then_with(|| self.entrant.cmp(&other.entrant))
+    }
+}
+
+/// Tally up every finished match
+fn tally(tournament: &Tournament) -> Vec<Standing> {
+    let mut standings = tournament
+        .entrants
+        .iter()
+        .enumerate()
+        .map(|(entrant, info)| Standing {
+            entrant,
+            name: info.name.clone(),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+
+    for game in &tournament.matches {
+        let Some(result) = &game.result else {
+            continue;
+        };
+
+        let Some(orange) = game.orange else {
+            standings[game.blue].byes += 1;
+            continue;
+        };
+
+        for (entrant, goals_for, goals_against) in [
+            (game.blue, result.blue_goals, result.orange_goals),
+            (orange, result.orange_goals, result.blue_goals),
+        ] {
+            let standing = &mut standings[entrant];
+            standing.played += 1;
+            standing.goals_for += goals_for;
+            standing.goals_against += goals_against;
+
+            match result.winner {
+                Some(winner) if winner == entrant => standing.wins += 1,
+                Some(_) => standing.losses += 1,
+                None => standing.draws += 1,
+            }
+        }
+    }
+
+    let max_losses = tournament.format.max_losses();
+    let byes_count = matches!(tournament.format, TournamentFormat::Swiss { .. });
+
+    for standing in &mut standings {
+        standing.points = standing.wins as f32 + standing.draws as f32 / 2.;
+        if byes_count {
+            standing.points += standing.byes as f32;
+        }
+
+        standing.eliminated = max_losses.map_or(false, |max_losses| standing.losses >= max_losses);
+    }
+
+    standings
+}
+
+/// Get the current standings of a tournament, from first to last place
+pub fn standings(tournament: &Tournament) -> Vec<Standing> {
+    let mut standings = tally(tournament);
+    standings.sort_by(Standing::rank);
+    standings
+}
+
+/// Pair up the first entrant with the last, the second with the second to last, and so on
+///
+/// If there's an odd number of entrants, the lowest seeded one that hasn't had a bye yet gets one
+fn pair_by_seed(mut group: Vec<usize>, standings: &[Standing]) -> Vec<(usize, Option<usize>)> {
+    let mut pairs = Vec::with_capacity(group.len() / 2 + 1);
+
+    if group.len() % 2 == 1 {
+        let bye_index = group
+            .iter()
+            .rposition(|&entrant| standings[entrant].byes == 0)
+            .unwrap_or(group.len() - 1);
+        pairs.push((group.remove(bye_index), None));
+    }
+
+    while group.len() >= 2 {
+        let blue = group.remove(0);
+        let orange = group.pop().unwrap();
+        pairs.push((blue, Some(orange)));
+    }
+
+    pairs
+}
+
+/// Play everyone against everyone else once, using the circle method so that nobody plays twice in a round
+fn round_robin(tournament: &Tournament, round: u32) -> Vec<(usize, Option<usize>)> {
+    let count = tournament.entrants.len();
+    // An odd number of entrants gets a placeholder, and whoever is paired with it sits the round out
+    let slots = count + count % 2;
+    if round as usize >= slots - 1 {
+        return Vec::new();
+    }
+
+    // The first slot stays put while the rest rotate around it
+    let mut order = (0..slots).collect::<Vec<_>>();
+    order[1..].rotate_right(round as usize % (slots - 1));
+
+    (0..slots / 2)
+        .map(|i| (order[i], order[slots - 1 - i]))
+        .filter(|&(blue, orange)| blue < count && orange < count)
+        .map(|(blue, orange)| (blue, Some(orange)))
+        .collect()
+}
+
+/// Pair entrants with the same number of losses against each other until only one entrant is left
+fn elimination(tournament: &Tournament, max_losses: u32) -> Vec<(usize, Option<usize>)> {
+    let standings = tally(tournament);
+    let alive = standings
+        .iter()
+        .filter(|standing| !standing.eliminated)
+        .map(|standing| standing.entrant)
+        .collect::<Vec<_>>();
+
+    match alive.len() {
+        0 | 1 => return Vec::new(),
+        // The final, which in double elimination is between the winners and losers brackets
+        2 => return vec![(alive[0], Some(alive[1]))],
+        _ => {}
+    }
+
+    (0..max_losses)
+        .flat_map(|losses| {
+            let group = alive
+                .iter()
+                .copied()
+                .filter(|&entrant| standings[entrant].losses == losses)
+                .collect();
+            pair_by_seed(group, &standings)
+        })
+        .collect()
+}
+
+/// Pair entrants with similar records, avoiding rematches where possible
+fn swiss(tournament: &Tournament, rounds: u32, round: u32) -> Vec<(usize, Option<usize>)> {
+    if round >= rounds {
+        return Vec::new();
+    }
+
+    let standings = tally(tournament);
+    let mut order = standings.clone();
+    order.sort_by(Standing::rank);
+    let mut order = order.into_iter().map(|standing| standing.entrant).collect::<Vec<_>>();
+
+    let played = tournament
+        .matches
+        .iter()
+        .filter_map(|game| game.orange.map(|orange| (game.blue.min(orange), game.blue.max(orange))))
+        .collect::<HashSet<_>>();
+
+    let mut pairs = Vec::with_capacity(order.len() / 2 + 1);
+
+    if order.len() % 2 == 1 {
+        // The lowest ranked entrant that hasn't had a bye yet sits the round out
+        let bye_index = order
+            .iter()
+            .rposition(|&entrant| standings[entrant].byes == 0)
+            .unwrap_or(order.len() - 1);
+        pairs.push((order.remove(bye_index), None));
+    }
+
+    while !order.is_empty() {
+        let blue = order.remove(0);
+        let opponent_index = order
+            .iter()
+            .position(|&orange| !played.contains(&(blue.min(orange), blue.max(orange))))
+            .unwrap_or(0);
+        pairs.push((blue, Some(order.remove(opponent_index))));
+    }
+
+    pairs
+}
+
+/// Get the pairings for the next round of a tournament, with `None` as the opponent for a bye
+///
+/// Returns an empty list if the tournament is over
+pub fn next_round(tournament: &Tournament) -> Vec<(usize, Option<usize>)> {
+    let round = tournament.rounds_played();
+
+    match tournament.format {
+        TournamentFormat::RoundRobin => round_robin(tournament, round),
+        TournamentFormat::SingleElimination => elimination(tournament, 1),
+        TournamentFormat::DoubleElimination => elimination(tournament, 2),
+        TournamentFormat::Swiss { rounds } => swiss(
+            tournament,
+            rounds.unwrap_or_else(|| TournamentFormat::default_swiss_rounds(tournament.entrants.len())),
+            round,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        settings::MiniMatchConfig,
+        tournament::{Entrant, TournamentMatch, TournamentResult},
+    };
+    use std::collections::HashMap;
+
+    fn tournament(format: TournamentFormat, entrants: usize) -> Tournament {
+        Tournament {
+            name: "Test".to_owned(),
+            format,
+            entrants: (0..entrants)
+                .map(|i| Entrant {
+                    name: format!("Bot {i}"),
+                    runnable_type: "rlbot".to_owned(),
+                    skill: 1.,
+                    path: None,
+                    supports_early_start: false,
+                    maximum_tick_rate_preference: None,
+                    bot_parameters: HashMap::new(),
+                })
+                .collect(),
+            match_settings: MiniMatchConfig::default(),
+            matches: Vec::new(),
+            finished: false,
+            created_at: 0,
+        }
+    }
+
+    /// Schedule the next round, with the lower seed winning every match 1-0
+    fn play_round(tournament: &mut Tournament) -> bool {
+        let round = tournament.rounds_played();
+        let pairs = next_round(tournament);
+        if pairs.is_empty() {
+            return false;
+        }
+
+        for (blue, orange) in pairs {
+            let result = TournamentResult {
+                blue_goals: usize::from(orange.map_or(true, |orange| blue < orange)),
+                orange_goals: usize::from(orange.map_or(false, |orange| orange < blue)),
+                winner: Some(orange.map_or(blue, |orange| blue.min(orange))),
+                record_id: None,
+            };
+
+            tournament.matches.push(TournamentMatch {
+                round,
+                blue,
+                orange,
+                result: Some(result),
+            });
+        }
+
+        true
+    }
+
+    #[test]
+    fn round_robin_plays_every_pair_once() {
+        for entrants in [4, 5] {
+            let mut tournament = tournament(TournamentFormat::RoundRobin, entrants);
+            while play_round(&mut tournament) {}
+
+            let mut pairs = tournament
+                .matches
+                .iter()
+                .filter_map(|game| game.orange.map(|orange| (game.blue.min(orange), game.blue.max(orange))))
+                .collect::<Vec<_>>();
+            let played = pairs.len();
+            pairs.sort_unstable();
+            pairs.dedup();
+
+            assert_eq!(played, entrants * (entrants - 1) / 2);
+            assert_eq!(pairs.len(), played);
+        }
+    }
+
+    #[test]
+    fn single_elimination_ends_with_one_winner() {
+        let mut tournament = tournament(TournamentFormat::SingleElimination, 5);
+        while play_round(&mut tournament) {}
+
+        let standings = standings(&tournament);
+        assert_eq!(standings[0].entrant, 0);
+        assert!(!standings[0].eliminated);
+        assert!(standings[1..].iter().all(|standing| standing.eliminated));
+    }
+
+    #[test]
+    fn odd_entrants_get_at_most_one_bye_each() {
+        let mut tournament = tournament(TournamentFormat::Swiss { rounds: None }, 5);
+        while play_round(&mut tournament) {}
+
+        assert_eq!(tournament.rounds_played(), TournamentFormat::default_swiss_rounds(5));
+        assert!(tally(&tournament).iter().all(|standing| standing.byes <= 1));
+    }
+
+    #[test]
+    fn swiss_avoids_rematches() {
+        let mut tournament = tournament(TournamentFormat::Swiss { rounds: Some(3) }, 4);
+        while play_round(&mut tournament) {}
+
+        let mut pairs = tournament
+            .matches
+            .iter()
+            .filter_map(|game| game.orange.map(|orange| (game.blue.min(orange), game.blue.max(orange))))
+            .collect::<Vec<_>>();
+        pairs.sort_unstable();
+        pairs.dedup();
+
+        assert_eq!(pairs.len(), tournament.matches.len());
+    }
+
+    #[test]
+    fn standings_rank_by_points() {
+        let mut tournament = tournament(TournamentFormat::RoundRobin, 3);
+        while play_round(&mut tournament) {}
+
+        let standings = standings(&tournament);
+        let order = standings.iter().map(|standing| standing.entrant).collect::<Vec<_>>();
+        assert_eq!(order, [0, 1, 2]);
+        assert!((standings[0].points - 2.).abs() < f32::EPSILON);
+    }
+}